@@ -7,7 +7,7 @@ use client::{
 };
 use dropset_interface::instructions::{
     BatchReplaceInstructionData,
-    UnvalidatedOrders,
+    BatchReplaceMode,
 };
 use itertools::Itertools;
 use price::client_helpers::to_order_info_args;
@@ -178,9 +178,10 @@ impl MakerContext {
                 self.maker_address,
                 BatchReplaceInstructionData::new(
                     self.latest_state.seat.index,
-                    UnvalidatedOrders::new([to_order_info_args(bid_price, ORDER_SIZE)?]),
-                    UnvalidatedOrders::new([to_order_info_args(ask_price, ORDER_SIZE)?]),
+                    BatchReplaceMode::Incremental as u8,
                 ),
+                &[to_order_info_args(bid_price, ORDER_SIZE)?.into()],
+                &[to_order_info_args(ask_price, ORDER_SIZE)?.into()],
             );
 
             Ok(vec![ixn])
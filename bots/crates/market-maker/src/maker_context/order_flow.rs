@@ -3,7 +3,9 @@ use std::collections::HashMap;
 use dropset_interface::{
     instructions::{
         CancelOrderInstructionData,
+        OrderExpiry,
         PostOrderInstructionData,
+        SelfTradeBehavior,
     },
     state::sector::SectorIndex,
 };
@@ -64,12 +66,28 @@ pub fn get_non_redundant_order_flow(
 
     let posts = unique_bid_posts
         .into_iter()
-        .map(|p| PostOrderInstructionData::new(p.clone(), true, maker_seat_index))
-        .chain(
-            unique_ask_posts
-                .into_iter()
-                .map(|p| PostOrderInstructionData::new(p.clone(), false, maker_seat_index)),
-        )
+        .map(|p| {
+            PostOrderInstructionData::new(
+                p.clone(),
+                true,
+                maker_seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            )
+        })
+        .chain(unique_ask_posts.into_iter().map(|p| {
+            PostOrderInstructionData::new(
+                p.clone(),
+                false,
+                maker_seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            )
+        }))
         .collect_vec();
 
     Ok((cancels, posts))
@@ -116,6 +134,8 @@ mod tests {
             user_seat: MAKER_SEAT_INDEX,
             base_remaining: info.base_atoms,
             quote_remaining: info.quote_atoms,
+            expiry: OrderExpiry::NONE,
+            client_order_id: 0,
         }
     }
 
@@ -217,8 +237,24 @@ mod tests {
         assert_eq!(
             posts,
             vec![
-                PostOrderInstructionData::new(p2.clone(), true, MAKER_SEAT_INDEX),
-                PostOrderInstructionData::new(p2, false, MAKER_SEAT_INDEX),
+                PostOrderInstructionData::new(
+                    p2.clone(),
+                    true,
+                    MAKER_SEAT_INDEX,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0
+                ),
+                PostOrderInstructionData::new(
+                    p2,
+                    false,
+                    MAKER_SEAT_INDEX,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0
+                ),
             ]
         );
     }
@@ -8,7 +8,7 @@ use client::{
 use dropset_interface::{
     instructions::{
         BatchReplaceInstructionData,
-        UnvalidatedOrders,
+        BatchReplaceMode,
     },
     state::sector::NIL,
 };
@@ -46,17 +46,15 @@ async fn main() -> anyhow::Result<()> {
         .market
         .batch_replace(
             trader.pubkey(),
-            BatchReplaceInstructionData::new(
-                seat.index,
-                UnvalidatedOrders::new([OrderInfoArgs::new_unscaled(11_000_000, 1)]),
-                UnvalidatedOrders::new([
-                    OrderInfoArgs::new_unscaled(12_000_000, 1),
-                    OrderInfoArgs::new_unscaled(13_000_000, 2),
-                    OrderInfoArgs::new_unscaled(14_000_000, 3),
-                    OrderInfoArgs::new_unscaled(15_000_000, 4),
-                    OrderInfoArgs::new_unscaled(16_000_000, 5),
-                ]),
-            ),
+            BatchReplaceInstructionData::new(seat.index, BatchReplaceMode::Replace as u8),
+            &[OrderInfoArgs::new_unscaled(11_000_000, 1).into()],
+            &[
+                OrderInfoArgs::new_unscaled(12_000_000, 1).into(),
+                OrderInfoArgs::new_unscaled(13_000_000, 2).into(),
+                OrderInfoArgs::new_unscaled(14_000_000, 3).into(),
+                OrderInfoArgs::new_unscaled(15_000_000, 4).into(),
+                OrderInfoArgs::new_unscaled(16_000_000, 5).into(),
+            ],
         )
         .send_single_signer(&e2e.rpc, &trader)
         .await?;
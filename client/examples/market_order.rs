@@ -14,8 +14,9 @@ use client::{
 use dropset_interface::{
     events::MarketOrderEventInstructionData,
     instructions::{
-        MarketOrderInstructionData,
+        OrderExpiry,
         PostOrderInstructionData,
+        SelfTradeBehavior,
     },
     state::sector::NIL,
 };
@@ -180,16 +181,14 @@ async fn run_partial_fill(
     let taker_is_market_buy = matches!(ctx.maker_side, BookSide::Ask);
 
     e2e.market
-        .market_order(
+        .unconstrained_market_order(
             ctx.taker.pubkey(),
-            MarketOrderInstructionData::new(
-                match denomination {
-                    Denomination::Base => ctx.taker_size_base()?,
-                    Denomination::Quote => ctx.taker_size_quote()?,
-                },
-                taker_is_market_buy,
-                denomination.is_base(),
-            ),
+            match denomination {
+                Denomination::Base => ctx.taker_size_base()?,
+                Denomination::Quote => ctx.taker_size_quote()?,
+            },
+            taker_is_market_buy,
+            denomination.is_base(),
         )
         .send_single_signer(&e2e.rpc, ctx.taker)
         .await
@@ -225,6 +224,10 @@ async fn post_maker_order(
                 ctx.order_info_args.clone(),
                 matches!(ctx.maker_side, BookSide::Bid),
                 maker_seat.index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
             ),
         )
         .send_single_signer(&e2e.rpc, ctx.maker)
@@ -424,6 +427,20 @@ async fn main() -> anyhow::Result<()> {
         assert_eq!(event_1.quote_filled, event_2.quote_filled);
         assert_eq!(event_1.base_filled, taker_base_size);
         assert_eq!(event_1.quote_filled, taker_quote_size);
+
+        // Ensure that the per-maker fill events sum to the aggregate market order fill.
+        for (txn, event) in [(&fill_1, &event_1), (&fill_2, &event_2)] {
+            let (base_filled, quote_filled) = txn
+                .events
+                .iter()
+                .filter_map(|ev| match ev {
+                    DropsetEvent::Fill(f) => Some((f.base_filled, f.quote_filled)),
+                    _ => None,
+                })
+                .fold((0, 0), |(b, q), (fb, fq)| (b + fb, q + fq));
+            assert_eq!(base_filled, event.base_filled);
+            assert_eq!(quote_filled, event.quote_filled);
+        }
     }
 
     Ok(())
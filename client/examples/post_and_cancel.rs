@@ -15,7 +15,9 @@ use client::{
 use dropset_interface::{
     instructions::{
         CancelOrderInstructionData,
+        OrderExpiry,
         PostOrderInstructionData,
+        SelfTradeBehavior,
     },
     state::sector::NIL,
 };
@@ -61,7 +63,15 @@ async fn main() -> anyhow::Result<()> {
         .market
         .post_order(
             trader.pubkey(),
-            PostOrderInstructionData::new(order_info_args, is_bid, user_seat.index),
+            PostOrderInstructionData::new(
+                order_info_args,
+                is_bid,
+                user_seat.index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
         )
         .send_single_signer(&e2e.rpc, trader)
         .await?;
@@ -13,7 +13,11 @@ use client::{
     },
 };
 use dropset_interface::{
-    instructions::PostOrderInstructionData,
+    instructions::{
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
     state::sector::NIL,
 };
 use itertools::Itertools;
@@ -54,7 +58,15 @@ async fn main() -> anyhow::Result<()> {
         .market
         .post_order(
             trader.pubkey(),
-            PostOrderInstructionData::new(order_info_args.clone(), is_bid, user_seat.index),
+            PostOrderInstructionData::new(
+                order_info_args.clone(),
+                is_bid,
+                user_seat.index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
         )
         .send_single_signer(&e2e.rpc, trader)
         .await?;
@@ -88,6 +100,10 @@ async fn main() -> anyhow::Result<()> {
                     ),
                     is_bid,
                     user_seat.index,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0,
                 ),
             )
         })
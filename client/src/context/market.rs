@@ -3,24 +3,51 @@
 use dropset_interface::{
     instructions::{
         generated_client::*,
+        AmendOrderInstructionData,
         BatchReplaceInstructionData,
+        CancelAllOrdersInstructionData,
+        CancelOrderByClientIdInstructionData,
         CancelOrderInstructionData,
+        CloseEmptySeatsInstructionData,
+        CloseMarketInstructionData,
         CloseSeatInstructionData,
+        CollectFeesInstructionData,
+        CompactMarketInstructionData,
+        DepositBothInstructionData,
+        DepositForInstructionData,
         DepositInstructionData,
         ExpandMarketInstructionData,
         MarketOrderInstructionData,
         PostOrderInstructionData,
+        PruneExpiredOrdersInstructionData,
         RegisterMarketInstructionData,
+        SeatMarketOrderInstructionData,
+        SelfTradeBehavior,
+        SetMarketStatusInstructionData,
+        SetSeatDelegateInstructionData,
+        SettleAndCloseSeatInstructionData,
+        SideFilter,
+        TimeInForce,
+        UnvalidatedOrder,
+        UnvalidatedOrders,
+        UpdateMarketConfigInstructionData,
+        WithdrawBothInstructionData,
         WithdrawInstructionData,
     },
     seeds::event_authority,
     state::{
+        market_status::MarketStatus,
         sector::NIL,
+        user_order_sectors::MAX_ORDERS,
         SYSTEM_PROGRAM_ID,
     },
 };
+use price::EncodedPrice;
 use solana_address::Address;
-use solana_instruction::Instruction;
+use solana_instruction::{
+    AccountMeta,
+    Instruction,
+};
 use transaction_parser::views::MarketSeatView;
 
 use crate::{
@@ -34,6 +61,7 @@ use crate::{
 /// Implements helper methods for building all program instructions using those values.
 pub struct MarketContext {
     pub market: Address,
+    pub market_id: u16,
     pub base: TokenContext,
     pub quote: TokenContext,
     pub base_market_ata: Address,
@@ -59,15 +87,23 @@ impl Denomination {
 }
 
 impl MarketContext {
-    /// Creates a new [`MarketContext`] by deriving the market PDA and ATAs from the given token
-    /// contexts.
+    /// Creates a new [`MarketContext`] for the market with ID `0` by deriving the market PDA and
+    /// ATAs from the given token contexts.
     pub fn new(base: TokenContext, quote: TokenContext) -> Self {
-        let (market, _bump) = find_market_address(&base.mint_address, &quote.mint_address);
+        Self::new_with_market_id(base, quote, 0)
+    }
+
+    /// Creates a new [`MarketContext`] by deriving the market PDA and ATAs from the given token
+    /// contexts and market ID.
+    pub fn new_with_market_id(base: TokenContext, quote: TokenContext, market_id: u16) -> Self {
+        let (market, _bump) =
+            find_market_address(&base.mint_address, &quote.mint_address, market_id);
         let base_market_ata = base.get_ata_for(&market);
         let quote_market_ata = quote.get_ata_for(&market);
 
         Self {
             market,
+            market_id,
             base,
             quote,
             base_market_ata,
@@ -120,7 +156,41 @@ impl MarketContext {
         )
     }
 
+    /// Registers the market with no fees, the payer as the fee recipient and market authority, and
+    /// the least restrictive market config.
     pub fn register_market(&self, payer: Address, num_sectors: u16) -> Instruction {
+        self.register_market_with_fees(payer, num_sectors, payer, 0, 0)
+    }
+
+    /// Registers the market with the passed fee schedule, the payer as the market authority, and
+    /// the least restrictive market config.
+    pub fn register_market_with_fees(
+        &self,
+        payer: Address,
+        num_sectors: u16,
+        fee_recipient: Address,
+        taker_fee_bps: u16,
+        maker_rebate_bps: u16,
+    ) -> Instruction {
+        let data = RegisterMarketInstructionData::new(
+            num_sectors,
+            self.market_id,
+            fee_recipient,
+            taker_fee_bps,
+            maker_rebate_bps,
+            payer,
+            0,
+            1,
+            MAX_ORDERS,
+        );
+        self.register_market_with_data(payer, data)
+    }
+
+    pub fn register_market_with_data(
+        &self,
+        payer: Address,
+        data: RegisterMarketInstructionData,
+    ) -> Instruction {
         RegisterMarket {
             event_authority: event_authority::ID,
             user: payer,
@@ -135,7 +205,67 @@ impl MarketContext {
             system_program: SYSTEM_PROGRAM_ID,
             dropset_program: dropset::ID,
         }
-        .create_instruction(RegisterMarketInstructionData::new(num_sectors))
+        .create_instruction(data)
+    }
+
+    pub fn update_market_config(
+        &self,
+        authority: Address,
+        data: UpdateMarketConfigInstructionData,
+    ) -> Instruction {
+        UpdateMarketConfig {
+            event_authority: event_authority::ID,
+            authority,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(data)
+    }
+
+    pub fn set_market_status(&self, authority: Address, status: MarketStatus) -> Instruction {
+        SetMarketStatus {
+            event_authority: event_authority::ID,
+            authority,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(SetMarketStatusInstructionData::new(status as u8))
+    }
+
+    pub fn close_market(&self, authority: Address) -> Instruction {
+        CloseMarket {
+            event_authority: event_authority::ID,
+            authority,
+            market_account: self.market,
+            base_authority_ata: self.get_base_ata(&authority),
+            quote_authority_ata: self.get_quote_ata(&authority),
+            base_market_ata: self.base_market_ata,
+            quote_market_ata: self.quote_market_ata,
+            base_mint: self.base.mint_address,
+            quote_mint: self.quote.mint_address,
+            base_token_program: self.base.token_program,
+            quote_token_program: self.quote.token_program,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(CloseMarketInstructionData::new())
+    }
+
+    pub fn collect_fees(&self, fee_recipient: Address) -> Instruction {
+        CollectFees {
+            event_authority: event_authority::ID,
+            fee_recipient,
+            market_account: self.market,
+            base_recipient_ata: self.get_base_ata(&fee_recipient),
+            quote_recipient_ata: self.get_quote_ata(&fee_recipient),
+            base_market_ata: self.base_market_ata,
+            quote_market_ata: self.quote_market_ata,
+            base_mint: self.base.mint_address,
+            quote_mint: self.quote.mint_address,
+            base_token_program: self.base.token_program,
+            quote_token_program: self.quote.token_program,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(CollectFeesInstructionData::new())
     }
 
     pub fn find_seat(&self, seats: &[MarketSeatView], user: &Address) -> Option<MarketSeatView> {
@@ -160,6 +290,78 @@ impl MarketContext {
         .create_instruction(CloseSeatInstructionData::new(sector_index_hint))
     }
 
+    /// Permissionlessly closes up to `max_seats` seats with no balances, no open orders and no
+    /// delegate.
+    pub fn close_empty_seats(&self, max_seats: u16) -> Instruction {
+        CloseEmptySeats {
+            event_authority: event_authority::ID,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(CloseEmptySeatsInstructionData::new(max_seats))
+    }
+
+    /// Closes `user`'s seat on behalf of `closer`, transferring its remaining balances to the
+    /// user's associated token accounts. The seat must not have any open orders, and `closer`
+    /// must be the seat's owner or the market authority unless the seat is abandoned.
+    pub fn settle_and_close_seat(
+        &self,
+        closer: Address,
+        user: Address,
+        sector_index_hint: u32,
+    ) -> Instruction {
+        SettleAndCloseSeat {
+            event_authority: event_authority::ID,
+            closer,
+            user,
+            market_account: self.market,
+            base_user_ata: self.get_base_ata(&user),
+            quote_user_ata: self.get_quote_ata(&user),
+            base_market_ata: self.base_market_ata,
+            quote_market_ata: self.quote_market_ata,
+            base_mint: self.base.mint_address,
+            quote_mint: self.quote.mint_address,
+            base_token_program: self.base.token_program,
+            quote_token_program: self.quote.token_program,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(SettleAndCloseSeatInstructionData::new(sector_index_hint))
+    }
+
+    /// Compacts the market account down to its live sectors plus `num_free_sectors` free sectors,
+    /// refunding the excess rent to the market `authority`.
+    ///
+    /// Seats and orders may be relocated, so any seat index hints (e.g. from [`Self::find_seat`])
+    /// fetched before compacting must be fetched again afterwards.
+    pub fn compact_market(&self, authority: Address, num_free_sectors: u32) -> Instruction {
+        CompactMarket {
+            event_authority: event_authority::ID,
+            authority,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(CompactMarketInstructionData::new(num_free_sectors))
+    }
+
+    /// Sets the delegate on the user's seat. Passing `None` removes the current delegate.
+    pub fn set_seat_delegate(
+        &self,
+        user: Address,
+        sector_index_hint: u32,
+        delegate: Option<Address>,
+    ) -> Instruction {
+        SetSeatDelegate {
+            event_authority: event_authority::ID,
+            user,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(SetSeatDelegateInstructionData::new(
+            sector_index_hint,
+            delegate.unwrap_or_default(),
+        ))
+    }
+
     pub fn deposit_base(&self, user: Address, amount: u64, sector_index_hint: u32) -> Instruction {
         let data = DepositInstructionData::new(amount, sector_index_hint);
         self.deposit(user, data, true)
@@ -170,6 +372,44 @@ impl MarketContext {
         self.deposit(user, data, false)
     }
 
+    /// Deposits base with `payer` as the optional expansion payer, which funds a market expansion
+    /// instead of the user if registering the seat requires one.
+    pub fn deposit_base_with_expansion_payer(
+        &self,
+        user: Address,
+        payer: Address,
+        amount: u64,
+        sector_index_hint: u32,
+    ) -> Instruction {
+        let mut instruction = self.deposit_base(user, amount, sector_index_hint);
+        instruction.accounts.push(AccountMeta::new(payer, true));
+        instruction
+    }
+
+    /// Deposits base from `funder`'s token account into `beneficiary`'s seat.
+    pub fn deposit_base_for(
+        &self,
+        funder: Address,
+        beneficiary: Address,
+        amount: u64,
+        sector_index_hint: u32,
+    ) -> Instruction {
+        let data = DepositForInstructionData::new(amount, sector_index_hint, beneficiary);
+        self.deposit_for(funder, data, true)
+    }
+
+    /// Deposits quote from `funder`'s token account into `beneficiary`'s seat.
+    pub fn deposit_quote_for(
+        &self,
+        funder: Address,
+        beneficiary: Address,
+        amount: u64,
+        sector_index_hint: u32,
+    ) -> Instruction {
+        let data = DepositForInstructionData::new(amount, sector_index_hint, beneficiary);
+        self.deposit_for(funder, data, false)
+    }
+
     pub fn withdraw_base(&self, user: Address, amount: u64, sector_index_hint: u32) -> Instruction {
         let data = WithdrawInstructionData::new(amount, sector_index_hint);
         self.withdraw(user, data, true)
@@ -185,6 +425,75 @@ impl MarketContext {
         self.withdraw(user, data, false)
     }
 
+    /// Deposits both base and quote in a single instruction. A zero amount skips that token.
+    pub fn deposit_both(
+        &self,
+        user: Address,
+        base_amount: u64,
+        quote_amount: u64,
+        sector_index_hint: u32,
+    ) -> Instruction {
+        DepositBoth {
+            event_authority: event_authority::ID,
+            user,
+            market_account: self.market,
+            base_user_ata: self.get_base_ata(&user),
+            quote_user_ata: self.get_quote_ata(&user),
+            base_market_ata: self.base_market_ata,
+            quote_market_ata: self.quote_market_ata,
+            base_mint: self.base.mint_address,
+            quote_mint: self.quote.mint_address,
+            base_token_program: self.base.token_program,
+            quote_token_program: self.quote.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(DepositBothInstructionData::new(
+            base_amount,
+            quote_amount,
+            sector_index_hint,
+        ))
+    }
+
+    /// Withdraws both base and quote in a single instruction. A zero amount skips that token.
+    pub fn withdraw_both(
+        &self,
+        user: Address,
+        base_amount: u64,
+        quote_amount: u64,
+        sector_index_hint: u32,
+    ) -> Instruction {
+        WithdrawBoth {
+            event_authority: event_authority::ID,
+            user,
+            market_account: self.market,
+            base_user_ata: self.get_base_ata(&user),
+            quote_user_ata: self.get_quote_ata(&user),
+            base_market_ata: self.base_market_ata,
+            quote_market_ata: self.quote_market_ata,
+            base_mint: self.base.mint_address,
+            quote_mint: self.quote.mint_address,
+            base_token_program: self.base.token_program,
+            quote_token_program: self.quote.token_program,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(WithdrawBothInstructionData::new(
+            base_amount,
+            quote_amount,
+            sector_index_hint,
+        ))
+    }
+
+    /// Withdraws the seat's entire available base and quote balances in a single instruction.
+    pub fn withdraw_all(&self, seat: &MarketSeatView) -> Instruction {
+        self.withdraw_both(
+            seat.user,
+            seat.base_available,
+            seat.quote_available,
+            seat.index,
+        )
+    }
+
     pub fn post_order(&self, user: Address, data: PostOrderInstructionData) -> Instruction {
         PostOrder {
             event_authority: event_authority::ID,
@@ -195,6 +504,22 @@ impl MarketContext {
         .create_instruction(data)
     }
 
+    /// Posts an order with `payer` as the optional expansion payer, which funds a market expansion
+    /// if the market has no free sector for the posted order.
+    pub fn post_order_with_expansion_payer(
+        &self,
+        user: Address,
+        payer: Address,
+        data: PostOrderInstructionData,
+    ) -> Instruction {
+        let mut instruction = self.post_order(user, data);
+        instruction.accounts.extend([
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ]);
+        instruction
+    }
+
     pub fn cancel_order(&self, user: Address, data: CancelOrderInstructionData) -> Instruction {
         CancelOrder {
             event_authority: event_authority::ID,
@@ -205,6 +530,61 @@ impl MarketContext {
         .create_instruction(data)
     }
 
+    pub fn cancel_order_by_client_id(
+        &self,
+        user: Address,
+        data: CancelOrderByClientIdInstructionData,
+    ) -> Instruction {
+        CancelOrderByClientId {
+            event_authority: event_authority::ID,
+            user,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(data)
+    }
+
+    /// Amends the price and/or size of the user's order at `data.encoded_price`. Size reductions at
+    /// the same price keep the order's time priority.
+    pub fn amend_order(&self, user: Address, data: AmendOrderInstructionData) -> Instruction {
+        AmendOrder {
+            event_authority: event_authority::ID,
+            user,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(data)
+    }
+
+    /// Cancels all of the user's orders on the side(s) of the book specified by `side_filter`.
+    pub fn cancel_all_orders(
+        &self,
+        user: Address,
+        sector_index_hint: u32,
+        side_filter: SideFilter,
+    ) -> Instruction {
+        CancelAllOrders {
+            event_authority: event_authority::ID,
+            user,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(CancelAllOrdersInstructionData::new(
+            sector_index_hint,
+            side_filter as u8,
+        ))
+    }
+
+    /// Removes up to `max_orders` expired orders from the book. Anyone can send this instruction.
+    pub fn prune_expired_orders(&self, max_orders: u16) -> Instruction {
+        PruneExpiredOrders {
+            event_authority: event_authority::ID,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(PruneExpiredOrdersInstructionData::new(max_orders))
+    }
+
     pub fn market_order(&self, user: Address, data: MarketOrderInstructionData) -> Instruction {
         MarketOrder {
             event_authority: event_authority::ID,
@@ -223,8 +603,42 @@ impl MarketContext {
         .create_instruction(data)
     }
 
-    pub fn batch_replace(&self, user: Address, data: BatchReplaceInstructionData) -> Instruction {
-        BatchReplace {
+    /// Creates an immediate-or-cancel market order with no limit price and no minimum amount out;
+    /// i.e., the order fills against the book until either the order size is exhausted or the book
+    /// is empty.
+    pub fn unconstrained_market_order(
+        &self,
+        user: Address,
+        order_size: u64,
+        is_buy: bool,
+        is_base: bool,
+    ) -> Instruction {
+        let limit_price = match is_buy {
+            true => EncodedPrice::infinity(),
+            false => EncodedPrice::zero(),
+        };
+        self.market_order(
+            user,
+            MarketOrderInstructionData::new(
+                order_size,
+                is_buy,
+                is_base,
+                limit_price.as_u32(),
+                0,
+                TimeInForce::ImmediateOrCancel as u8,
+                SelfTradeBehavior::Abort as u8,
+            ),
+        )
+    }
+
+    /// Creates a market order that's settled against the user's seat balances instead of their
+    /// token accounts.
+    pub fn seat_market_order(
+        &self,
+        user: Address,
+        data: SeatMarketOrderInstructionData,
+    ) -> Instruction {
+        SeatMarketOrder {
             event_authority: event_authority::ID,
             user,
             market_account: self.market,
@@ -233,6 +647,56 @@ impl MarketContext {
         .create_instruction(data)
     }
 
+    /// Creates an immediate-or-cancel seat market order with no limit price and no minimum amount
+    /// out. See [`Self::unconstrained_market_order`].
+    pub fn unconstrained_seat_market_order(
+        &self,
+        user: Address,
+        order_size: u64,
+        is_buy: bool,
+        is_base: bool,
+        sector_index_hint: u32,
+    ) -> Instruction {
+        let limit_price = match is_buy {
+            true => EncodedPrice::infinity(),
+            false => EncodedPrice::zero(),
+        };
+        self.seat_market_order(
+            user,
+            SeatMarketOrderInstructionData::new(
+                order_size,
+                is_buy,
+                is_base,
+                limit_price.as_u32(),
+                0,
+                TimeInForce::ImmediateOrCancel as u8,
+                SelfTradeBehavior::Abort as u8,
+                sector_index_hint,
+            ),
+        )
+    }
+
+    /// Creates a batch replace instruction, appending `new_bids` and `new_asks` to the instruction
+    /// data as length-prefixed order lists.
+    pub fn batch_replace(
+        &self,
+        user: Address,
+        data: BatchReplaceInstructionData,
+        new_bids: &[UnvalidatedOrder],
+        new_asks: &[UnvalidatedOrder],
+    ) -> Instruction {
+        let mut instruction = BatchReplace {
+            event_authority: event_authority::ID,
+            user,
+            market_account: self.market,
+            dropset_program: dropset::ID,
+        }
+        .create_instruction(data);
+        UnvalidatedOrders::pack_length_prefixed(new_bids, &mut instruction.data);
+        UnvalidatedOrders::pack_length_prefixed(new_asks, &mut instruction.data);
+        instruction
+    }
+
     fn deposit(&self, user: Address, data: DepositInstructionData, is_base: bool) -> Instruction {
         match is_base {
             true => Deposit {
@@ -261,6 +725,39 @@ impl MarketContext {
         .create_instruction(data)
     }
 
+    fn deposit_for(
+        &self,
+        funder: Address,
+        data: DepositForInstructionData,
+        is_base: bool,
+    ) -> Instruction {
+        match is_base {
+            true => DepositFor {
+                event_authority: event_authority::ID,
+                funder,
+                market_account: self.market,
+                funder_ata: self.get_base_ata(&funder),
+                market_ata: self.base_market_ata,
+                mint: self.base.mint_address,
+                token_program: self.base.token_program,
+                system_program: SYSTEM_PROGRAM_ID,
+                dropset_program: dropset::ID,
+            },
+            false => DepositFor {
+                event_authority: event_authority::ID,
+                funder,
+                market_account: self.market,
+                funder_ata: self.get_quote_ata(&funder),
+                market_ata: self.quote_market_ata,
+                mint: self.quote.mint_address,
+                token_program: self.quote.token_program,
+                system_program: SYSTEM_PROGRAM_ID,
+                dropset_program: dropset::ID,
+            },
+        }
+        .create_instruction(data)
+    }
+
     fn withdraw(&self, user: Address, data: WithdrawInstructionData, is_base: bool) -> Instruction {
         match is_base {
             true => Withdraw {
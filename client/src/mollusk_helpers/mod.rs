@@ -96,11 +96,12 @@ pub const MOLLUSK_DEFAULT_QUOTE_TOKEN: TokenContext = TokenContext::new(
 );
 
 pub const MOLLUSK_DEFAULT_MARKET: MarketContext = MarketContext {
-    market: pubkey!("7iHzqGHqCpmaEhFXbpoGnceWv7zYveUXyUdJYvYYyM1Q"),
+    market: pubkey!("8eYSL68X7xebZu2tTLV7tf4rLyECoaMc8EHEvxwP5kRR"),
+    market_id: 0,
     base: MOLLUSK_DEFAULT_BASE_TOKEN,
     quote: MOLLUSK_DEFAULT_QUOTE_TOKEN,
-    base_market_ata: pubkey!("4n7H8mBnXnKeZh8be3u7SCFygen7pRBgF9H3NP37VtAV"),
-    quote_market_ata: pubkey!("CyoUPgiQGzUB1e8SqgrMKiF5gkoezSiw4yB4x2ya5kAu"),
+    base_market_ata: pubkey!("5eBpgbQcVYa7GCZySnNo2HvRcVCFUKzD3sqFnB4BjCKH"),
+    quote_market_ata: pubkey!("7RE7k81fxNmyYYKxBbSDYWgm7ekWTZ6KGYpGvP9uvGH8"),
 };
 
 /// Creates and returns a [MolluskContext] with `dropset` and all token programs created and
@@ -110,6 +111,22 @@ pub const MOLLUSK_DEFAULT_MARKET: MarketContext = MarketContext {
 /// the default market.
 pub fn new_dropset_mollusk_context_with_default_market(
     accounts: &[(Address, Account)],
+) -> (MolluskContext<HashMap<Address, Account>>, MarketContext) {
+    new_dropset_mollusk_context_with_default_market_and_fees(
+        accounts,
+        MOLLUSK_DEFAULT_MINT_AUTHORITY,
+        0,
+        0,
+    )
+}
+
+/// The same as [`new_dropset_mollusk_context_with_default_market`] but registers the default
+/// market with the passed fee schedule.
+pub fn new_dropset_mollusk_context_with_default_market_and_fees(
+    accounts: &[(Address, Account)],
+    fee_recipient: Address,
+    taker_fee_bps: u16,
+    maker_rebate_bps: u16,
 ) -> (MolluskContext<HashMap<Address, Account>>, MarketContext) {
     let mint_authority_addr_and_account =
         create_mock_user_account(MOLLUSK_DEFAULT_MINT_AUTHORITY, 100_000_000_000);
@@ -123,8 +140,13 @@ pub fn new_dropset_mollusk_context_with_default_market(
         )
         .expect("Should create token instructions");
 
-    let register_market = MOLLUSK_DEFAULT_MARKET
-        .register_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, MOLLUSK_DEFAULT_NUM_SECTORS);
+    let register_market = MOLLUSK_DEFAULT_MARKET.register_market_with_fees(
+        MOLLUSK_DEFAULT_MINT_AUTHORITY,
+        MOLLUSK_DEFAULT_NUM_SECTORS,
+        fee_recipient,
+        taker_fee_bps,
+        maker_rebate_bps,
+    );
 
     assert!(res
         .process_instruction_chain(&[create_tokens, vec![register_market]].concat())
@@ -139,7 +161,9 @@ mod tests {
     use anyhow::anyhow;
     use dropset_interface::state::{
         market_header::MARKET_ACCOUNT_DISCRIMINANT,
+        market_status::MarketStatus,
         sector::NIL,
+        user_order_sectors::MAX_ORDERS,
     };
     use spl_associated_token_account_interface::address::get_associated_token_address;
     use transaction_parser::views::{
@@ -192,6 +216,7 @@ mod tests {
         let (derived_market, bump) = find_market_address(
             &MOLLUSK_DEFAULT_BASE_TOKEN.mint_address,
             &MOLLUSK_DEFAULT_QUOTE_TOKEN.mint_address,
+            MOLLUSK_DEFAULT_MARKET.market_id,
         );
         let (ctx, market) = new_dropset_mollusk_context_with_default_market(&[]);
         assert_eq!(market.market, derived_market);
@@ -229,8 +254,19 @@ mod tests {
                 base_mint: MOLLUSK_DEFAULT_BASE_TOKEN.mint_address,
                 quote_mint: MOLLUSK_DEFAULT_QUOTE_TOKEN.mint_address,
                 market_bump: bump,
+                market_id: 0,
                 nonce: 1, // The register market event.
-                _padding: [0, 0, 0],
+                fee_recipient: MOLLUSK_DEFAULT_MINT_AUTHORITY,
+                taker_fee_bps: 0,
+                maker_rebate_bps: 0,
+                base_fees_accrued: 0,
+                quote_fees_accrued: 0,
+                authority: MOLLUSK_DEFAULT_MINT_AUTHORITY,
+                min_order_base_atoms: 0,
+                price_mantissa_tick: 1,
+                max_orders_per_user: MAX_ORDERS,
+                status: MarketStatus::Active,
+                _padding: [0; 7],
             }
         );
 
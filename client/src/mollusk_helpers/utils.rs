@@ -1,6 +1,7 @@
 use dropset_interface::state::SYSTEM_PROGRAM_ID;
 use solana_account::Account;
 use solana_address::Address;
+use solana_instruction::Instruction;
 
 /// Create the data necessary to send to [mollusk_svm::MolluskContext] to mock a funded account.
 pub fn create_mock_user_account(address: Address, lamport_balance: u64) -> (Address, Account) {
@@ -15,3 +16,14 @@ pub fn create_mock_user_account(address: Address, lamport_balance: u64) -> (Addr
         },
     )
 }
+
+/// Clears the signer flag on every account meta for `address`, e.g. to verify that an instruction
+/// is rejected when an account it requires to sign doesn't.
+pub fn without_signer(mut instruction: Instruction, address: &Address) -> Instruction {
+    instruction
+        .accounts
+        .iter_mut()
+        .filter(|meta| meta.pubkey == *address)
+        .for_each(|meta| meta.is_signer = false);
+    instruction
+}
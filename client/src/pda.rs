@@ -2,11 +2,16 @@
 
 use solana_address::Address;
 
-pub fn find_market_address(base_mint: &Address, quote_mint: &Address) -> (Address, u8) {
+pub fn find_market_address(
+    base_mint: &Address,
+    quote_mint: &Address,
+    market_id: u16,
+) -> (Address, u8) {
     Address::find_program_address(
         &[
             base_mint.as_ref(),
             quote_mint.as_ref(),
+            &market_id.to_le_bytes(),
             dropset_interface::seeds::market::MARKET_SEED_STR,
         ],
         &dropset::ID,
@@ -0,0 +1,134 @@
+use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
+    helper_trait::DropsetTestHelper,
+    market_checker::MarketChecker,
+    new_dropset_mollusk_context_with_default_market,
+    utils::create_mock_user_account,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        AmendOrderInstructionData,
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
+    state::sector::NIL,
+};
+use mollusk_svm::result::Check;
+use price::{
+    client_helpers::sum_quote_necessary,
+    to_order_info,
+    OrderInfoArgs,
+};
+use solana_address::Address;
+
+/// Verifies that reducing an order's size at the same price keeps its time priority, and that
+/// changing its price reposts it at the new price with the same client order id.
+#[test]
+fn amend_order_keeps_priority_on_size_reduction() -> anyhow::Result<()> {
+    let user_1_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user_2_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let (user_1, user_2) = (user_1_mock.0, user_2_mock.0);
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[user_1_mock, user_2_mock]);
+
+    let original = OrderInfoArgs::new_unscaled(10_000_000, 2);
+    let reduced = OrderInfoArgs::new_unscaled(10_000_000, 1);
+    let repriced = OrderInfoArgs::new_unscaled(11_000_000, 1);
+    let quote_deposited = sum_quote_necessary(&[OrderInfoArgs::new_unscaled(11_000_000, 2)])?;
+
+    for user in [user_1, user_2] {
+        assert!(mollusk
+            .process_instruction_chain(&[
+                market_ctx.quote.create_ata_idempotent(&user, &user),
+                market_ctx.quote.mint_to_owner(&user, quote_deposited)?,
+                market_ctx.deposit_quote(user, quote_deposited, NIL),
+            ])
+            .program_result
+            .is_ok());
+    }
+
+    let seat_1 = mollusk.get_seat(market_ctx.market, user_1).index;
+    let seat_2 = mollusk.get_seat(market_ctx.market, user_2).index;
+
+    let post_bid = |user: Address, seat: u32, client_order_id: u64| {
+        market_ctx.post_order(
+            user,
+            PostOrderInstructionData::new(
+                original.clone(),
+                true,
+                seat,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                client_order_id,
+            ),
+        )
+    };
+
+    // Both users post a bid at the same price, with user 1's bid first in the queue.
+    assert!(mollusk
+        .process_instruction_chain(&[post_bid(user_1, seat_1, 7), post_bid(user_2, seat_2, 8)])
+        .program_result
+        .is_ok());
+
+    let encoded_price = |args: &OrderInfoArgs| {
+        to_order_info(args.clone())
+            .expect("Should be a valid order")
+            .encoded_price
+            .as_u32()
+    };
+    let amend = |price: u32, args: &OrderInfoArgs| {
+        market_ctx.amend_order(
+            user_1,
+            AmendOrderInstructionData::new(price, true, seat_1, args.clone()),
+        )
+    };
+
+    // Reduce user 1's bid at the same price.
+    mollusk.process_and_validate_instruction_chain(&[(
+        &amend(encoded_price(&original), &reduced),
+        &[Check::success()],
+    )]);
+
+    let reduced_info = to_order_info(reduced.clone()).expect("Should be a valid order");
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(2);
+    check.bids(|bids| {
+        assert_eq!(bids[0].user_seat, seat_1);
+        assert_eq!(bids[0].base_remaining, reduced_info.base_atoms);
+        assert_eq!(bids[0].client_order_id, 7);
+        assert_eq!(bids[1].user_seat, seat_2);
+    });
+    check.seat_quote_available(user_1, quote_deposited - reduced_info.quote_atoms);
+
+    mollusk.process_and_validate_instruction_chain(&[
+        // User 1 has no bid at the repriced order's price yet.
+        (
+            &amend(encoded_price(&repriced), &repriced),
+            &[DropsetError::OrderNotFound.into_check_failure()],
+        ),
+        // Reprice user 1's bid, which moves it to the new price level.
+        (
+            &amend(encoded_price(&reduced), &repriced),
+            &[Check::success()],
+        ),
+    ]);
+
+    let repriced_info = to_order_info(repriced.clone()).expect("Should be a valid order");
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(2);
+    check.bids(|bids| {
+        assert_eq!(bids[0].user_seat, seat_1);
+        assert_eq!(bids[0].encoded_price, repriced_info.encoded_price);
+        assert_eq!(bids[0].client_order_id, 7);
+        assert_eq!(bids[1].user_seat, seat_2);
+    });
+    check.seat_quote_available(user_1, quote_deposited - repriced_info.quote_atoms);
+    check.seat(user_1, |seat| {
+        assert_eq!(seat.user_order_sectors.bids.len(), 1);
+    });
+
+    Ok(())
+}
@@ -8,11 +8,18 @@ use dropset_interface::{
     error::DropsetError,
     instructions::{
         BatchReplaceInstructionData,
-        UnvalidatedOrders,
+        BatchReplaceMode,
+        UnvalidatedOrder,
     },
-    state::sector::{
-        MAX_PERMITTED_SECTOR_INCREASE,
-        NIL,
+    state::{
+        sector::{
+            MAX_PERMITTED_SECTOR_INCREASE,
+            NIL,
+        },
+        user_order_sectors::{
+            MAX_ORDERS,
+            MAX_ORDERS_USIZE,
+        },
     },
 };
 use itertools::Itertools;
@@ -71,11 +78,9 @@ fn batch_replace_add_orders_happy_path() -> anyhow::Result<()> {
     assert!(mollusk
         .process_instruction_chain(&[market_ctx.batch_replace(
             user,
-            BatchReplaceInstructionData::new(
-                seat_index,
-                UnvalidatedOrders::new(bid_order_args.clone()),
-                UnvalidatedOrders::new(ask_order_args.clone()),
-            ),
+            BatchReplaceInstructionData::new(seat_index, BatchReplaceMode::Replace as u8),
+            &bid_order_args.clone().map(UnvalidatedOrder::from),
+            &ask_order_args.clone().map(UnvalidatedOrder::from),
         )])
         .program_result
         .is_ok());
@@ -163,11 +168,9 @@ fn batch_replace_interleaved_bids_are_sorted() -> anyhow::Result<()> {
     assert!(mollusk
         .process_instruction_chain(&[market_ctx.batch_replace(
             maker_a,
-            BatchReplaceInstructionData::new(
-                maker_a_seat,
-                UnvalidatedOrders::new(maker_a_bids),
-                UnvalidatedOrders::new([]),
-            ),
+            BatchReplaceInstructionData::new(maker_a_seat, BatchReplaceMode::Replace as u8),
+            &maker_a_bids.map(UnvalidatedOrder::from),
+            &[],
         )])
         .program_result
         .is_ok());
@@ -202,11 +205,9 @@ fn batch_replace_interleaved_bids_are_sorted() -> anyhow::Result<()> {
     assert!(mollusk
         .process_instruction_chain(&[market_ctx.batch_replace(
             maker_b,
-            BatchReplaceInstructionData::new(
-                maker_b_seat,
-                UnvalidatedOrders::new(maker_b_bids),
-                UnvalidatedOrders::new([]),
-            ),
+            BatchReplaceInstructionData::new(maker_b_seat, BatchReplaceMode::Replace as u8),
+            &maker_b_bids.map(UnvalidatedOrder::from),
+            &[],
         )])
         .program_result
         .is_ok());
@@ -284,63 +285,231 @@ fn batch_replace_unsorted_orders_failure() -> anyhow::Result<()> {
 
     check.seat_index(user, seat_index);
 
-    let ascending_bids = BatchReplaceInstructionData::new(
-        seat_index,
-        UnvalidatedOrders::new(ascending_orders.clone()),
-        UnvalidatedOrders::new([]),
-    );
-    let descending_bids = BatchReplaceInstructionData::new(
-        seat_index,
-        UnvalidatedOrders::new(descending_orders.clone()),
-        UnvalidatedOrders::new([]),
-    );
-    let equal_bids = BatchReplaceInstructionData::new(
-        seat_index,
-        UnvalidatedOrders::new(equal_orders.clone()),
-        UnvalidatedOrders::new([]),
-    );
-    let ascending_asks = BatchReplaceInstructionData::new(
-        seat_index,
-        UnvalidatedOrders::new([]),
-        UnvalidatedOrders::new(ascending_orders.clone()),
-    );
-    let descending_asks = BatchReplaceInstructionData::new(
-        seat_index,
-        UnvalidatedOrders::new([]),
-        UnvalidatedOrders::new(descending_orders.clone()),
-    );
-    let equal_asks = BatchReplaceInstructionData::new(
-        seat_index,
-        UnvalidatedOrders::new([]),
-        UnvalidatedOrders::new(equal_orders.clone()),
-    );
+    let [ascending, descending, equal] = [ascending_orders, descending_orders, equal_orders]
+        .map(|orders| orders.map(UnvalidatedOrder::from));
+    let batch_replace = |new_bids: &[UnvalidatedOrder], new_asks: &[UnvalidatedOrder]| {
+        market_ctx.batch_replace(
+            user,
+            BatchReplaceInstructionData::new(seat_index, BatchReplaceMode::Replace as u8),
+            new_bids,
+            new_asks,
+        )
+    };
+    let ascending_bids = batch_replace(&ascending, &[]);
+    let descending_bids = batch_replace(&descending, &[]);
+    let equal_bids = batch_replace(&equal, &[]);
+    let ascending_asks = batch_replace(&[], &ascending);
+    let descending_asks = batch_replace(&[], &descending);
+    let equal_asks = batch_replace(&[], &equal);
 
     mollusk.process_and_validate_instruction_chain(&[
         (
-            &market_ctx.batch_replace(user, ascending_bids),
+            &ascending_bids,
             &[Check::err(DropsetError::OrdersNotSorted.into())],
         ),
+        (&descending_bids, &[Check::success()]),
+        (&ascending_asks, &[Check::success()]),
         (
-            &market_ctx.batch_replace(user, descending_bids),
-            &[Check::success()],
+            &descending_asks,
+            &[Check::err(DropsetError::OrdersNotSorted.into())],
         ),
         (
-            &market_ctx.batch_replace(user, ascending_asks),
-            &[Check::success()],
+            &equal_bids,
+            &[Check::err(DropsetError::OrdersNotSorted.into())],
         ),
         (
-            &market_ctx.batch_replace(user, descending_asks),
+            &equal_asks,
             &[Check::err(DropsetError::OrdersNotSorted.into())],
         ),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn batch_replace_more_than_ten_orders() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    // One more ask than the default market's max orders per user, in ascending price order.
+    let ask_order_args = (0..=MAX_ORDERS as u32)
+        .map(|i| OrderInfoArgs::new_unscaled(11_000_000 + i * 100_000, 1))
+        .collect_vec();
+    let base_necessary = sum_base_necessary(&ask_order_args)?;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, base_necessary)?,
+            market_ctx.deposit_base(user, base_necessary, NIL),
+            market_ctx.expand(user, MAX_PERMITTED_SECTOR_INCREASE as u16),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+    let asks = ask_order_args
+        .iter()
+        .cloned()
+        .map(UnvalidatedOrder::from)
+        .collect_vec();
+    let batch_replace = |new_asks: &[UnvalidatedOrder]| {
+        market_ctx.batch_replace(
+            user,
+            BatchReplaceInstructionData::new(seat_index, BatchReplaceMode::Replace as u8),
+            &[],
+            new_asks,
+        )
+    };
+
+    mollusk.process_and_validate_instruction_chain(&[
         (
-            &market_ctx.batch_replace(user, equal_bids),
-            &[Check::err(DropsetError::OrdersNotSorted.into())],
+            &batch_replace(&asks),
+            &[Check::err(DropsetError::UserHasMaxOrders.into())],
         ),
         (
-            &market_ctx.batch_replace(user, equal_asks),
-            &[Check::err(DropsetError::OrdersNotSorted.into())],
+            &batch_replace(&asks[..MAX_ORDERS_USIZE]),
+            &[Check::success()],
         ),
     ]);
 
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(MAX_ORDERS_USIZE);
+    check.seat(user, |seat| {
+        assert_eq!(seat.user_order_sectors.asks.len(), MAX_ORDERS_USIZE);
+    });
+
+    // Replacing the asks with a single ask frees the other orders' sectors and the seat's overflow
+    // order sectors.
+    let num_free_sectors = mollusk
+        .view_market(market_ctx.market)
+        .header
+        .num_free_sectors;
+    mollusk.process_and_validate_instruction(&batch_replace(&asks[..1]), &[Check::success()]);
+
+    check.num_asks(1);
+    check.seat(user, |seat| {
+        assert_eq!(seat.user_order_sectors.asks.len(), 1)
+    });
+    check.market_header(|header| {
+        assert_eq!(
+            header.num_free_sectors,
+            num_free_sectors + MAX_ORDERS as u32
+        )
+    });
+
+    Ok(())
+}
+
+/// Maker A's asks are [12M x2, 13M x1, 14M x1], followed by maker B's ask at 12M. An incremental
+/// batch replace to [12M x1, 13M x1, 15M x1] should reduce A's 12M ask in place, leave the 13M ask
+/// untouched, cancel the 14M ask and post the 15M ask.
+#[test]
+fn batch_replace_incremental_keeps_unchanged_orders() -> anyhow::Result<()> {
+    let maker_a_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker_b_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker_a = maker_a_mock.0;
+    let maker_b = maker_b_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_a_mock, maker_b_mock]);
+
+    let initial_asks = [
+        OrderInfoArgs::new_unscaled(12_000_000, 2),
+        OrderInfoArgs::new_unscaled(13_000_000, 1),
+        OrderInfoArgs::new_unscaled(14_000_000, 1),
+    ];
+    let new_asks = [
+        OrderInfoArgs::new_unscaled(12_000_000, 1),
+        OrderInfoArgs::new_unscaled(13_000_000, 1),
+        OrderInfoArgs::new_unscaled(15_000_000, 1),
+    ];
+    let maker_b_asks = [OrderInfoArgs::new_unscaled(12_000_000, 1)];
+
+    let base_necessary = sum_base_necessary(&initial_asks)?;
+    for maker in [maker_a, maker_b] {
+        assert!(mollusk
+            .process_instruction_chain(&[
+                market_ctx.base.create_ata_idempotent(&maker, &maker),
+                market_ctx.base.mint_to_owner(&maker, base_necessary)?,
+                market_ctx.deposit_base(maker, base_necessary, NIL),
+            ])
+            .program_result
+            .is_ok());
+    }
+    let maker_a_seat = mollusk.get_seat(market_ctx.market, maker_a).index;
+    let maker_b_seat = mollusk.get_seat(market_ctx.market, maker_b).index;
+
+    let batch_replace =
+        |maker: Address, seat: u32, mode: BatchReplaceMode, asks: &[OrderInfoArgs]| {
+            market_ctx.batch_replace(
+                maker,
+                BatchReplaceInstructionData::new(seat, mode as u8),
+                &[],
+                &asks
+                    .iter()
+                    .cloned()
+                    .map(UnvalidatedOrder::from)
+                    .collect_vec(),
+            )
+        };
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            batch_replace(
+                maker_a,
+                maker_a_seat,
+                BatchReplaceMode::Replace,
+                &initial_asks
+            ),
+            batch_replace(
+                maker_b,
+                maker_b_seat,
+                BatchReplaceMode::Replace,
+                &maker_b_asks
+            ),
+        ])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    let mut initial_sector_indices = vec![];
+    check.asks(|asks| {
+        assert_eq!(asks.len(), 4);
+        assert_eq!(asks[0].user_seat, maker_a_seat);
+        assert_eq!(asks[1].user_seat, maker_b_seat);
+        initial_sector_indices = asks.iter().map(|ask| ask.index).collect_vec();
+    });
+
+    assert!(mollusk
+        .process_instruction_chain(&[batch_replace(
+            maker_a,
+            maker_a_seat,
+            BatchReplaceMode::Incremental,
+            &new_asks,
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.asks(|asks| {
+        assert_eq!(asks.len(), 4);
+        let expected_owners = [maker_a_seat, maker_b_seat, maker_a_seat, maker_a_seat];
+        let expected_args = [&new_asks[0], &maker_b_asks[0], &new_asks[1], &new_asks[2]];
+        for ((ask, owner), args) in asks.iter().zip(expected_owners).zip(expected_args) {
+            let order = to_order_info(args.clone()).unwrap();
+            assert_eq!(ask.user_seat, owner);
+            assert_eq!(ask.encoded_price, order.encoded_price);
+            assert_eq!(ask.base_remaining, order.base_atoms);
+        }
+        // The reduced and unchanged orders stayed in the same sectors.
+        assert_eq!(asks[0].index, initial_sector_indices[0]);
+        assert_eq!(asks[2].index, initial_sector_indices[2]);
+    });
+    check.seat_base_available(maker_a, base_necessary - sum_base_necessary(&new_asks)?);
+    check.seat(maker_a, |seat| {
+        assert_eq!(seat.user_order_sectors.asks.len(), new_asks.len());
+    });
+
     Ok(())
 }
@@ -0,0 +1,188 @@
+use client::mollusk_helpers::{
+    helper_trait::DropsetTestHelper,
+    market_checker::MarketChecker,
+    new_dropset_mollusk_context_with_default_market,
+    utils::create_mock_user_account,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        generated_client::CancelAllOrders,
+        BatchReplaceInstructionData,
+        BatchReplaceMode,
+        CancelAllOrdersInstructionData,
+        SideFilter,
+        UnvalidatedOrder,
+    },
+    seeds::event_authority,
+    state::sector::NIL,
+};
+use mollusk_svm::result::Check;
+use price::{
+    client_helpers::{
+        sum_base_necessary,
+        sum_quote_necessary,
+    },
+    OrderInfoArgs,
+};
+use solana_address::Address;
+
+#[test]
+fn cancel_all_orders_by_side() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    let bid_order_args = [
+        OrderInfoArgs::new_unscaled(11_000_000, 1),
+        OrderInfoArgs::new_unscaled(10_000_000, 1),
+    ];
+    let ask_order_args = [
+        OrderInfoArgs::new_unscaled(12_000_000, 1),
+        OrderInfoArgs::new_unscaled(13_000_000, 1),
+    ];
+
+    let quote_necessary = sum_quote_necessary(&bid_order_args)?;
+    let base_necessary = sum_base_necessary(&ask_order_args)?;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, base_necessary)?,
+            market_ctx.quote.mint_to_owner(&user, quote_necessary)?,
+        ])
+        .program_result
+        .is_ok());
+
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.deposit_base(user, base_necessary, NIL)])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.deposit_quote(user, quote_necessary, seat_index)])
+        .program_result
+        .is_ok());
+
+    let post_all_orders = || {
+        market_ctx.batch_replace(
+            user,
+            BatchReplaceInstructionData::new(seat_index, BatchReplaceMode::Replace as u8),
+            &bid_order_args.clone().map(UnvalidatedOrder::from),
+            &ask_order_args.clone().map(UnvalidatedOrder::from),
+        )
+    };
+
+    assert!(mollusk
+        .process_instruction_chain(&[post_all_orders()])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(2);
+    check.num_asks(2);
+    check.seat_base_available(user, 0);
+    check.seat_quote_available(user, 0);
+
+    // Cancel only the asks. The bids should be left untouched.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.cancel_all_orders(
+            user,
+            seat_index,
+            SideFilter::Asks
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(2);
+    check.num_asks(0);
+    check.seat_base_available(user, base_necessary);
+    check.seat_quote_available(user, 0);
+
+    // Cancel both sides. Canceling the already empty ask side is a no-op.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.cancel_all_orders(
+            user,
+            seat_index,
+            SideFilter::Both
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(0);
+    check.num_asks(0);
+    check.seat_base_available(user, base_necessary);
+    check.seat_quote_available(user, quote_necessary);
+
+    // The seat's order mappings were cleared, so the same orders can be posted again.
+    assert!(mollusk
+        .process_instruction_chain(&[post_all_orders()])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(2);
+    check.num_asks(2);
+
+    // Cancel only the bids.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.cancel_all_orders(
+            user,
+            seat_index,
+            SideFilter::Bids
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(0);
+    check.num_asks(2);
+    check.seat_base_available(user, 0);
+    check.seat_quote_available(user, quote_necessary);
+
+    Ok(())
+}
+
+#[test]
+fn cancel_all_orders_invalid_side_filter() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, 1)?,
+            market_ctx.deposit_base(user, 1, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+
+    let invalid_side_filter = CancelAllOrders {
+        event_authority: event_authority::ID,
+        user,
+        market_account: market_ctx.market,
+        dropset_program: dropset::ID,
+    }
+    .create_instruction(CancelAllOrdersInstructionData::new(seat_index, 3));
+
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &invalid_side_filter,
+            &[Check::err(DropsetError::InvalidSideFilter.into())],
+        ),
+        (
+            &market_ctx.cancel_all_orders(user, seat_index, SideFilter::Both),
+            &[Check::success()],
+        ),
+    ]);
+
+    Ok(())
+}
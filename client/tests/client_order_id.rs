@@ -0,0 +1,174 @@
+use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
+    helper_trait::DropsetTestHelper,
+    market_checker::MarketChecker,
+    new_dropset_mollusk_context_with_default_market,
+    utils::create_mock_user_account,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        BatchReplaceInstructionData,
+        BatchReplaceMode,
+        CancelOrderByClientIdInstructionData,
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+        UnvalidatedOrder,
+    },
+    state::sector::NIL,
+};
+use mollusk_svm::result::Check;
+use price::{
+    client_helpers::{
+        sum_base_necessary,
+        sum_quote_necessary,
+    },
+    to_order_info,
+    OrderInfoArgs,
+};
+use solana_address::Address;
+
+#[test]
+fn post_and_cancel_by_client_order_id() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    let bid_args = [
+        OrderInfoArgs::new_unscaled(11_000_000, 1),
+        OrderInfoArgs::new_unscaled(10_000_000, 1),
+    ];
+    let quote_necessary = sum_quote_necessary(&bid_args)?;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.quote.mint_to_owner(&user, quote_necessary)?,
+            market_ctx.deposit_quote(user, quote_necessary, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+
+    let post_bid = |args: OrderInfoArgs, client_order_id: u64| {
+        market_ctx.post_order(
+            user,
+            PostOrderInstructionData::new(
+                args,
+                true,
+                seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                client_order_id,
+            ),
+        )
+    };
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            post_bid(bid_args[0].clone(), 42),
+            post_bid(bid_args[1].clone(), 43),
+        ])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(2);
+    check.bids(|bids| {
+        assert_eq!(bids[0].client_order_id, 42);
+        assert_eq!(bids[1].client_order_id, 43);
+    });
+
+    let cancel = |client_order_id: u64, is_bid: bool| {
+        market_ctx.cancel_order_by_client_id(
+            user,
+            CancelOrderByClientIdInstructionData::new(client_order_id, is_bid, seat_index),
+        )
+    };
+
+    mollusk.process_and_validate_instruction_chain(&[
+        // No order has the client order id.
+        (
+            &cancel(44, true),
+            &[DropsetError::OrderNotFound.into_check_failure()],
+        ),
+        // The order with the client order id is a bid, not an ask.
+        (
+            &cancel(43, false),
+            &[DropsetError::OrderNotFound.into_check_failure()],
+        ),
+        (&cancel(43, true), &[Check::success()]),
+    ]);
+
+    let cheaper_bid = to_order_info(bid_args[1].clone()).expect("Should be a valid order");
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(1);
+    check.bids(|bids| assert_eq!(bids[0].client_order_id, 42));
+    check.seat_quote_available(user, cheaper_bid.quote_atoms);
+    check.seat(user, |seat| {
+        assert_eq!(seat.user_order_sectors.bids.len(), 1);
+    });
+
+    Ok(())
+}
+
+#[test]
+fn batch_replace_with_client_order_ids() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    let ask_args = [
+        OrderInfoArgs::new_unscaled(12_000_000, 1),
+        OrderInfoArgs::new_unscaled(13_000_000, 1),
+    ];
+    let base_necessary = sum_base_necessary(&ask_args)?;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, base_necessary)?,
+            market_ctx.deposit_base(user, base_necessary, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.batch_replace(
+            user,
+            BatchReplaceInstructionData::new(seat_index, BatchReplaceMode::Replace as u8),
+            &[],
+            &[
+                UnvalidatedOrder::new(ask_args[0].clone(), 7),
+                UnvalidatedOrder::new(ask_args[1].clone(), 8),
+            ],
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(2);
+    check.asks(|asks| {
+        assert_eq!(asks[0].client_order_id, 7);
+        assert_eq!(asks[1].client_order_id, 8);
+    });
+
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.cancel_order_by_client_id(
+            user,
+            CancelOrderByClientIdInstructionData::new(7, false, seat_index),
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(1);
+    check.asks(|asks| assert_eq!(asks[0].client_order_id, 8));
+
+    Ok(())
+}
@@ -1,9 +1,35 @@
 use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
+    helper_trait::DropsetTestHelper,
     market_checker::MarketChecker,
     new_dropset_mollusk_context_with_default_market,
-    utils::create_mock_user_account,
+    utils::{
+        create_mock_user_account,
+        without_signer,
+    },
+    MOLLUSK_DEFAULT_MINT_AUTHORITY,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        CancelOrderInstructionData,
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
+    state::{
+        market_seat::MAX_DUST_ATOMS,
+        sector::NIL,
+    },
+};
+use mollusk_svm::result::Check;
+use price::{
+    client_helpers::sum_base_necessary,
+    to_order_info,
+    OrderInfoArgs,
 };
 use solana_address::Address;
+use solana_program_error::ProgramError;
 
 #[test]
 fn close_seat() -> anyhow::Result<()> {
@@ -48,3 +74,264 @@ fn close_seat() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Verifies that a seat with open orders can't be closed, since the collateral locked in them would
+/// be stranded, and that it can once the orders are canceled.
+#[test]
+fn close_seat_with_open_orders_fails() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    let ask_args = OrderInfoArgs::new_unscaled(12_000_000, 1);
+    let base_deposited = sum_base_necessary(&[ask_args.clone()])?;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, base_deposited)?,
+            market_ctx.deposit_base(user, base_deposited, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+    let encoded_price = to_order_info(ask_args.clone())
+        .expect("Should be a valid order")
+        .encoded_price
+        .as_u32();
+
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &market_ctx.post_order(
+                user,
+                PostOrderInstructionData::new(
+                    ask_args,
+                    false,
+                    seat_index,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0,
+                ),
+            ),
+            &[Check::success()],
+        ),
+        (
+            &market_ctx.close_seat(user, seat_index),
+            &[DropsetError::SeatHasOpenOrders.into_check_failure()],
+        ),
+        (
+            &market_ctx.cancel_order(
+                user,
+                CancelOrderInstructionData::new(encoded_price, false, seat_index),
+            ),
+            &[Check::success()],
+        ),
+        (
+            &market_ctx.close_seat(user, seat_index),
+            &[Check::success()],
+        ),
+    ]);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_seats(0);
+    check.base_token_balance(user, base_deposited);
+
+    Ok(())
+}
+
+/// Verifies that the permissionless crank only closes seats with no balances, no open orders and no
+/// delegate, and that it closes at most `max_seats` seats.
+#[test]
+fn close_empty_seats() -> anyhow::Result<()> {
+    let mocks = [0; 4].map(|_| create_mock_user_account(Address::new_unique(), 100_000_000));
+    let [empty_1, empty_2, delegated, funded] = mocks.clone().map(|mock| mock.0);
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&mocks);
+
+    // Each user creates a seat with 1 base, then the empty seats' users withdraw it.
+    for user in [empty_1, empty_2, delegated, funded] {
+        assert!(mollusk
+            .process_instruction_chain(&[
+                market_ctx.base.create_ata_idempotent(&user, &user),
+                market_ctx.base.mint_to_owner(&user, 1)?,
+                market_ctx.create_seat(user),
+            ])
+            .program_result
+            .is_ok());
+    }
+    for user in [empty_1, empty_2, delegated] {
+        let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+        assert!(mollusk
+            .process_instruction_chain(&[market_ctx.withdraw_base(user, 1, seat_index)])
+            .program_result
+            .is_ok());
+    }
+
+    // The empty seat with a delegate is skipped by the crank.
+    let delegated_index = mollusk.get_seat(market_ctx.market, delegated).index;
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.set_seat_delegate(
+            delegated,
+            delegated_index,
+            Some(Address::new_unique()),
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_seats(4);
+
+    mollusk.process_and_validate_instruction_chain(&[(
+        &market_ctx.close_empty_seats(1),
+        &[Check::success()],
+    )]);
+    check.num_seats(3);
+
+    mollusk.process_and_validate_instruction_chain(&[(
+        &market_ctx.close_empty_seats(u16::MAX),
+        &[Check::success()],
+    )]);
+    check.num_seats(2);
+    check.has_seat(delegated);
+    check.has_seat(funded);
+    check.seat_base_available(funded, 1);
+
+    Ok(())
+}
+
+/// Verifies that the market authority can close a seat without open orders without the owner's
+/// signature, that no one else can while the seat has more than dust available, and that the seat's
+/// remaining balances are transferred to the owner's token accounts.
+#[test]
+fn settle_and_close_seat() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let stranger_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let stranger = stranger_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[user_mock, stranger_mock]);
+
+    let ask_args = OrderInfoArgs::new_unscaled(12_000_000, 1);
+    let base_deposited = sum_base_necessary(&[ask_args.clone()])? + MAX_DUST_ATOMS;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, base_deposited)?,
+            market_ctx.deposit_base(user, base_deposited, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+    let encoded_price = to_order_info(ask_args.clone())
+        .expect("Should be a valid order")
+        .encoded_price
+        .as_u32();
+
+    let settle_ixn =
+        market_ctx.settle_and_close_seat(MOLLUSK_DEFAULT_MINT_AUTHORITY, user, seat_index);
+    assert!(settle_ixn
+        .accounts
+        .iter()
+        .all(|meta| !meta.is_signer || meta.pubkey == MOLLUSK_DEFAULT_MINT_AUTHORITY));
+
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &market_ctx.post_order(
+                user,
+                PostOrderInstructionData::new(
+                    ask_args,
+                    false,
+                    seat_index,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0,
+                ),
+            ),
+            &[Check::success()],
+        ),
+        (
+            &settle_ixn,
+            &[DropsetError::SeatHasOpenOrders.into_check_failure()],
+        ),
+        (
+            &market_ctx.cancel_order(
+                user,
+                CancelOrderInstructionData::new(encoded_price, false, seat_index),
+            ),
+            &[Check::success()],
+        ),
+        // Only the seat's owner or the market authority can close a seat that isn't abandoned, and
+        // the closer must sign.
+        (
+            &market_ctx.settle_and_close_seat(stranger, user, seat_index),
+            &[DropsetError::UnauthorizedSeatCloser.into_check_failure()],
+        ),
+        (
+            &without_signer(settle_ixn.clone(), &MOLLUSK_DEFAULT_MINT_AUTHORITY),
+            &[Check::err(ProgramError::MissingRequiredSignature)],
+        ),
+        (&settle_ixn, &[Check::success()]),
+    ]);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_seats(0);
+    check.base_token_balance(user, base_deposited);
+    check.quote_token_balance(user, 0);
+
+    Ok(())
+}
+
+/// Verifies that anyone can settle and close an abandoned seat, i.e., one with only dust available
+/// and no delegate, transferring the dust to the owner's token accounts.
+#[test]
+fn settle_and_close_abandoned_seat() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let crank_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let crank = crank_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[user_mock, crank_mock]);
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, MAX_DUST_ATOMS)?,
+            market_ctx.deposit_base(user, MAX_DUST_ATOMS, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+    let settle_ixn = market_ctx.settle_and_close_seat(crank, user, seat_index);
+
+    mollusk.process_and_validate_instruction_chain(&[
+        // A seat with a delegate isn't abandoned, even if it only has dust available.
+        (
+            &market_ctx.set_seat_delegate(user, seat_index, Some(Address::new_unique())),
+            &[Check::success()],
+        ),
+        (
+            &settle_ixn,
+            &[DropsetError::UnauthorizedSeatCloser.into_check_failure()],
+        ),
+        (
+            &market_ctx.set_seat_delegate(user, seat_index, None),
+            &[Check::success()],
+        ),
+        (&settle_ixn, &[Check::success()]),
+    ]);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_seats(0);
+    check.base_token_balance(user, MAX_DUST_ATOMS);
+    check.quote_token_balance(user, 0);
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
+    helper_trait::DropsetTestHelper,
+    market_checker::MarketChecker,
+    new_dropset_mollusk_context_with_default_market,
+    utils::{
+        create_mock_user_account,
+        without_signer,
+    },
+    MOLLUSK_DEFAULT_MINT_AUTHORITY,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        BatchReplaceInstructionData,
+        BatchReplaceMode,
+        CancelOrderInstructionData,
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+        UnvalidatedOrder,
+    },
+    state::{
+        market_header::MarketHeader,
+        sector::Sector,
+        transmutable::Transmutable,
+        user_order_sectors::MAX_INLINE_ORDERS,
+    },
+};
+use mollusk_svm::result::Check;
+use price::{
+    client_helpers::{
+        sum_base_necessary,
+        sum_quote_necessary,
+    },
+    to_order_info,
+    OrderInfoArgs,
+};
+use solana_address::Address;
+use solana_program_error::ProgramError;
+
+/// Verifies that compacting the market relocates the live seat and orders to the front of the
+/// market account, truncates the account data and keeps the seat's orders usable afterwards.
+#[test]
+fn compact_market() -> anyhow::Result<()> {
+    let mocks = [0; 4].map(|_| create_mock_user_account(Address::new_unique(), 100_000_000));
+    let users = mocks.clone().map(|mock| mock.0);
+    let maker = users[3];
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&mocks);
+
+    // Each user creates a seat, so the maker's seat is the fourth sector.
+    for user in users {
+        assert!(mollusk
+            .process_instruction_chain(&[
+                market_ctx.base.create_ata_idempotent(&user, &user),
+                market_ctx.quote.create_ata_idempotent(&user, &user),
+                market_ctx.base.mint_to_owner(&user, 1)?,
+                market_ctx.create_seat(user),
+            ])
+            .program_result
+            .is_ok());
+    }
+
+    let ask_args = OrderInfoArgs::new_unscaled(12_000_000, 1);
+    let bid_args = OrderInfoArgs::new_unscaled(10_000_000, 1);
+    let base_deposited = sum_base_necessary(&[ask_args.clone()])?;
+    let quote_deposited = sum_quote_necessary(&[bid_args.clone()])?;
+    let seat_index = mollusk.get_seat(market_ctx.market, maker).index;
+    assert_eq!(seat_index, 3);
+
+    let post = |args: OrderInfoArgs, is_bid: bool, seat_index: u32| {
+        market_ctx.post_order(
+            maker,
+            PostOrderInstructionData::new(
+                args,
+                is_bid,
+                seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
+        )
+    };
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.mint_to_owner(&maker, base_deposited)?,
+            market_ctx.quote.mint_to_owner(&maker, quote_deposited)?,
+            market_ctx.deposit_base(maker, base_deposited, seat_index),
+            market_ctx.deposit_quote(maker, quote_deposited, seat_index),
+            post(ask_args.clone(), false, seat_index),
+            post(bid_args.clone(), true, seat_index),
+        ])
+        .program_result
+        .is_ok());
+
+    // Close the other seats so that every live sector is past the first three sectors.
+    for (i, user) in users[..3].iter().enumerate() {
+        assert!(mollusk
+            .process_instruction_chain(&[market_ctx.close_seat(*user, i as u32)])
+            .program_result
+            .is_ok());
+    }
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_seats(1);
+    check.num_asks(1);
+    check.num_bids(1);
+
+    let num_free_sectors = mollusk
+        .view_market(market_ctx.market)
+        .header
+        .num_free_sectors;
+
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &market_ctx.compact_market(maker, 0),
+            &[DropsetError::IncorrectMarketAuthority.into_check_failure()],
+        ),
+        (
+            &without_signer(
+                market_ctx.compact_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, 0),
+                &MOLLUSK_DEFAULT_MINT_AUTHORITY,
+            ),
+            &[Check::err(ProgramError::MissingRequiredSignature)],
+        ),
+        (
+            &market_ctx.compact_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, num_free_sectors + 1),
+            &[DropsetError::InvalidNumFreeSectors.into_check_failure()],
+        ),
+        (
+            &market_ctx.compact_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, 0),
+            &[
+                Check::success(),
+                Check::account(&market_ctx.market)
+                    .space(MarketHeader::LEN + 3 * Sector::LEN)
+                    .build(),
+            ],
+        ),
+    ]);
+
+    check.num_seats(1);
+    check.num_asks(1);
+    check.num_bids(1);
+    check.market_header(|header| assert_eq!(header.num_free_sectors, 0));
+    check.seat_base_available(maker, 1);
+    check.seat_quote_available(maker, 0);
+
+    // The seat was relocated, and its orders must still be cancelable through the new seat index.
+    let new_seat_index = mollusk.get_seat(market_ctx.market, maker).index;
+    assert!(new_seat_index < 3);
+
+    let encoded_price = |args: OrderInfoArgs| {
+        to_order_info(args)
+            .expect("Should be a valid order")
+            .encoded_price
+    };
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.cancel_order(
+                maker,
+                CancelOrderInstructionData::new(
+                    encoded_price(ask_args).as_u32(),
+                    false,
+                    new_seat_index
+                ),
+            ),
+            market_ctx.cancel_order(
+                maker,
+                CancelOrderInstructionData::new(
+                    encoded_price(bid_args).as_u32(),
+                    true,
+                    new_seat_index
+                ),
+            ),
+            market_ctx.close_seat(maker, new_seat_index),
+        ])
+        .program_result
+        .is_ok());
+
+    check.num_seats(0);
+    check.num_asks(0);
+    check.num_bids(0);
+    check.base_token_balance(maker, base_deposited + 1);
+    check.quote_token_balance(maker, quote_deposited);
+
+    // Compacting an empty market removes every sector.
+    mollusk.process_and_validate_instruction(
+        &market_ctx.compact_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, 0),
+        &[
+            Check::success(),
+            Check::account(&market_ctx.market)
+                .space(MarketHeader::LEN)
+                .build(),
+        ],
+    );
+
+    Ok(())
+}
+
+/// Verifies that compacting the market relocates a seat's overflow order sectors when it's past the
+/// new end, and that the seat's orders are still usable afterwards.
+#[test]
+fn compact_market_relocates_overflow_order_sectors() -> anyhow::Result<()> {
+    let mocks = [0; 2].map(|_| create_mock_user_account(Address::new_unique(), 100_000_000));
+    let [maker, other] = mocks.clone().map(|mock| mock.0);
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&mocks);
+
+    // One more ask than fits in the seat, so the last one is mapped in an overflow sector.
+    let ask_args = (0..=MAX_INLINE_ORDERS as u32)
+        .map(|i| OrderInfoArgs::new_unscaled(11_000_000 + i * 100_000, 1))
+        .collect::<Vec<_>>();
+    let base_necessary = sum_base_necessary(&ask_args)?;
+    let asks = ask_args
+        .iter()
+        .cloned()
+        .map(UnvalidatedOrder::from)
+        .collect::<Vec<_>>();
+
+    for user in [maker, other] {
+        assert!(mollusk
+            .process_instruction_chain(&[
+                market_ctx.base.create_ata_idempotent(&user, &user),
+                market_ctx.quote.create_ata_idempotent(&user, &user),
+                market_ctx.base.mint_to_owner(&user, 1)?,
+                market_ctx.create_seat(user),
+            ])
+            .program_result
+            .is_ok());
+    }
+
+    // The maker's seat is the first sector and the other seat is the second, so the asks are the
+    // next sectors and the overflow sector, allocated last, is the last live sector.
+    let batch_replace = |new_asks: &[UnvalidatedOrder]| {
+        market_ctx.batch_replace(
+            maker,
+            BatchReplaceInstructionData::new(0, BatchReplaceMode::Replace as u8),
+            &[],
+            new_asks,
+        )
+    };
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.mint_to_owner(&maker, base_necessary)?,
+            market_ctx.deposit_base(maker, base_necessary, 0),
+            batch_replace(&asks),
+            market_ctx.close_seat(other, 1),
+        ])
+        .program_result
+        .is_ok());
+
+    // The seat, the asks and the overflow sector are live, and the overflow sector is moved into
+    // the closed seat's sector.
+    let num_live_sectors = 1 + asks.len() + 1;
+    mollusk.process_and_validate_instruction(
+        &market_ctx.compact_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, 0),
+        &[
+            Check::success(),
+            Check::account(&market_ctx.market)
+                .space(MarketHeader::LEN + num_live_sectors * Sector::LEN)
+                .build(),
+        ],
+    );
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(asks.len());
+    check.seat(maker, |seat| {
+        assert_eq!(seat.user_order_sectors.asks.len(), asks.len())
+    });
+
+    // Canceling every ask frees the relocated overflow sector along with the orders.
+    mollusk.process_and_validate_instruction(&batch_replace(&[]), &[Check::success()]);
+    check.num_asks(0);
+    check.seat(maker, |seat| {
+        assert!(seat.user_order_sectors.asks.is_empty())
+    });
+    check.market_header(|header| assert_eq!(header.num_free_sectors, num_live_sectors as u32 - 1));
+    check.seat_base_available(maker, base_necessary + 1);
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use client::{
+    context::market::MarketContext,
+    mollusk_helpers::{
+        checks::IntoCheckFailure,
+        helper_trait::DropsetTestHelper,
+        market_checker::MarketChecker,
+        new_dropset_mollusk_context_with_default_market,
+        utils::create_mock_user_account,
+        MOLLUSK_DEFAULT_MINT_AUTHORITY,
+    },
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+        UpdateMarketConfigInstructionData,
+    },
+    return_data::TakerReturnData,
+    state::sector::NIL,
+};
+use mollusk_svm::{
+    result::Check,
+    MolluskContext,
+};
+use price::{
+    to_order_info,
+    OrderInfo,
+    OrderInfoArgs,
+};
+use solana_account::Account;
+use solana_address::Address;
+
+const MAKER_ASK_PRICE: u32 = 10_000_000;
+
+/// Sets up a maker with a single resting ask and a taker with a seat funded with `taker_quote`
+/// quote atoms. Returns the maker's ask order info and the taker's seat index.
+fn setup_maker_ask_and_taker_seat(
+    mollusk: &MolluskContext<HashMap<Address, Account>>,
+    market_ctx: &MarketContext,
+    maker: Address,
+    taker: Address,
+    taker_quote: u64,
+) -> anyhow::Result<(OrderInfo, u32)> {
+    let ask_args = OrderInfoArgs::new_unscaled(MAKER_ASK_PRICE, 500);
+    let ask = to_order_info(ask_args.clone()).expect("Should be a valid order");
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&maker, &maker),
+            market_ctx.base.mint_to_owner(&maker, ask.base_atoms)?,
+            market_ctx.deposit_base(maker, ask.base_atoms, NIL),
+            market_ctx.post_order(
+                maker,
+                PostOrderInstructionData::new(
+                    ask_args,
+                    false,
+                    0,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0
+                )
+            ),
+            market_ctx.quote.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.mint_to_owner(&taker, taker_quote)?,
+            market_ctx.deposit_quote(taker, taker_quote, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let taker_seat = mollusk.get_seat(market_ctx.market, taker).index;
+    Ok((ask, taker_seat))
+}
+
+/// A crossing bid larger than the resting ask takes the ask and posts the remainder as a bid.
+#[test]
+fn crossing_bid_fills_and_posts_remainder() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let bid_args = OrderInfoArgs::new_unscaled(MAKER_ASK_PRICE, 1_000);
+    let bid = to_order_info(bid_args.clone()).expect("Should be a valid order");
+    let (ask, taker_seat) =
+        setup_maker_ask_and_taker_seat(&mollusk, &market_ctx, maker, taker, bid.quote_atoms)?;
+
+    // The amounts filled by the crossing portion are set as the instruction's return data.
+    mollusk.process_and_validate_instruction(
+        &market_ctx.post_order(
+            taker,
+            PostOrderInstructionData::new(
+                bid_args,
+                true,
+                taker_seat,
+                false,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
+        ),
+        &[
+            Check::success(),
+            Check::return_data(
+                &TakerReturnData {
+                    base_filled: ask.base_atoms,
+                    quote_filled: ask.quote_atoms,
+                    taker_fee: 0,
+                }
+                .pack(),
+            ),
+        ],
+    );
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.num_bids(1);
+    check.bids(|bids| {
+        assert_eq!(bids[0].encoded_price, bid.encoded_price);
+        assert_eq!(bids[0].base_remaining, bid.base_atoms - ask.base_atoms);
+        assert_eq!(bids[0].quote_remaining, bid.quote_atoms - ask.quote_atoms);
+    });
+
+    // The taker received the ask's base in their seat and paid for both the fill and the bid.
+    check.seat_base_available(taker, ask.base_atoms);
+    check.seat_quote_available(taker, 0);
+    // The maker received quote for their filled ask.
+    check.seat_quote_available(maker, ask.quote_atoms);
+    check.seat_base_available(maker, 0);
+
+    Ok(())
+}
+
+/// A crossing bid whose remainder falls below the market's minimum order size fills the ask but
+/// drops the remainder instead of posting it.
+#[test]
+fn crossing_bid_drops_remainder_below_min_order_size() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let bid_args = OrderInfoArgs::new_unscaled(MAKER_ASK_PRICE, 600);
+    let bid = to_order_info(bid_args.clone()).expect("Should be a valid order");
+    let (ask, taker_seat) =
+        setup_maker_ask_and_taker_seat(&mollusk, &market_ctx, maker, taker, bid.quote_atoms)?;
+
+    // The full bid meets the minimum order size, but its remainder after taking the ask doesn't.
+    assert!(bid.base_atoms - ask.base_atoms < ask.base_atoms);
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.update_market_config(
+                MOLLUSK_DEFAULT_MINT_AUTHORITY,
+                UpdateMarketConfigInstructionData::new(
+                    ask.base_atoms,
+                    1,
+                    10,
+                    MOLLUSK_DEFAULT_MINT_AUTHORITY,
+                    0,
+                    0,
+                ),
+            ),
+            market_ctx.post_order(
+                taker,
+                PostOrderInstructionData::new(
+                    bid_args,
+                    true,
+                    taker_seat,
+                    false,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0
+                ),
+            ),
+        ])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.num_bids(0);
+    check.seat(taker, |seat| {
+        assert!(seat.user_order_sectors.bids.is_empty())
+    });
+    // The taker only paid for the fill.
+    check.seat_base_available(taker, ask.base_atoms);
+    check.seat_quote_available(taker, bid.quote_atoms - ask.quote_atoms);
+    check.seat_quote_available(maker, ask.quote_atoms);
+
+    Ok(())
+}
+
+/// A crossing bid fills at the resting ask's better price and, when completely filled, doesn't
+/// post anything to the book.
+#[test]
+fn crossing_bid_fills_at_maker_price() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let bid_args = OrderInfoArgs::new_unscaled(MAKER_ASK_PRICE * 2, 500);
+    let bid = to_order_info(bid_args.clone()).expect("Should be a valid order");
+    let (ask, taker_seat) =
+        setup_maker_ask_and_taker_seat(&mollusk, &market_ctx, maker, taker, bid.quote_atoms)?;
+
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.post_order(
+            taker,
+            PostOrderInstructionData::new(
+                bid_args,
+                true,
+                taker_seat,
+                false,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0
+            ),
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.num_bids(0);
+    check.seat(taker, |seat| {
+        assert!(seat.user_order_sectors.bids.is_empty())
+    });
+    check.seat_base_available(taker, ask.base_atoms);
+    check.seat_quote_available(taker, bid.quote_atoms - ask.quote_atoms);
+    check.seat_quote_available(maker, ask.quote_atoms);
+
+    Ok(())
+}
+
+/// The same crossing bid is still rejected when posted as post-only, and a crossing bid without
+/// enough seat collateral fails outright.
+#[test]
+fn crossing_bid_post_only_and_insufficient_balance() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let bid_args = OrderInfoArgs::new_unscaled(MAKER_ASK_PRICE, 1_000);
+    let bid = to_order_info(bid_args.clone()).expect("Should be a valid order");
+    // Only fund the taker with enough quote to fill the ask, not to post the remainder.
+    let (ask, taker_seat) =
+        setup_maker_ask_and_taker_seat(&mollusk, &market_ctx, maker, taker, bid.quote_atoms - 1)?;
+
+    let post_bid = |post_only: bool| {
+        market_ctx.post_order(
+            taker,
+            PostOrderInstructionData::new(
+                bid_args.clone(),
+                true,
+                taker_seat,
+                post_only,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
+        )
+    };
+    let chain = [
+        (
+            post_bid(true),
+            [DropsetError::PostOnlyWouldImmediatelyFill.into_check_failure()],
+        ),
+        (
+            post_bid(false),
+            [DropsetError::InsufficientUserBalance.into_check_failure()],
+        ),
+    ];
+    let chain_refs: Vec<_> = chain.iter().map(|(i, c)| (i, c.as_slice())).collect();
+    mollusk.process_and_validate_instruction_chain(&chain_refs);
+
+    // Neither instruction changed the book or the seats.
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(1);
+    check.num_bids(0);
+    check.asks(|asks| assert_eq!(asks[0].base_remaining, ask.base_atoms));
+    check.seat_quote_available(taker, bid.quote_atoms - 1);
+
+    Ok(())
+}
@@ -1,20 +1,26 @@
 use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
     helper_trait::DropsetTestHelper,
     market_checker::MarketChecker,
     new_dropset_mollusk_context_with_default_market,
     utils::create_mock_user_account,
     MOLLUSK_DEFAULT_NUM_SECTORS,
 };
-use dropset_interface::state::{
-    sector::{
-        Sector,
-        NIL,
+use dropset_interface::{
+    error::DropsetError,
+    state::{
+        sector::{
+            Sector,
+            NIL,
+        },
+        transmutable::Transmutable,
     },
-    transmutable::Transmutable,
 };
 use itertools::Itertools;
+use mollusk_svm::result::Check;
 use solana_address::Address;
 use solana_instruction::Instruction;
+use solana_system_interface::instruction::transfer;
 use transaction_parser::views::MarketSeatView;
 
 #[test]
@@ -67,6 +73,7 @@ fn deposit_and_withdraw() -> anyhow::Result<()> {
             next_index: NIL,
             user,
             user_order_sectors: Default::default(),
+            delegate: None,
         };
         assert_eq!(seat, expected_seat);
     });
@@ -88,6 +95,138 @@ fn deposit_and_withdraw() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn deposit_and_withdraw_both() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, 10_000)?,
+            market_ctx.quote.mint_to_owner(&user, 20_000)?,
+        ])
+        .program_result
+        .is_ok());
+
+    mollusk.process_and_validate_instruction_chain(&[
+        // At least one of the amounts must be non-zero.
+        (
+            &market_ctx.deposit_both(user, 0, 0, NIL),
+            &[DropsetError::AmountCannotBeZero.into_check_failure()],
+        ),
+        // Register the seat with both base and quote.
+        (
+            &market_ctx.deposit_both(user, 1_000, 2_000, NIL),
+            &[Check::success()],
+        ),
+        // A zero amount skips that token.
+        (
+            &market_ctx.deposit_both(user, 0, 500, 0),
+            &[Check::success()],
+        ),
+    ]);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.base_token_balance(user, 9_000);
+    check.quote_token_balance(user, 17_500);
+    check.base_token_balance(market_ctx.market, 1_000);
+    check.quote_token_balance(market_ctx.market, 2_500);
+    check.seat_base_available(user, 1_000);
+    check.seat_quote_available(user, 2_500);
+
+    mollusk.process_and_validate_instruction_chain(&[
+        // The user can't withdraw more than they have available.
+        (
+            &market_ctx.withdraw_both(user, 1_000, 2_501, 0),
+            &[DropsetError::InsufficientUserBalance.into_check_failure()],
+        ),
+        (
+            &market_ctx.withdraw_both(user, 400, 0, 0),
+            &[Check::success()],
+        ),
+    ]);
+    check.seat_base_available(user, 600);
+    check.seat_quote_available(user, 2_500);
+
+    // Drain the rest of the seat.
+    let seat = mollusk.get_seat(market_ctx.market, user);
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.withdraw_all(&seat)])
+        .program_result
+        .is_ok());
+
+    check.base_token_balance(user, 10_000);
+    check.quote_token_balance(user, 20_000);
+    check.base_token_balance(market_ctx.market, 0);
+    check.quote_token_balance(market_ctx.market, 0);
+    check.seat_base_available(user, 0);
+    check.seat_quote_available(user, 0);
+
+    Ok(())
+}
+
+#[test]
+fn deposit_for_beneficiary() -> anyhow::Result<()> {
+    let funder_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let beneficiary_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let funder = funder_mock.0;
+    let beneficiary = beneficiary_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[funder_mock, beneficiary_mock]);
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&funder, &funder),
+            market_ctx.quote.create_ata_idempotent(&funder, &funder),
+            market_ctx
+                .base
+                .create_ata_idempotent(&beneficiary, &beneficiary),
+            market_ctx.base.mint_to_owner(&funder, 10_000)?,
+            market_ctx.quote.mint_to_owner(&funder, 20_000)?,
+        ])
+        .program_result
+        .is_ok());
+
+    mollusk.process_and_validate_instruction_chain(&[
+        // The funder registers a seat for the beneficiary without the beneficiary signing.
+        (
+            &market_ctx.deposit_base_for(funder, beneficiary, 1_000, NIL),
+            &[Check::success()],
+        ),
+        // The beneficiary already has a seat, so it can't be registered again.
+        (
+            &market_ctx.deposit_base_for(funder, beneficiary, 1_000, NIL),
+            &[DropsetError::UserAlreadyExists.into_check_failure()],
+        ),
+        // The funder credits the beneficiary's existing seat.
+        (
+            &market_ctx.deposit_quote_for(funder, beneficiary, 2_000, 0),
+            &[Check::success()],
+        ),
+    ]);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_seats(1);
+    check.has_seat(beneficiary);
+    check.seat_base_available(beneficiary, 1_000);
+    check.seat_quote_available(beneficiary, 2_000);
+    check.base_token_balance(funder, 9_000);
+    check.quote_token_balance(funder, 18_000);
+
+    // The beneficiary owns the seat and can withdraw from it.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.withdraw_base(beneficiary, 1_000, 0)])
+        .program_result
+        .is_ok());
+    check.base_token_balance(beneficiary, 1_000);
+    check.seat_base_available(beneficiary, 0);
+
+    Ok(())
+}
+
 #[test]
 fn deposit_auto_expand() -> anyhow::Result<()> {
     let users_that_do_not_expand_market = (0..MOLLUSK_DEFAULT_NUM_SECTORS)
@@ -155,3 +294,57 @@ fn deposit_auto_expand() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Verifies that a deposit still expands a full market whose account already holds enough lamports
+/// for the new sector, e.g. because someone transferred lamports directly to the market account.
+#[test]
+fn deposit_auto_expand_prefunded_market() -> anyhow::Result<()> {
+    let users = (0..=MOLLUSK_DEFAULT_NUM_SECTORS)
+        .map(|_| create_mock_user_account(Address::new_unique(), 100_000_000))
+        .collect_vec();
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&users);
+
+    let create_seat_for = |user: Address| -> Vec<Instruction> {
+        vec![
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx
+                .base
+                .mint_to_owner(&user, 1)
+                .expect("Should mint to owner"),
+            market_ctx.create_seat(user),
+        ]
+    };
+
+    let (last_user, first_users) = users.split_last().expect("Should have users");
+    for (user, _) in first_users {
+        assert!(mollusk
+            .process_instruction_chain(&create_seat_for(*user))
+            .program_result
+            .is_ok());
+    }
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.market_header(|header| assert_eq!(header.num_free_sectors, 0));
+
+    // Donate more than enough lamports to cover the rent for the next sector.
+    assert!(mollusk
+        .process_instruction_chain(&[transfer(&last_user.0, &market_ctx.market, 10_000_000)])
+        .program_result
+        .is_ok());
+
+    let data_len_prior_to_expansion = mollusk.view_market_data(market_ctx.market).len();
+    assert!(mollusk
+        .process_instruction_chain(&create_seat_for(last_user.0))
+        .program_result
+        .is_ok());
+    let data_len_post_expansion = mollusk.view_market_data(market_ctx.market).len();
+
+    check.num_seats(MOLLUSK_DEFAULT_NUM_SECTORS as usize + 1);
+    check.market_header(|header| assert_eq!(header.num_free_sectors, 0));
+    assert_eq!(
+        data_len_post_expansion - data_len_prior_to_expansion,
+        Sector::LEN
+    );
+
+    Ok(())
+}
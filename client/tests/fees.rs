@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use client::{
+    context::{
+        market::MarketContext,
+        token::TokenContext,
+    },
+    mollusk_helpers::{
+        checks::IntoCheckFailure,
+        market_checker::MarketChecker,
+        new_dropset_mollusk_context,
+        new_dropset_mollusk_context_with_default_market_and_fees,
+        utils::{
+            create_mock_user_account,
+            without_signer,
+        },
+    },
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
+    state::sector::NIL,
+};
+use mollusk_svm::{
+    result::Check,
+    MolluskContext,
+};
+use price::{
+    to_order_info,
+    OrderInfo,
+    OrderInfoArgs,
+};
+use solana_account::Account;
+use solana_address::Address;
+use solana_program_error::ProgramError;
+use solana_sdk::{
+    program_pack::Pack,
+    rent::Rent,
+};
+use spl_token_interface::state::Mint;
+use transaction_parser::program_ids::SPL_TOKEN_ID;
+
+const TAKER_FEE_BPS: u16 = 30;
+const MAKER_REBATE_BPS: u16 = 10;
+
+fn taker_fee(amount: u64) -> u64 {
+    (amount * TAKER_FEE_BPS as u64).div_ceil(10_000)
+}
+
+fn maker_rebate(amount: u64) -> u64 {
+    amount * MAKER_REBATE_BPS as u64 / 10_000
+}
+
+/// Posts a single maker ask and has the taker buy all of it with a market order. Returns the
+/// filled ask's order info.
+fn fill_single_ask(
+    mollusk: &MolluskContext<HashMap<Address, Account>>,
+    market_ctx: &MarketContext,
+    maker: Address,
+    taker: Address,
+) -> anyhow::Result<OrderInfo> {
+    let ask_args = OrderInfoArgs::new_unscaled(10_000_000, 500);
+    let ask = to_order_info(ask_args.clone()).expect("Should be a valid order");
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&maker, &maker),
+            market_ctx.base.mint_to_owner(&maker, ask.base_atoms)?,
+            market_ctx.deposit_base(maker, ask.base_atoms, NIL),
+            market_ctx.post_order(
+                maker,
+                PostOrderInstructionData::new(
+                    ask_args,
+                    false,
+                    0,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0
+                )
+            ),
+            market_ctx.base.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.mint_to_owner(&taker, ask.quote_atoms)?,
+            market_ctx.unconstrained_market_order(taker, ask.base_atoms, true, true),
+        ])
+        .program_result
+        .is_ok());
+
+    Ok(ask)
+}
+
+#[test]
+fn taker_fee_and_maker_rebate() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market_and_fees(
+        &[maker_mock, taker_mock],
+        Address::new_unique(),
+        TAKER_FEE_BPS,
+        MAKER_REBATE_BPS,
+    );
+
+    let ask = fill_single_ask(&mollusk, &market_ctx, maker, taker)?;
+    let fee = taker_fee(ask.base_atoms);
+    let rebate = maker_rebate(ask.base_atoms);
+    assert!(fee > rebate);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    // The taker receives base net of the taker fee.
+    check.base_token_balance(taker, ask.base_atoms - fee);
+    check.quote_token_balance(taker, 0);
+    // The maker receives the full quote amount plus the rebate in base.
+    check.seat_quote_available(maker, ask.quote_atoms);
+    check.seat_base_available(maker, rebate);
+    // The rest of the fee accrues to the market.
+    check.market_header(|header| {
+        assert_eq!(header.base_fees_accrued, fee - rebate);
+        assert_eq!(header.quote_fees_accrued, 0);
+    });
+
+    Ok(())
+}
+
+#[test]
+fn collect_fees() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let recipient_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let recipient = recipient_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market_and_fees(
+        &[maker_mock, taker_mock, recipient_mock],
+        recipient,
+        TAKER_FEE_BPS,
+        MAKER_REBATE_BPS,
+    );
+
+    let ask = fill_single_ask(&mollusk, &market_ctx, maker, taker)?;
+    let accrued = taker_fee(ask.base_atoms) - maker_rebate(ask.base_atoms);
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx
+                .base
+                .create_ata_idempotent(&recipient, &recipient),
+            market_ctx
+                .quote
+                .create_ata_idempotent(&recipient, &recipient),
+        ])
+        .program_result
+        .is_ok());
+
+    // Only the fee recipient can collect the fees, and it must sign to do so.
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &market_ctx.collect_fees(taker),
+            &[DropsetError::IncorrectFeeRecipient.into_check_failure()],
+        ),
+        (
+            &without_signer(market_ctx.collect_fees(recipient), &recipient),
+            &[Check::err(ProgramError::MissingRequiredSignature)],
+        ),
+    ]);
+
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.collect_fees(recipient)])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.base_token_balance(recipient, accrued);
+    check.quote_token_balance(recipient, 0);
+    check.market_header(|header| {
+        assert_eq!(header.base_fees_accrued, 0);
+        assert_eq!(header.quote_fees_accrued, 0);
+    });
+
+    // There's nothing left to collect.
+    mollusk.process_and_validate_instruction(
+        &market_ctx.collect_fees(recipient),
+        &[DropsetError::AmountCannotBeZero.into_check_failure()],
+    );
+
+    Ok(())
+}
+
+#[test]
+fn register_market_with_invalid_fee_schedule_fails() -> anyhow::Result<()> {
+    let mock_funder = create_mock_user_account(Address::new_unique(), 100_000_000_000);
+    let funder = mock_funder.0;
+    let mollusk = new_dropset_mollusk_context(vec![mock_funder]);
+    let market_ctx = MarketContext::new(
+        TokenContext::new(Some(funder), Address::new_unique(), SPL_TOKEN_ID, 8),
+        TokenContext::new(Some(funder), Address::new_unique(), SPL_TOKEN_ID, 8),
+    );
+
+    assert!(mollusk
+        .process_instruction_chain(
+            &market_ctx
+                .create_tokens(funder, Rent::default().minimum_balance(Mint::LEN))
+                .expect("Should create token instructions"),
+        )
+        .program_result
+        .is_ok());
+
+    // The maker rebate can't exceed the taker fee.
+    mollusk.process_and_validate_instruction(
+        &market_ctx.register_market_with_fees(funder, 10, funder, 10, 11),
+        &[DropsetError::InvalidFeeSchedule.into_check_failure()],
+    );
+
+    // The taker fee can't exceed the max taker fee.
+    mollusk.process_and_validate_instruction(
+        &market_ctx.register_market_with_fees(funder, 10, funder, 1_001, 0),
+        &[DropsetError::InvalidFeeSchedule.into_check_failure()],
+    );
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
+    helper_trait::DropsetTestHelper,
+    market_checker::MarketChecker,
+    new_dropset_mollusk_context_with_default_market,
+    utils::{
+        create_mock_user_account,
+        without_signer,
+    },
+    MOLLUSK_DEFAULT_MINT_AUTHORITY,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        BatchReplaceInstructionData,
+        BatchReplaceMode,
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+        UnvalidatedOrder,
+        UpdateMarketConfigInstructionData,
+    },
+    state::sector::NIL,
+};
+use mollusk_svm::result::Check;
+use price::{
+    to_order_info,
+    OrderInfoArgs,
+};
+use solana_address::Address;
+use solana_program_error::ProgramError;
+
+const TICK: u32 = 1_000;
+const BASE_SCALAR: u64 = 500;
+
+/// Verifies that posted orders must meet the market's minimum order size, price mantissa tick, and
+/// max orders per user, both for `PostOrder` and `BatchReplace`.
+#[test]
+fn post_and_batch_replace_enforce_market_config() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    let min_order = to_order_info(OrderInfoArgs::new_unscaled(10_000_000, BASE_SCALAR))
+        .expect("Should be a valid order");
+    let too_small = to_order_info(OrderInfoArgs::new_unscaled(10_000_000, BASE_SCALAR - 1))
+        .expect("Should be a valid order");
+    assert!(too_small.base_atoms < min_order.base_atoms);
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.update_market_config(
+                MOLLUSK_DEFAULT_MINT_AUTHORITY,
+                UpdateMarketConfigInstructionData::new(
+                    min_order.base_atoms,
+                    TICK,
+                    2,
+                    MOLLUSK_DEFAULT_MINT_AUTHORITY,
+                    0,
+                    0,
+                ),
+            ),
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, u64::MAX)?,
+            market_ctx.deposit_base(user, u64::MAX, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.market_header(|header| {
+        assert_eq!(header.min_order_base_atoms, min_order.base_atoms);
+        assert_eq!(header.price_mantissa_tick, TICK);
+        assert_eq!(header.max_orders_per_user, 2);
+    });
+
+    let seat = mollusk.get_seat(market_ctx.market, user).index;
+    let post_ask = |price_mantissa: u32, base_scalar: u64| {
+        market_ctx.post_order(
+            user,
+            PostOrderInstructionData::new(
+                OrderInfoArgs::new_unscaled(price_mantissa, base_scalar),
+                false,
+                seat,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
+        )
+    };
+    let batch_replace_asks = |price_mantissas: [u32; 3]| {
+        market_ctx.batch_replace(
+            user,
+            BatchReplaceInstructionData::new(seat, BatchReplaceMode::Replace as u8),
+            &[],
+            &price_mantissas
+                .map(|p| UnvalidatedOrder::from(OrderInfoArgs::new_unscaled(p, BASE_SCALAR))),
+        )
+    };
+
+    let chain = [
+        (
+            post_ask(10_000_000, BASE_SCALAR - 1),
+            DropsetError::OrderSizeBelowMinimum.into_check_failure(),
+        ),
+        (
+            post_ask(10_000_001, BASE_SCALAR),
+            DropsetError::PriceNotOnTick.into_check_failure(),
+        ),
+        (post_ask(10_000_000, BASE_SCALAR), Check::success()),
+        (post_ask(10_001_000, BASE_SCALAR), Check::success()),
+        (
+            post_ask(10_002_000, BASE_SCALAR),
+            DropsetError::UserHasMaxOrders.into_check_failure(),
+        ),
+        (
+            batch_replace_asks([10_000_000, 10_001_000, 10_002_000]),
+            DropsetError::UserHasMaxOrders.into_check_failure(),
+        ),
+        (
+            batch_replace_asks([10_000_001, 10_001_000, 10_002_000]),
+            DropsetError::PriceNotOnTick.into_check_failure(),
+        ),
+    ];
+    let chain_refs: Vec<_> = chain
+        .iter()
+        .map(|(ixn, check)| (ixn, core::slice::from_ref(check)))
+        .collect();
+    mollusk.process_and_validate_instruction_chain(&chain_refs);
+
+    check.num_asks(2);
+
+    Ok(())
+}
+
+/// Verifies that only the market authority, as a signer, can update the market config and fee
+/// schedule and that invalid configs and fee schedules are rejected.
+#[test]
+fn update_market_config_authority_and_validation() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    let fee_recipient = Address::new_unique();
+    let update_with_fees = |authority: Address, tick: u32, max_orders: u8, taker_fee_bps: u16| {
+        market_ctx.update_market_config(
+            authority,
+            UpdateMarketConfigInstructionData::new(
+                0,
+                tick,
+                max_orders,
+                fee_recipient,
+                taker_fee_bps,
+                5,
+            ),
+        )
+    };
+    let update = |authority: Address, tick: u32, max_orders: u8| {
+        update_with_fees(authority, tick, max_orders, 10)
+    };
+
+    let chain = [
+        (
+            update(user, TICK, 2),
+            DropsetError::IncorrectMarketAuthority.into_check_failure(),
+        ),
+        (
+            // The authority must sign, not just be passed in.
+            without_signer(
+                update(MOLLUSK_DEFAULT_MINT_AUTHORITY, TICK, 2),
+                &MOLLUSK_DEFAULT_MINT_AUTHORITY,
+            ),
+            Check::err(ProgramError::MissingRequiredSignature),
+        ),
+        (
+            update(MOLLUSK_DEFAULT_MINT_AUTHORITY, 0, 2),
+            DropsetError::InvalidMarketConfig.into_check_failure(),
+        ),
+        (
+            update(MOLLUSK_DEFAULT_MINT_AUTHORITY, TICK, 0),
+            DropsetError::InvalidMarketConfig.into_check_failure(),
+        ),
+        (
+            update(MOLLUSK_DEFAULT_MINT_AUTHORITY, TICK, u8::MAX),
+            DropsetError::InvalidMarketConfig.into_check_failure(),
+        ),
+        (
+            // The maker rebate can't exceed the taker fee.
+            update_with_fees(MOLLUSK_DEFAULT_MINT_AUTHORITY, TICK, 2, 4),
+            DropsetError::InvalidFeeSchedule.into_check_failure(),
+        ),
+        (
+            update(MOLLUSK_DEFAULT_MINT_AUTHORITY, TICK, 2),
+            Check::success(),
+        ),
+    ];
+    let chain_refs: Vec<_> = chain
+        .iter()
+        .map(|(ixn, check)| (ixn, core::slice::from_ref(check)))
+        .collect();
+    mollusk.process_and_validate_instruction_chain(&chain_refs);
+
+    MarketChecker::new(&mollusk, &market_ctx).market_header(|header| {
+        assert_eq!(header.price_mantissa_tick, TICK);
+        assert_eq!(header.max_orders_per_user, 2);
+        assert_eq!(header.fee_recipient, fee_recipient);
+        assert_eq!(header.taker_fee_bps, 10);
+        assert_eq!(header.maker_rebate_bps, 5);
+    });
+
+    Ok(())
+}
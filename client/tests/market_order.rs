@@ -1,20 +1,41 @@
-use client::mollusk_helpers::{
-    market_checker::MarketChecker,
-    new_dropset_mollusk_context_with_default_market,
-    utils::create_mock_user_account,
+use std::collections::HashMap;
+
+use client::{
+    context::market::MarketContext,
+    mollusk_helpers::{
+        checks::IntoCheckFailure,
+        helper_trait::DropsetTestHelper,
+        market_checker::MarketChecker,
+        new_dropset_mollusk_context_with_default_market,
+        utils::create_mock_user_account,
+    },
 };
 use dropset_interface::{
+    error::DropsetError,
     instructions::{
         MarketOrderInstructionData,
+        OrderExpiry,
         PostOrderInstructionData,
+        SeatMarketOrderInstructionData,
+        SelfTradeBehavior,
+        TimeInForce,
     },
+    return_data::TakerReturnData,
     state::sector::NIL,
 };
+use mollusk_svm::{
+    result::Check,
+    MolluskContext,
+};
 use price::{
     to_order_info,
+    EncodedPrice,
+    OrderInfo,
     OrderInfoArgs,
 };
+use solana_account::Account;
 use solana_address::Address;
+use solana_instruction::Instruction;
 
 #[test]
 fn market_order() -> anyhow::Result<()> {
@@ -36,7 +57,16 @@ fn market_order() -> anyhow::Result<()> {
     let maker_deposit_base = market_ctx.deposit_base(maker, market_order.base_atoms, NIL);
     let maker_post_ask = market_ctx.post_order(
         maker,
-        PostOrderInstructionData::new(order_info_args, false, 0), // First seat on the market.
+        // First seat on the market.
+        PostOrderInstructionData::new(
+            order_info_args,
+            false,
+            0,
+            true,
+            OrderExpiry::NONE,
+            SelfTradeBehavior::Abort as u8,
+            0,
+        ),
     );
     // Set up taker: mint quote for the fill, create both ATAs (base to receive, quote to spend).
     let create_taker_base_ata = market_ctx.base.create_ata_idempotent(&taker, &taker);
@@ -61,14 +91,22 @@ fn market_order() -> anyhow::Result<()> {
     check.num_asks(1);
     check.num_bids(0);
 
-    // Market buy: taker buys base_atoms worth of base, spending quote.
-    assert!(mollusk
-        .process_instruction_chain(&[market_ctx.market_order(
-            taker,
-            MarketOrderInstructionData::new(market_order.base_atoms, true, true),
-        )])
-        .program_result
-        .is_ok());
+    // Market buy: taker buys base_atoms worth of base, spending quote. The amounts filled are set
+    // as the instruction's return data.
+    mollusk.process_and_validate_instruction(
+        &market_ctx.unconstrained_market_order(taker, market_order.base_atoms, true, true),
+        &[
+            Check::success(),
+            Check::return_data(
+                &TakerReturnData {
+                    base_filled: market_order.base_atoms,
+                    quote_filled: market_order.quote_atoms,
+                    taker_fee: 0,
+                }
+                .pack(),
+            ),
+        ],
+    );
 
     // Taker should have sent all their quote to receive all the base directly (ATA, not seat).
     check.base_token_balance(taker, market_order.base_atoms);
@@ -85,3 +123,348 @@ fn market_order() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Verifies that a seat market order debits and credits the taker's seat balances without touching
+/// the taker's token accounts, and fails if the taker's seat can't pay for the fill.
+#[test]
+fn seat_market_order() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let order_info_args = OrderInfoArgs::new_unscaled(10_000_000, 500);
+    let order = to_order_info(order_info_args.clone()).expect("Should be a valid order");
+
+    // The maker posts an ask, and the taker deposits one atom less quote than the ask costs.
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&maker, &maker),
+            market_ctx.base.mint_to_owner(&maker, order.base_atoms)?,
+            market_ctx.deposit_base(maker, order.base_atoms, NIL),
+            market_ctx.post_order(
+                maker,
+                PostOrderInstructionData::new(
+                    order_info_args,
+                    false,
+                    0,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0,
+                ),
+            ),
+            market_ctx.quote.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.mint_to_owner(&taker, order.quote_atoms)?,
+            market_ctx.deposit_quote(taker, order.quote_atoms - 1, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let taker_seat_index = mollusk.get_seat(market_ctx.market, taker).index;
+    let buy_all = market_ctx.unconstrained_seat_market_order(
+        taker,
+        order.base_atoms,
+        true,
+        true,
+        taker_seat_index,
+    );
+
+    mollusk.process_and_validate_instruction(
+        &buy_all,
+        &[DropsetError::InsufficientUserBalance.into_check_failure()],
+    );
+
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &market_ctx.deposit_quote(taker, 1, taker_seat_index),
+            &[Check::success()],
+        ),
+        (
+            &buy_all,
+            &[
+                Check::success(),
+                Check::return_data(
+                    &TakerReturnData {
+                        base_filled: order.base_atoms,
+                        quote_filled: order.quote_atoms,
+                        taker_fee: 0,
+                    }
+                    .pack(),
+                ),
+            ],
+        ),
+    ]);
+
+    // The fill is settled entirely through the seats, and the taker never needed a base account.
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.seat_base_available(taker, order.base_atoms);
+    check.seat_quote_available(taker, 0);
+    check.seat_base_available(maker, 0);
+    check.seat_quote_available(maker, order.quote_atoms);
+    check.quote_token_balance(taker, 0);
+
+    // The taker sells the base back through their seat, but the book has no bids to fill against.
+    mollusk.process_and_validate_instruction(
+        &market_ctx.seat_market_order(
+            taker,
+            SeatMarketOrderInstructionData::new(
+                order.base_atoms,
+                false,
+                true,
+                EncodedPrice::zero().as_u32(),
+                1,
+                TimeInForce::ImmediateOrCancel as u8,
+                SelfTradeBehavior::Abort as u8,
+                taker_seat_index,
+            ),
+        ),
+        &[DropsetError::SlippageExceeded.into_check_failure()],
+    );
+
+    Ok(())
+}
+
+/// Posts two asks from a single maker at increasing prices and funds a taker with enough quote to
+/// buy both of them. Returns the order info for the cheaper and the more expensive ask.
+fn setup_two_asks(
+    mollusk: &MolluskContext<HashMap<Address, Account>>,
+    market_ctx: &MarketContext,
+    maker: Address,
+    taker: Address,
+) -> anyhow::Result<(OrderInfo, OrderInfo)> {
+    let cheap_args = OrderInfoArgs::new_unscaled(10_000_000, 500);
+    let expensive_args = OrderInfoArgs::new_unscaled(20_000_000, 500);
+    let cheap = to_order_info(cheap_args.clone()).expect("Should be a valid order");
+    let expensive = to_order_info(expensive_args.clone()).expect("Should be a valid order");
+    let total_base = cheap.base_atoms + expensive.base_atoms;
+    let total_quote = cheap.quote_atoms + expensive.quote_atoms;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&maker, &maker),
+            market_ctx.base.mint_to_owner(&maker, total_base)?,
+            market_ctx.deposit_base(maker, total_base, NIL),
+            market_ctx.post_order(
+                maker,
+                PostOrderInstructionData::new(
+                    cheap_args,
+                    false,
+                    0,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0
+                )
+            ),
+            market_ctx.post_order(
+                maker,
+                PostOrderInstructionData::new(
+                    expensive_args,
+                    false,
+                    0,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0
+                ),
+            ),
+            market_ctx.base.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.mint_to_owner(&taker, total_quote)?,
+        ])
+        .program_result
+        .is_ok());
+
+    Ok((cheap, expensive))
+}
+
+#[test]
+fn market_order_stops_at_limit_price() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let (cheap, expensive) = setup_two_asks(&mollusk, &market_ctx, maker, taker)?;
+
+    // Try to buy both asks, but with a limit price at the cheaper ask. Only the cheaper ask fills.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.market_order(
+            taker,
+            MarketOrderInstructionData::new(
+                cheap.base_atoms + expensive.base_atoms,
+                true,
+                true,
+                cheap.encoded_price.as_u32(),
+                cheap.base_atoms,
+                TimeInForce::ImmediateOrCancel as u8,
+                SelfTradeBehavior::Abort as u8,
+            ),
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.base_token_balance(taker, cheap.base_atoms);
+    check.quote_token_balance(taker, expensive.quote_atoms);
+    check.seat_quote_available(maker, cheap.quote_atoms);
+    check.num_asks(1);
+    check.asks(|asks| assert_eq!(asks[0].encoded_price, expensive.encoded_price));
+
+    Ok(())
+}
+
+#[test]
+fn market_order_below_min_amount_out_fails() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let (cheap, expensive) = setup_two_asks(&mollusk, &market_ctx, maker, taker)?;
+    let total_base = cheap.base_atoms + expensive.base_atoms;
+
+    let buy_with_limit = |limit_price: u32| {
+        market_ctx.market_order(
+            taker,
+            MarketOrderInstructionData::new(
+                total_base,
+                true,
+                true,
+                limit_price,
+                total_base,
+                TimeInForce::ImmediateOrCancel as u8,
+                SelfTradeBehavior::Abort as u8,
+            ),
+        )
+    };
+
+    let chain = [
+        // The limit price stops the fill after the cheaper ask, so the taker receives too little.
+        (
+            buy_with_limit(cheap.encoded_price.as_u32()),
+            [DropsetError::SlippageExceeded.into_check_failure()],
+        ),
+        // A limit price at the more expensive ask lets the whole order fill.
+        (
+            buy_with_limit(expensive.encoded_price.as_u32()),
+            [Check::success()],
+        ),
+    ];
+    let chain_refs: Vec<_> = chain.iter().map(|(i, c)| (i, c.as_slice())).collect();
+    mollusk.process_and_validate_instruction_chain(&chain_refs);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.base_token_balance(taker, total_base);
+    check.quote_token_balance(taker, 0);
+    check.num_asks(0);
+
+    Ok(())
+}
+
+/// Builds an unconstrained market buy denominated in base with the given time-in-force.
+fn market_buy(
+    market_ctx: &MarketContext,
+    taker: Address,
+    base_atoms: u64,
+    time_in_force: u8,
+) -> Instruction {
+    market_ctx.market_order(
+        taker,
+        MarketOrderInstructionData::new(
+            base_atoms,
+            true,
+            true,
+            EncodedPrice::infinity().as_u32(),
+            0,
+            time_in_force,
+            SelfTradeBehavior::Abort as u8,
+        ),
+    )
+}
+
+#[test]
+fn fill_or_kill_fails_when_book_is_too_thin() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let (cheap, expensive) = setup_two_asks(&mollusk, &market_ctx, maker, taker)?;
+    let total_base = cheap.base_atoms + expensive.base_atoms;
+    let fok = TimeInForce::FillOrKill as u8;
+
+    let chain = [
+        // Asking for one more atom than the book has fails the fill-or-kill order.
+        (
+            market_buy(&market_ctx, taker, total_base + 1, fok),
+            [DropsetError::FillOrKillNotFilled.into_check_failure()],
+        ),
+        // An unknown time-in-force discriminant is rejected.
+        (
+            market_buy(&market_ctx, taker, total_base, u8::MAX),
+            [DropsetError::InvalidTimeInForce.into_check_failure()],
+        ),
+    ];
+    let chain_refs: Vec<_> = chain.iter().map(|(i, c)| (i, c.as_slice())).collect();
+    mollusk.process_and_validate_instruction_chain(&chain_refs);
+
+    // Nothing was filled, so the book and the taker's balances are untouched.
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(2);
+    check.base_token_balance(taker, 0);
+    check.quote_token_balance(taker, cheap.quote_atoms + expensive.quote_atoms);
+
+    // Asking for exactly what the book has fills the fill-or-kill order completely.
+    assert!(mollusk
+        .process_instruction_chain(&[market_buy(&market_ctx, taker, total_base, fok)])
+        .program_result
+        .is_ok());
+
+    check.num_asks(0);
+    check.base_token_balance(taker, total_base);
+    check.quote_token_balance(taker, 0);
+
+    Ok(())
+}
+
+#[test]
+fn immediate_or_cancel_partially_fills_when_book_is_too_thin() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let (cheap, expensive) = setup_two_asks(&mollusk, &market_ctx, maker, taker)?;
+    let total_base = cheap.base_atoms + expensive.base_atoms;
+
+    // The unfilled remainder of the immediate-or-cancel order is simply dropped.
+    assert!(mollusk
+        .process_instruction_chain(&[market_buy(
+            &market_ctx,
+            taker,
+            total_base * 2,
+            TimeInForce::ImmediateOrCancel as u8,
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.base_token_balance(taker, total_base);
+    check.quote_token_balance(taker, 0);
+
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
+    helper_trait::DropsetTestHelper,
+    market_checker::MarketChecker,
+    new_dropset_mollusk_context_with_default_market,
+    utils::{
+        create_mock_user_account,
+        without_signer,
+    },
+    MOLLUSK_DEFAULT_MINT_AUTHORITY,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        BatchReplaceInstructionData,
+        BatchReplaceMode,
+        CancelOrderInstructionData,
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
+    state::{
+        market_status::MarketStatus,
+        sector::NIL,
+    },
+};
+use mollusk_svm::result::Check;
+use price::{
+    to_order_info,
+    OrderInfoArgs,
+};
+use solana_address::Address;
+use solana_program_error::ProgramError;
+
+/// Walks the market through each status and verifies which order book instructions each status
+/// allows.
+#[test]
+fn market_status_gates_instructions() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, 1_000_000_000)?,
+            market_ctx.deposit_base(user, 500_000_000, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat = mollusk.get_seat(market_ctx.market, user).index;
+    let ask_args = |price_mantissa: u32| OrderInfoArgs::new_unscaled(price_mantissa, 500);
+    let post_ask = |price_mantissa: u32| {
+        market_ctx.post_order(
+            user,
+            PostOrderInstructionData::new(
+                ask_args(price_mantissa),
+                false,
+                seat,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
+        )
+    };
+    let cancel_ask = |price_mantissa: u32| {
+        let encoded_price = to_order_info(ask_args(price_mantissa))
+            .expect("Should be a valid order")
+            .encoded_price
+            .as_u32();
+        market_ctx.cancel_order(
+            user,
+            CancelOrderInstructionData::new(encoded_price, false, seat),
+        )
+    };
+    let cancel_all = market_ctx.batch_replace(
+        user,
+        BatchReplaceInstructionData::new(seat, BatchReplaceMode::Replace as u8),
+        &[],
+        &[],
+    );
+    let replace_asks = market_ctx.batch_replace(
+        user,
+        BatchReplaceInstructionData::new(seat, BatchReplaceMode::Replace as u8),
+        &[],
+        &[ask_args(12_000_000).into()],
+    );
+    let set_status =
+        |status: MarketStatus| market_ctx.set_market_status(MOLLUSK_DEFAULT_MINT_AUTHORITY, status);
+    let forbidden = || DropsetError::MarketStatusForbidsInstruction.into_check_failure();
+
+    let chain = [
+        (post_ask(10_000_000), Check::success()),
+        (post_ask(11_000_000), Check::success()),
+        // Only the market authority can change the market status.
+        (
+            market_ctx.set_market_status(user, MarketStatus::Paused),
+            DropsetError::IncorrectMarketAuthority.into_check_failure(),
+        ),
+        (
+            without_signer(
+                set_status(MarketStatus::Closed),
+                &MOLLUSK_DEFAULT_MINT_AUTHORITY,
+            ),
+            Check::err(ProgramError::MissingRequiredSignature),
+        ),
+        // Cancel only: orders can be canceled but not posted or taken.
+        (set_status(MarketStatus::CancelOnly), Check::success()),
+        (post_ask(12_000_000), forbidden()),
+        (replace_asks.clone(), forbidden()),
+        (
+            market_ctx.unconstrained_market_order(user, 100, false, true),
+            forbidden(),
+        ),
+        (cancel_ask(10_000_000), Check::success()),
+        // Paused: the order book is frozen, but deposits are still allowed.
+        (set_status(MarketStatus::Paused), Check::success()),
+        (cancel_ask(11_000_000), forbidden()),
+        (cancel_all.clone(), forbidden()),
+        (post_ask(12_000_000), forbidden()),
+        (market_ctx.deposit_base(user, 1, seat), Check::success()),
+        // Active: everything is allowed again.
+        (set_status(MarketStatus::Active), Check::success()),
+        (replace_asks, Check::success()),
+        // Closed: deposits are rejected, orders can still be canceled, and the status is final.
+        (set_status(MarketStatus::Closed), Check::success()),
+        (market_ctx.deposit_base(user, 1, seat), forbidden()),
+        (post_ask(10_000_000), forbidden()),
+        (cancel_all, Check::success()),
+        (
+            set_status(MarketStatus::Active),
+            DropsetError::MarketIsClosed.into_check_failure(),
+        ),
+    ];
+    let chain_refs: Vec<_> = chain
+        .iter()
+        .map(|(ixn, check)| (ixn, core::slice::from_ref(check)))
+        .collect();
+    mollusk.process_and_validate_instruction_chain(&chain_refs);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.market_header(|header| assert_eq!(header.status, MarketStatus::Closed));
+
+    Ok(())
+}
+
+/// Verifies that a market can only be closed once it's closed and empty, and that closing it
+/// returns the rent for the market account and its token accounts to the market authority.
+#[test]
+fn close_market() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx
+                .base
+                .create_ata_idempotent(&user, &MOLLUSK_DEFAULT_MINT_AUTHORITY),
+            market_ctx
+                .quote
+                .create_ata_idempotent(&user, &MOLLUSK_DEFAULT_MINT_AUTHORITY),
+            market_ctx.base.mint_to_owner(&user, 1_000)?,
+            market_ctx.deposit_base(user, 1_000, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat = mollusk.get_seat(market_ctx.market, user).index;
+    let close_market = market_ctx.close_market(MOLLUSK_DEFAULT_MINT_AUTHORITY);
+
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &close_market,
+            &[DropsetError::MarketStatusForbidsInstruction.into_check_failure()],
+        ),
+        (
+            &market_ctx.set_market_status(MOLLUSK_DEFAULT_MINT_AUTHORITY, MarketStatus::Closed),
+            &[Check::success()],
+        ),
+        (
+            &close_market,
+            &[DropsetError::MarketNotEmpty.into_check_failure()],
+        ),
+        (
+            &market_ctx.close_market(user),
+            &[DropsetError::IncorrectMarketAuthority.into_check_failure()],
+        ),
+        (&market_ctx.close_seat(user, seat), &[Check::success()]),
+        // The market is now closable, but only with the authority's signature.
+        (
+            &without_signer(close_market.clone(), &MOLLUSK_DEFAULT_MINT_AUTHORITY),
+            &[Check::err(ProgramError::MissingRequiredSignature)],
+        ),
+    ]);
+
+    let lamports = |address: &Address| {
+        mollusk
+            .account_store
+            .borrow()
+            .get(address)
+            .map_or(0, |account| account.lamports)
+    };
+    let rent = lamports(&market_ctx.market)
+        + lamports(&market_ctx.base_market_ata)
+        + lamports(&market_ctx.quote_market_ata);
+    let authority_lamports_before = lamports(&MOLLUSK_DEFAULT_MINT_AUTHORITY);
+
+    assert!(mollusk
+        .process_instruction_chain(&[close_market])
+        .program_result
+        .is_ok());
+
+    assert_eq!(lamports(&market_ctx.market), 0);
+    assert_eq!(lamports(&market_ctx.base_market_ata), 0);
+    assert_eq!(lamports(&market_ctx.quote_market_ata), 0);
+    assert_eq!(
+        lamports(&MOLLUSK_DEFAULT_MINT_AUTHORITY),
+        authority_lamports_before + rent
+    );
+
+    Ok(())
+}
+
+/// Verifies that tokens sent directly to the market's token accounts, which no seat is owed, are
+/// swept to the market authority's token accounts when the market is closed.
+#[test]
+fn close_market_sweeps_leftover_tokens() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+    let authority = MOLLUSK_DEFAULT_MINT_AUTHORITY;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &authority),
+            market_ctx.quote.create_ata_idempotent(&user, &authority),
+            market_ctx
+                .base
+                .mint_to_ata(&market_ctx.base_market_ata, 123)?,
+            market_ctx
+                .quote
+                .mint_to_ata(&market_ctx.quote_market_ata, 456)?,
+            market_ctx.set_market_status(authority, MarketStatus::Closed),
+        ])
+        .program_result
+        .is_ok());
+
+    assert_eq!(
+        mollusk.get_token_balance(market_ctx.market, market_ctx.base.mint_address),
+        123
+    );
+    assert_eq!(
+        mollusk.get_token_balance(market_ctx.market, market_ctx.quote.mint_address),
+        456
+    );
+
+    mollusk
+        .process_and_validate_instruction(&market_ctx.close_market(authority), &[Check::success()]);
+
+    assert_eq!(
+        mollusk.get_token_balance(authority, market_ctx.base.mint_address),
+        123
+    );
+    assert_eq!(
+        mollusk.get_token_balance(authority, market_ctx.quote.mint_address),
+        456
+    );
+    assert_eq!(
+        mollusk
+            .account_store
+            .borrow()
+            .get(&market_ctx.base_market_ata)
+            .map_or(0, |account| account.lamports),
+        0
+    );
+
+    Ok(())
+}
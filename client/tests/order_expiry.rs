@@ -0,0 +1,219 @@
+use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
+    market_checker::MarketChecker,
+    new_dropset_mollusk_context_with_default_market,
+    utils::create_mock_user_account,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
+    state::sector::NIL,
+};
+use mollusk_svm::result::Check;
+use price::{
+    to_order_info,
+    OrderInfoArgs,
+};
+use solana_address::Address;
+
+const EXPIRY_SLOT: u64 = 100;
+
+#[test]
+fn expired_orders_are_reaped_instead_of_filled() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let (mut mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[maker_mock, taker_mock]);
+
+    let expiring_args = OrderInfoArgs::new_unscaled(10_000_000, 500);
+    let resting_args = OrderInfoArgs::new_unscaled(20_000_000, 500);
+    let expiring = to_order_info(expiring_args.clone()).expect("Should be a valid order");
+    let resting = to_order_info(resting_args.clone()).expect("Should be a valid order");
+    let total_base = expiring.base_atoms + resting.base_atoms;
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&maker, &maker),
+            market_ctx.base.mint_to_owner(&maker, total_base)?,
+            market_ctx.deposit_base(maker, total_base, NIL),
+            market_ctx.base.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.create_ata_idempotent(&taker, &taker),
+            market_ctx
+                .quote
+                .mint_to_owner(&taker, resting.quote_atoms)?,
+        ])
+        .program_result
+        .is_ok());
+
+    let post_ask = |args: OrderInfoArgs, expiry: OrderExpiry| {
+        market_ctx.post_order(
+            maker,
+            PostOrderInstructionData::new(
+                args,
+                false,
+                0,
+                true,
+                expiry,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
+        )
+    };
+
+    mollusk.process_and_validate_instruction_chain(&[
+        // An order can't be posted with an expiry that has already passed.
+        (
+            &post_ask(expiring_args.clone(), OrderExpiry::at_slot(0)),
+            &[DropsetError::OrderAlreadyExpired.into_check_failure()],
+        ),
+        (
+            &post_ask(
+                expiring_args.clone(),
+                OrderExpiry {
+                    kind: 3,
+                    expires_at: EXPIRY_SLOT,
+                },
+            ),
+            &[DropsetError::InvalidExpiryKind.into_check_failure()],
+        ),
+        (
+            &post_ask(expiring_args, OrderExpiry::at_slot(EXPIRY_SLOT)),
+            &[Check::success()],
+        ),
+        (
+            &post_ask(resting_args, OrderExpiry::NONE),
+            &[Check::success()],
+        ),
+    ]);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(2);
+    check.seat_base_available(maker, 0);
+    check.asks(|asks| {
+        assert_eq!(asks[0].expiry, OrderExpiry::at_slot(EXPIRY_SLOT));
+        assert_eq!(asks[1].expiry, OrderExpiry::NONE);
+    });
+
+    mollusk.mollusk.warp_to_slot(EXPIRY_SLOT);
+
+    // The taker can only afford the more expensive ask. The cheaper ask is at the top of the book
+    // but has expired, so it's removed instead of filled.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.unconstrained_market_order(
+            taker,
+            resting.quote_atoms,
+            true,
+            false,
+        )])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.base_token_balance(taker, resting.base_atoms);
+    check.quote_token_balance(taker, 0);
+    // The expired ask's collateral is returned to the maker's seat.
+    check.seat_base_available(maker, expiring.base_atoms);
+    check.seat_quote_available(maker, resting.quote_atoms);
+    check.seat(maker, |seat| {
+        assert_eq!(seat.user_order_sectors.asks.len(), 0);
+    });
+
+    Ok(())
+}
+
+#[test]
+fn prune_expired_orders() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let (mut mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[maker_mock]);
+
+    let bid_args = [
+        OrderInfoArgs::new_unscaled(13_000_000, 1),
+        OrderInfoArgs::new_unscaled(12_000_000, 1),
+        OrderInfoArgs::new_unscaled(11_000_000, 1),
+    ];
+    let bids = bid_args
+        .iter()
+        .map(|args| to_order_info(args.clone()).expect("Should be a valid order"))
+        .collect::<Vec<_>>();
+    let total_quote = bids.iter().map(|bid| bid.quote_atoms).sum::<u64>();
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.quote.create_ata_idempotent(&maker, &maker),
+            market_ctx.quote.mint_to_owner(&maker, total_quote)?,
+            market_ctx.deposit_quote(maker, total_quote, NIL),
+        ])
+        .program_result
+        .is_ok());
+
+    // The first and last bids expire; the middle bid never does.
+    let expiries = [
+        OrderExpiry::at_slot(EXPIRY_SLOT),
+        OrderExpiry::NONE,
+        OrderExpiry::at_slot(EXPIRY_SLOT),
+    ];
+    let posts = bid_args
+        .into_iter()
+        .zip(expiries)
+        .map(|(args, expiry)| {
+            market_ctx.post_order(
+                maker,
+                PostOrderInstructionData::new(
+                    args,
+                    true,
+                    0,
+                    true,
+                    expiry,
+                    SelfTradeBehavior::Abort as u8,
+                    0,
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert!(mollusk
+        .process_instruction_chain(&posts)
+        .program_result
+        .is_ok());
+
+    // Nothing has expired yet, so pruning is a no-op.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.prune_expired_orders(u16::MAX)])
+        .program_result
+        .is_ok());
+    MarketChecker::new(&mollusk, &market_ctx).num_bids(3);
+
+    mollusk.mollusk.warp_to_slot(EXPIRY_SLOT);
+
+    // Prune a single expired order at a time. Anyone can prune, so no signer is needed.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.prune_expired_orders(1)])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(2);
+    check.seat_quote_available(maker, bids[0].quote_atoms);
+
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.prune_expired_orders(u16::MAX)])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_bids(1);
+    check.bids(|bids_left| assert_eq!(bids_left[0].encoded_price, bids[1].encoded_price));
+    check.seat_quote_available(maker, bids[0].quote_atoms + bids[2].quote_atoms);
+    check.seat(maker, |seat| {
+        assert_eq!(seat.user_order_sectors.bids.len(), 1);
+    });
+
+    Ok(())
+}
@@ -1,21 +1,32 @@
 use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
     helper_trait::DropsetTestHelper,
     market_checker::MarketChecker,
     new_dropset_mollusk_context_with_default_market,
     utils::create_mock_user_account,
+    MOLLUSK_DEFAULT_MINT_AUTHORITY,
 };
 use dropset_interface::{
+    error::DropsetError,
     instructions::{
         CancelOrderInstructionData,
+        OrderExpiry,
         PostOrderInstructionData,
+        SelfTradeBehavior,
     },
-    state::sector::{
-        MAX_PERMITTED_SECTOR_INCREASE,
-        NIL,
+    state::{
+        sector::{
+            Sector,
+            MAX_PERMITTED_SECTOR_INCREASE,
+            NIL,
+        },
+        transmutable::Transmutable,
     },
 };
 use itertools::Itertools;
+use mollusk_svm::result::Check;
 use price::{
+    client_helpers::sum_base_necessary,
     to_order_info,
     OrderInfoArgs,
 };
@@ -56,7 +67,15 @@ fn post_and_cancel() -> anyhow::Result<()> {
     assert!(mollusk
         .process_instruction_chain(&[market_ctx.post_order(
             user,
-            PostOrderInstructionData::new(order_info_args, is_bid, seat_index),
+            PostOrderInstructionData::new(
+                order_info_args,
+                is_bid,
+                seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0
+            ),
         )])
         .program_result
         .is_ok());
@@ -119,7 +138,15 @@ fn post_and_cancel_maintains_sort_order() -> anyhow::Result<()> {
     let post_order = |price: u32, is_bid: bool| {
         market_ctx.post_order(
             user,
-            PostOrderInstructionData::new(OrderInfoArgs::order_at_price(price), is_bid, seat_index),
+            PostOrderInstructionData::new(
+                OrderInfoArgs::order_at_price(price),
+                is_bid,
+                seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
         )
     };
     let post_bid = |price: u32| post_order(price, true);
@@ -219,3 +246,59 @@ fn post_and_cancel_maintains_sort_order() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Verifies that posting an order on a market with no free sectors fails without an expansion
+/// payer, and that passing one expands the market by a single sector instead.
+#[test]
+fn post_order_auto_expand() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+
+    let ask_args = OrderInfoArgs::new_unscaled(12_000_000, 1);
+    let base_deposited = sum_base_necessary(&[ask_args.clone()])?;
+
+    // Create the user's seat, then compact the market so that it has no free sectors left.
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, base_deposited)?,
+            market_ctx.deposit_base(user, base_deposited, NIL),
+            market_ctx.compact_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, 0),
+        ])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.market_header(|header| assert_eq!(header.num_free_sectors, 0));
+
+    let seat = mollusk.get_seat(market_ctx.market, user).index;
+    let data = PostOrderInstructionData::new(
+        ask_args,
+        false,
+        seat,
+        true,
+        OrderExpiry::NONE,
+        SelfTradeBehavior::Abort as u8,
+        0,
+    );
+
+    let data_len_before = mollusk.view_market_data(market_ctx.market).len();
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &market_ctx.post_order(user, data.clone()),
+            &[DropsetError::NoFreeSectorsRemaining.into_check_failure()],
+        ),
+        (
+            &market_ctx.post_order_with_expansion_payer(user, user, data),
+            &[Check::success()],
+        ),
+    ]);
+    let data_len_after = mollusk.view_market_data(market_ctx.market).len();
+
+    check.num_asks(1);
+    check.market_header(|header| assert_eq!(header.num_free_sectors, 0));
+    assert_eq!(data_len_after - data_len_before, Sector::LEN);
+
+    Ok(())
+}
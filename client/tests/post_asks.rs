@@ -5,7 +5,11 @@ use client::mollusk_helpers::{
     utils::create_mock_user_account,
 };
 use dropset_interface::{
-    instructions::PostOrderInstructionData,
+    instructions::{
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
     state::sector::NIL,
 };
 use itertools::Itertools;
@@ -45,6 +49,10 @@ fn post_asks() -> anyhow::Result<()> {
                     OrderInfoArgs::new_unscaled(10_000_000 + i, 100),
                     is_bid,
                     seat.index,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0,
                 ),
             )
         })
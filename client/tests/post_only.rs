@@ -9,7 +9,9 @@ use dropset_interface::{
     error::DropsetError,
     instructions::{
         CancelOrderInstructionData,
+        OrderExpiry,
         PostOrderInstructionData,
+        SelfTradeBehavior,
     },
     state::sector::NIL,
 };
@@ -57,7 +59,15 @@ fn post_only_crossing_check() -> anyhow::Result<()> {
     let post = |price, is_bid| {
         market_ctx.post_order(
             user,
-            PostOrderInstructionData::new(OrderInfoArgs::order_at_price(price), is_bid, seat.index),
+            PostOrderInstructionData::new(
+                OrderInfoArgs::order_at_price(price),
+                is_bid,
+                seat.index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
         )
     };
     let fail = || [DropsetError::PostOnlyWouldImmediatelyFill.into_check_failure()];
@@ -106,8 +116,15 @@ fn crossing_check_clears_with_cancel() -> anyhow::Result<()> {
     let seat = mollusk.get_seat(market_ctx.market, user);
 
     let post = |price, is_bid| {
-        let data =
-            PostOrderInstructionData::new(OrderInfoArgs::order_at_price(price), is_bid, seat.index);
+        let data = PostOrderInstructionData::new(
+            OrderInfoArgs::order_at_price(price),
+            is_bid,
+            seat.index,
+            true,
+            OrderExpiry::NONE,
+            SelfTradeBehavior::Abort as u8,
+            0,
+        );
         market_ctx.post_order(user, data)
     };
     let post_bid = |price| post(price, true);
@@ -188,6 +205,10 @@ fn crossing_check_across_users() -> anyhow::Result<()> {
                 OrderInfoArgs::order_at_price(50_000_000),
                 false,
                 seat_a.index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
             ),
         ),
         &[Check::success()],
@@ -200,6 +221,10 @@ fn crossing_check_across_users() -> anyhow::Result<()> {
                 OrderInfoArgs::order_at_price(price),
                 is_bid,
                 seat_b.index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
             ),
         )
     };
@@ -282,7 +307,15 @@ fn price_time_priority() -> anyhow::Result<()> {
     let post_ask = |price: u32, user: Address, seat_index: u32| {
         market_ctx.post_order(
             user,
-            PostOrderInstructionData::new(OrderInfoArgs::order_at_price(price), false, seat_index),
+            PostOrderInstructionData::new(
+                OrderInfoArgs::order_at_price(price),
+                false,
+                seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
+            ),
         )
     };
     let post_ask_a = |price: u32| post_ask(price, user_a, seat_a.index);
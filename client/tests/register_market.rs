@@ -15,11 +15,13 @@ use dropset_interface::state::{
         MarketHeader,
         MARKET_ACCOUNT_DISCRIMINANT,
     },
+    market_status::MarketStatus,
     sector::{
         Sector,
         NIL,
     },
     transmutable::Transmutable,
+    user_order_sectors::MAX_ORDERS,
 };
 use mollusk_svm::result::Check;
 use solana_address::Address;
@@ -73,6 +75,7 @@ fn register_market() -> anyhow::Result<()> {
     let (_, bump) = find_market_address(
         &market_ctx.base.mint_address,
         &market_ctx.quote.mint_address,
+        market_ctx.market_id,
     );
 
     check.num_asks(0);
@@ -97,8 +100,19 @@ fn register_market() -> anyhow::Result<()> {
                 base_mint: market_ctx.base.mint_address,
                 quote_mint: market_ctx.quote.mint_address,
                 market_bump: bump,
+                market_id: 0,
                 nonce: 1, // The register market event.
-                _padding: [0, 0, 0],
+                fee_recipient: funder,
+                taker_fee_bps: 0,
+                maker_rebate_bps: 0,
+                base_fees_accrued: 0,
+                quote_fees_accrued: 0,
+                authority: funder,
+                min_order_base_atoms: 0,
+                price_mantissa_tick: 1,
+                max_orders_per_user: MAX_ORDERS,
+                status: MarketStatus::Active,
+                _padding: [0; 7],
             }
         );
     });
@@ -117,3 +131,64 @@ fn register_market() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn register_multiple_markets_per_pair() -> anyhow::Result<()> {
+    let mock_funder = create_mock_user_account(Address::new_unique(), 100_000_000_000);
+    let funder = mock_funder.0;
+    let mollusk = new_dropset_mollusk_context(vec![mock_funder]);
+    let (base_mint, quote_mint) = (Address::new_unique(), Address::new_unique());
+    let market_ctx_for_id = |market_id: u16| {
+        MarketContext::new_with_market_id(
+            TokenContext::new(Some(funder), base_mint, SPL_TOKEN_ID, 8),
+            TokenContext::new(Some(funder), quote_mint, SPL_TOKEN_ID, 8),
+            market_id,
+        )
+    };
+    let market_0 = market_ctx_for_id(0);
+    let market_1 = market_ctx_for_id(1);
+    assert_ne!(market_0.market, market_1.market);
+    assert_ne!(market_0.base_market_ata, market_1.base_market_ata);
+
+    assert!(mollusk
+        .process_instruction_chain(
+            &[
+                market_0
+                    .create_tokens(funder, Rent::default().minimum_balance(Mint::LEN))
+                    .expect("Should create token instructions"),
+                vec![
+                    market_0.register_market(funder, 10),
+                    market_1.register_market(funder, 10),
+                ],
+            ]
+            .concat(),
+        )
+        .program_result
+        .is_ok());
+
+    for market_ctx in [&market_0, &market_1] {
+        let (_, bump) = find_market_address(&base_mint, &quote_mint, market_ctx.market_id);
+        MarketChecker::new(&mollusk, market_ctx).market_header(|header| {
+            assert_eq!(header.market_id, market_ctx.market_id);
+            assert_eq!(header.market_bump, bump);
+        });
+    }
+
+    // Withdrawing from the second market requires the program to sign with its market ID seed.
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_1.base.create_ata_idempotent(&funder, &funder),
+            market_1.base.mint_to_owner(&funder, 1_000)?,
+            market_1.deposit_base(funder, 1_000, NIL),
+            market_1.withdraw_base(funder, 400, 0),
+        ])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_1);
+    check.seat_base_available(funder, 600);
+    check.base_token_balance(funder, 400);
+    MarketChecker::new(&mollusk, &market_0).num_seats(0);
+
+    Ok(())
+}
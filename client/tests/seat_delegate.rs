@@ -0,0 +1,150 @@
+use client::mollusk_helpers::{
+    checks::IntoCheckFailure,
+    helper_trait::DropsetTestHelper,
+    market_checker::MarketChecker,
+    new_dropset_mollusk_context_with_default_market,
+    utils::{
+        create_mock_user_account,
+        without_signer,
+    },
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        CancelOrderInstructionData,
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
+    state::sector::NIL,
+};
+use mollusk_svm::result::Check;
+use price::{
+    to_order_info,
+    OrderInfoArgs,
+};
+use solana_address::Address;
+use solana_program_error::ProgramError;
+
+/// Verifies that a seat's delegate can post and cancel orders for the seat, but can't withdraw,
+/// close the seat or change the delegate, and that removing the delegate revokes its access.
+#[test]
+fn seat_delegate() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let delegate_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let delegate = delegate_mock.0;
+    let (mollusk, market_ctx) =
+        new_dropset_mollusk_context_with_default_market(&[user_mock, delegate_mock]);
+
+    let order_info_args = OrderInfoArgs::new_unscaled(10_000_000, 500);
+    let order = to_order_info(order_info_args.clone()).expect("Should be a valid order");
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, order.base_atoms)?,
+            market_ctx.deposit_base(user, order.base_atoms, NIL),
+            market_ctx.base.create_ata_idempotent(&delegate, &delegate),
+            market_ctx.quote.create_ata_idempotent(&delegate, &delegate),
+        ])
+        .program_result
+        .is_ok());
+
+    let seat_index = mollusk.get_seat(market_ctx.market, user).index;
+    let post_ask = market_ctx.post_order(
+        delegate,
+        PostOrderInstructionData::new(
+            order_info_args,
+            false,
+            seat_index,
+            true,
+            OrderExpiry::NONE,
+            SelfTradeBehavior::Abort as u8,
+            0,
+        ),
+    );
+    let cancel_ask = market_ctx.cancel_order(
+        delegate,
+        CancelOrderInstructionData::new(order.encoded_price.as_u32(), false, seat_index),
+    );
+
+    // The delegate can't trade for the seat before it's set.
+    mollusk.process_and_validate_instruction(
+        &post_ask,
+        &[DropsetError::InvalidIndexHint.into_check_failure()],
+    );
+
+    let set_delegate = market_ctx.set_seat_delegate(user, seat_index, Some(delegate));
+    mollusk.process_and_validate_instruction_chain(&[
+        // The seat's user must sign to set the delegate.
+        (
+            &without_signer(set_delegate.clone(), &user),
+            &[Check::err(ProgramError::MissingRequiredSignature)],
+        ),
+        (&set_delegate, &[Check::success()]),
+        // Only the seat's user can change the delegate.
+        (
+            &market_ctx.set_seat_delegate(delegate, seat_index, None),
+            &[DropsetError::InvalidIndexHint.into_check_failure()],
+        ),
+    ]);
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.seat(user, |seat| assert_eq!(seat.delegate, Some(delegate)));
+
+    // The delegate must sign to trade for the seat.
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &without_signer(post_ask.clone(), &delegate),
+            &[Check::err(ProgramError::MissingRequiredSignature)],
+        ),
+        (
+            &without_signer(cancel_ask.clone(), &delegate),
+            &[Check::err(ProgramError::MissingRequiredSignature)],
+        ),
+    ]);
+    check.num_asks(0);
+
+    // The delegate posts an ask for the seat, which belongs to the seat's user.
+    assert!(mollusk
+        .process_instruction_chain(&[post_ask.clone()])
+        .program_result
+        .is_ok());
+    check.num_asks(1);
+    check.seat_base_available(user, 0);
+    check.asks(|asks| assert_eq!(asks[0].user_seat, seat_index));
+
+    // The delegate can't withdraw from or close the seat.
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &market_ctx.withdraw_base(delegate, 1, seat_index),
+            &[DropsetError::InvalidIndexHint.into_check_failure()],
+        ),
+        (
+            &market_ctx.close_seat(delegate, seat_index),
+            &[DropsetError::InvalidIndexHint.into_check_failure()],
+        ),
+    ]);
+
+    // The delegate cancels the ask, returning the collateral to the user's seat.
+    assert!(mollusk
+        .process_instruction_chain(&[cancel_ask])
+        .program_result
+        .is_ok());
+    check.num_asks(0);
+    check.seat_base_available(user, order.base_atoms);
+
+    // Removing the delegate revokes its access to the seat.
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.set_seat_delegate(user, seat_index, None)])
+        .program_result
+        .is_ok());
+    check.seat(user, |seat| assert_eq!(seat.delegate, None));
+    mollusk.process_and_validate_instruction(
+        &post_ask,
+        &[DropsetError::InvalidIndexHint.into_check_failure()],
+    );
+
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use client::{
+    context::market::MarketContext,
+    mollusk_helpers::{
+        checks::IntoCheckFailure,
+        market_checker::MarketChecker,
+        new_dropset_mollusk_context_with_default_market,
+        utils::create_mock_user_account,
+    },
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        OrderExpiry,
+        PostOrderInstructionData,
+        SeatMarketOrderInstructionData,
+        SelfTradeBehavior,
+        TimeInForce,
+    },
+    state::sector::NIL,
+};
+use mollusk_svm::{
+    result::Check,
+    MolluskContext,
+};
+use price::{
+    to_order_info,
+    EncodedPrice,
+    OrderInfo,
+    OrderInfoArgs,
+};
+use solana_account::Account;
+use solana_address::Address;
+use solana_instruction::Instruction;
+
+fn order_args() -> OrderInfoArgs {
+    OrderInfoArgs::new_unscaled(11_000_000, 1)
+}
+
+fn post_order(
+    market_ctx: &MarketContext,
+    user: Address,
+    is_bid: bool,
+    post_only: bool,
+    self_trade_behavior: SelfTradeBehavior,
+) -> Instruction {
+    market_ctx.post_order(
+        user,
+        PostOrderInstructionData::new(
+            order_args(),
+            is_bid,
+            0,
+            post_only,
+            OrderExpiry::NONE,
+            self_trade_behavior as u8,
+            0,
+        ),
+    )
+}
+
+/// Posts a resting ask for `user` and leaves enough quote in their seat to post a bid of the same
+/// size and price.
+fn setup_resting_ask(
+    mollusk: &MolluskContext<HashMap<Address, Account>>,
+    market_ctx: &MarketContext,
+    user: Address,
+) -> anyhow::Result<OrderInfo> {
+    let order = to_order_info(order_args()).expect("Should be a valid order");
+
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&user, &user),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.base.mint_to_owner(&user, order.base_atoms)?,
+            market_ctx.quote.mint_to_owner(&user, order.quote_atoms)?,
+            market_ctx.deposit_base(user, order.base_atoms, NIL),
+            market_ctx.deposit_quote(user, order.quote_atoms, 0),
+            post_order(market_ctx, user, false, true, SelfTradeBehavior::Abort),
+        ])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(mollusk, market_ctx);
+    check.num_asks(1);
+    check.seat_base_available(user, 0);
+    check.seat_quote_available(user, order.quote_atoms);
+
+    Ok(order)
+}
+
+#[test]
+fn self_trade_abort_and_cancel_resting() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+    let order = setup_resting_ask(&mollusk, &market_ctx, user)?;
+
+    mollusk.process_and_validate_instruction_chain(&[
+        (
+            &post_order(&market_ctx, user, true, false, SelfTradeBehavior::Abort),
+            &[DropsetError::SelfTradeAborted.into_check_failure()],
+        ),
+        (
+            &post_order(
+                &market_ctx,
+                user,
+                true,
+                false,
+                SelfTradeBehavior::CancelResting,
+            ),
+            &[Check::success()],
+        ),
+    ]);
+
+    // The resting ask was canceled instead of filled, so the bid is posted in full.
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.num_bids(1);
+    check.seat_base_available(user, order.base_atoms);
+    check.seat_quote_available(user, 0);
+    check.seat(user, |seat| {
+        assert_eq!(seat.user_order_sectors.asks.len(), 0);
+        assert_eq!(seat.user_order_sectors.bids.len(), 1);
+    });
+
+    Ok(())
+}
+
+#[test]
+fn self_trade_decrement_both() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+    let order = setup_resting_ask(&mollusk, &market_ctx, user)?;
+
+    assert!(mollusk
+        .process_instruction_chain(&[post_order(
+            &market_ctx,
+            user,
+            true,
+            false,
+            SelfTradeBehavior::DecrementBoth,
+        )])
+        .program_result
+        .is_ok());
+
+    // Both orders were decremented by the full size, so the ask is removed, the bid is never
+    // posted and no funds change hands.
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.num_bids(0);
+    check.seat_base_available(user, order.base_atoms);
+    check.seat_quote_available(user, order.quote_atoms);
+    check.seat(user, |seat| {
+        assert_eq!(seat.user_order_sectors.asks.len(), 0);
+        assert_eq!(seat.user_order_sectors.bids.len(), 0);
+    });
+
+    Ok(())
+}
+
+/// Size decremented by a self trade counts toward a fill-or-kill order's size, so a fill-or-kill
+/// order that only matches the user's own order succeeds without filling anything.
+#[test]
+fn fill_or_kill_counts_self_trade_decrements() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+    let order = setup_resting_ask(&mollusk, &market_ctx, user)?;
+
+    let fill_or_kill_buy = |order_size: u64| {
+        market_ctx.seat_market_order(
+            user,
+            SeatMarketOrderInstructionData::new(
+                order_size,
+                true,
+                true,
+                EncodedPrice::infinity().as_u32(),
+                0,
+                TimeInForce::FillOrKill as u8,
+                SelfTradeBehavior::DecrementBoth as u8,
+                0,
+            ),
+        )
+    };
+
+    mollusk.process_and_validate_instruction_chain(&[
+        // More than the resting ask's size still can't be matched in full.
+        (
+            &fill_or_kill_buy(order.base_atoms + 1),
+            &[DropsetError::FillOrKillNotFilled.into_check_failure()],
+        ),
+        (&fill_or_kill_buy(order.base_atoms), &[Check::success()]),
+    ]);
+
+    // The ask was decremented by the full order size, so it's removed and no funds change hands.
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.num_asks(0);
+    check.num_bids(0);
+    check.seat_base_available(user, order.base_atoms);
+    check.seat_quote_available(user, order.quote_atoms);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_self_trade_behavior() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let (mollusk, market_ctx) = new_dropset_mollusk_context_with_default_market(&[user_mock]);
+    setup_resting_ask(&mollusk, &market_ctx, user)?;
+
+    let invalid = market_ctx.post_order(
+        user,
+        PostOrderInstructionData::new(order_args(), true, 0, false, OrderExpiry::NONE, 3, 0),
+    );
+
+    mollusk.process_and_validate_instruction_chain(&[(
+        &invalid,
+        &[DropsetError::InvalidSelfTradeBehavior.into_check_failure()],
+    )]);
+
+    Ok(())
+}
@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use client::{
+    context::{
+        market::MarketContext,
+        token::TokenContext,
+    },
+    mollusk_helpers::{
+        checks::IntoCheckFailure,
+        helper_trait::DropsetTestHelper,
+        market_checker::MarketChecker,
+        new_dropset_mollusk_context,
+        utils::create_mock_user_account,
+        MOLLUSK_DEFAULT_BASE_TOKEN,
+        MOLLUSK_DEFAULT_MINT_AUTHORITY,
+        MOLLUSK_DEFAULT_NUM_SECTORS,
+        MOLLUSK_DEFAULT_QUOTE_TOKEN,
+    },
+    token_instructions::create_and_initialize_token_instructions,
+};
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        OrderExpiry,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
+    state::{
+        market_status::MarketStatus,
+        sector::NIL,
+    },
+};
+use mollusk_svm::MolluskContext;
+use price::{
+    to_order_info,
+    OrderInfoArgs,
+};
+use solana_account::Account;
+use solana_address::Address;
+use solana_instruction::Instruction;
+use solana_sdk::{
+    program_pack::Pack,
+    rent::Rent,
+};
+use spl_associated_token_account_interface::address::get_associated_token_address_with_program_id;
+use spl_token_2022_interface::{
+    extension::{
+        transfer_fee::{
+            instruction::initialize_transfer_fee_config,
+            TransferFeeConfig,
+        },
+        BaseStateWithExtensions,
+        ExtensionType,
+        StateWithExtensions,
+    },
+    instruction::{
+        initialize_mint2,
+        initialize_non_transferable_mint,
+    },
+    state::{
+        Account as TokenAccount,
+        Mint,
+    },
+};
+use transaction_parser::program_ids::SPL_TOKEN_2022_ID;
+
+const TRANSFER_FEE_BPS: u16 = 100;
+
+/// Creates the instructions that create and initialize a `spl_token_2022` mint with `extension`,
+/// where `initialize_extension` initializes the extension before the mint itself is initialized.
+fn create_token_2022_mint_with_extension(
+    token: &TokenContext,
+    extension: ExtensionType,
+    initialize_extension: Instruction,
+) -> anyhow::Result<Vec<Instruction>> {
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[extension])?;
+    let create_mint_account = solana_system_interface::instruction::create_account(
+        &MOLLUSK_DEFAULT_MINT_AUTHORITY,
+        &token.mint_address,
+        Rent::default().minimum_balance(space),
+        space as u64,
+        &SPL_TOKEN_2022_ID,
+    );
+    let initialize_mint = initialize_mint2(
+        &SPL_TOKEN_2022_ID,
+        &token.mint_address,
+        &MOLLUSK_DEFAULT_MINT_AUTHORITY,
+        None,
+        token.mint_decimals,
+    )?;
+
+    Ok(vec![
+        create_mint_account,
+        initialize_extension,
+        initialize_mint,
+    ])
+}
+
+/// Creates the instructions that create and initialize the default `spl_token` base mint.
+fn create_default_base_mint() -> anyhow::Result<Vec<Instruction>> {
+    let (create_base, initialize_base) = create_and_initialize_token_instructions(
+        &MOLLUSK_DEFAULT_MINT_AUTHORITY,
+        &MOLLUSK_DEFAULT_BASE_TOKEN.mint_address,
+        Rent::default().minimum_balance(spl_token_interface::state::Mint::LEN),
+        MOLLUSK_DEFAULT_BASE_TOKEN.mint_decimals,
+        &MOLLUSK_DEFAULT_BASE_TOKEN.token_program,
+    )?;
+    Ok(vec![create_base, initialize_base])
+}
+
+fn token_2022_quote(mint_address: Address) -> TokenContext {
+    TokenContext::new(
+        Some(MOLLUSK_DEFAULT_MINT_AUTHORITY),
+        mint_address,
+        SPL_TOKEN_2022_ID,
+        MOLLUSK_DEFAULT_QUOTE_TOKEN.mint_decimals,
+    )
+}
+
+fn token_2022_balance(
+    mollusk: &MolluskContext<HashMap<Address, Account>>,
+    owner: &Address,
+    mint: &Address,
+) -> u64 {
+    let ata = get_associated_token_address_with_program_id(owner, mint, &SPL_TOKEN_2022_ID);
+    let account_store = mollusk.account_store.borrow();
+    let account = account_store.get(&ata).expect("Token account should exist");
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .expect("Should unpack token account")
+        .base
+        .amount
+}
+
+/// Verifies that registering a market with a `spl_token_2022` mint that has an unsupported
+/// extension fails.
+#[test]
+fn register_market_rejects_unsupported_mint_extensions() -> anyhow::Result<()> {
+    let mollusk = new_dropset_mollusk_context(vec![create_mock_user_account(
+        MOLLUSK_DEFAULT_MINT_AUTHORITY,
+        100_000_000_000,
+    )]);
+
+    let quote_mint = Address::new_unique();
+    let market_ctx = MarketContext::new(MOLLUSK_DEFAULT_BASE_TOKEN, token_2022_quote(quote_mint));
+    let create_quote = create_token_2022_mint_with_extension(
+        &market_ctx.quote,
+        ExtensionType::NonTransferable,
+        initialize_non_transferable_mint(&SPL_TOKEN_2022_ID, &quote_mint)?,
+    )?;
+
+    assert!(mollusk
+        .process_instruction_chain(&[create_default_base_mint()?, create_quote].concat())
+        .program_result
+        .is_ok());
+
+    mollusk.process_and_validate_instruction(
+        &market_ctx.register_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, MOLLUSK_DEFAULT_NUM_SECTORS),
+        &[DropsetError::UnsupportedMintExtension.into_check_failure()],
+    );
+
+    Ok(())
+}
+
+/// Verifies that deposits of a transfer fee mint credit the amount received after the fee, and
+/// that a market order paying with a transfer fee mint transfers enough to cover the fee.
+#[test]
+fn transfer_fee_quote_mint() -> anyhow::Result<()> {
+    let maker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let taker_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let maker = maker_mock.0;
+    let taker = taker_mock.0;
+    let mollusk = new_dropset_mollusk_context(vec![
+        create_mock_user_account(MOLLUSK_DEFAULT_MINT_AUTHORITY, 100_000_000_000),
+        maker_mock,
+        taker_mock,
+    ]);
+
+    let quote_mint = Address::new_unique();
+    let market_ctx = MarketContext::new(MOLLUSK_DEFAULT_BASE_TOKEN, token_2022_quote(quote_mint));
+    let create_quote = create_token_2022_mint_with_extension(
+        &market_ctx.quote,
+        ExtensionType::TransferFeeConfig,
+        initialize_transfer_fee_config(
+            &SPL_TOKEN_2022_ID,
+            &quote_mint,
+            Some(&MOLLUSK_DEFAULT_MINT_AUTHORITY),
+            Some(&MOLLUSK_DEFAULT_MINT_AUTHORITY),
+            TRANSFER_FEE_BPS,
+            u64::MAX,
+        )?,
+    )?;
+
+    assert!(mollusk
+        .process_instruction_chain(
+            &[
+                create_default_base_mint()?,
+                create_quote,
+                vec![market_ctx
+                    .register_market(MOLLUSK_DEFAULT_MINT_AUTHORITY, MOLLUSK_DEFAULT_NUM_SECTORS)],
+            ]
+            .concat()
+        )
+        .program_result
+        .is_ok());
+
+    let order_info_args = OrderInfoArgs::new_unscaled(10_000_000, 500);
+    let order = to_order_info(order_info_args.clone()).expect("Should be a valid order");
+
+    // The maker deposits 10_000 quote, and the 1% transfer fee is withheld from the deposit.
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&maker, &maker),
+            market_ctx.quote.create_ata_idempotent(&maker, &maker),
+            market_ctx.base.mint_to_owner(&maker, order.base_atoms)?,
+            market_ctx.quote.mint_to_owner(&maker, 10_000)?,
+            market_ctx.deposit_base(maker, order.base_atoms, NIL),
+            market_ctx.deposit_quote(maker, 10_000, 0),
+            market_ctx.post_order(
+                maker,
+                PostOrderInstructionData::new(
+                    order_info_args,
+                    false,
+                    0,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0,
+                ),
+            ),
+        ])
+        .program_result
+        .is_ok());
+
+    let check = MarketChecker::new(&mollusk, &market_ctx);
+    check.seat_quote_available(maker, 9_900);
+    check.num_asks(1);
+
+    // The taker buys the whole ask. Without accounting for the transfer fee, the market would
+    // receive less quote than the amount filled and the order would fail.
+    let taker_quote_minted = order.quote_atoms * 2;
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx.base.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.create_ata_idempotent(&taker, &taker),
+            market_ctx.quote.mint_to_owner(&taker, taker_quote_minted)?,
+            market_ctx.unconstrained_market_order(taker, order.base_atoms, true, true),
+        ])
+        .program_result
+        .is_ok());
+
+    check.num_asks(0);
+    check.base_token_balance(taker, order.base_atoms);
+    check.seat_quote_available(maker, 9_900 + order.quote_atoms);
+
+    // The taker paid the amount filled plus the transfer fee withheld from it, so the market
+    // received at least the amount filled.
+    let quote_paid = taker_quote_minted - token_2022_balance(&mollusk, &taker, &quote_mint);
+    let transfer_fee = (quote_paid * TRANSFER_FEE_BPS as u64).div_ceil(10_000);
+    let quote_received = quote_paid - transfer_fee;
+    assert!(quote_received >= order.quote_atoms);
+    assert!(quote_received - order.quote_atoms <= 1);
+
+    // Any excess the market received is accrued as fees instead of being left untracked.
+    assert_eq!(
+        mollusk
+            .view_market(market_ctx.market)
+            .header
+            .quote_fees_accrued,
+        quote_received - order.quote_atoms
+    );
+
+    Ok(())
+}
+
+/// Verifies that closing a market with a transfer fee mint harvests the fees withheld in the
+/// market's token account to the mint, since the token program won't close a token account with
+/// withheld fees.
+#[test]
+fn close_market_harvests_withheld_transfer_fees() -> anyhow::Result<()> {
+    let user_mock = create_mock_user_account(Address::new_unique(), 100_000_000);
+    let user = user_mock.0;
+    let authority = MOLLUSK_DEFAULT_MINT_AUTHORITY;
+    let mollusk = new_dropset_mollusk_context(vec![
+        create_mock_user_account(authority, 100_000_000_000),
+        user_mock,
+    ]);
+
+    let quote_mint = Address::new_unique();
+    let market_ctx = MarketContext::new(MOLLUSK_DEFAULT_BASE_TOKEN, token_2022_quote(quote_mint));
+    let create_quote = create_token_2022_mint_with_extension(
+        &market_ctx.quote,
+        ExtensionType::TransferFeeConfig,
+        initialize_transfer_fee_config(
+            &SPL_TOKEN_2022_ID,
+            &quote_mint,
+            Some(&authority),
+            Some(&authority),
+            TRANSFER_FEE_BPS,
+            u64::MAX,
+        )?,
+    )?;
+
+    assert!(mollusk
+        .process_instruction_chain(
+            &[
+                create_default_base_mint()?,
+                create_quote,
+                vec![market_ctx.register_market(authority, MOLLUSK_DEFAULT_NUM_SECTORS)],
+            ]
+            .concat()
+        )
+        .program_result
+        .is_ok());
+
+    // The user deposits 10_000 quote and withdraws the 9_900 received after the 1% transfer fee,
+    // leaving the market's quote token account empty but with 100 quote withheld.
+    assert!(mollusk
+        .process_instruction_chain(&[
+            market_ctx
+                .base
+                .create_ata_idempotent(&authority, &authority),
+            market_ctx
+                .quote
+                .create_ata_idempotent(&authority, &authority),
+            market_ctx.quote.create_ata_idempotent(&user, &user),
+            market_ctx.quote.mint_to_owner(&user, 10_000)?,
+            market_ctx.deposit_quote(user, 10_000, NIL),
+            market_ctx.withdraw_quote(user, 9_900, 0),
+            market_ctx.close_seat(user, 0),
+            market_ctx.set_market_status(authority, MarketStatus::Closed),
+        ])
+        .program_result
+        .is_ok());
+
+    let withheld_in_mint = || {
+        let account_store = mollusk.account_store.borrow();
+        let mint = account_store
+            .get(&quote_mint)
+            .expect("Mint account should exist");
+        let withheld_amount = StateWithExtensions::<Mint>::unpack(&mint.data)
+            .expect("Should unpack mint")
+            .get_extension::<TransferFeeConfig>()
+            .expect("Mint should have a transfer fee config")
+            .withheld_amount;
+        u64::from(withheld_amount)
+    };
+    assert_eq!(withheld_in_mint(), 0);
+
+    assert!(mollusk
+        .process_instruction_chain(&[market_ctx.close_market(authority)])
+        .program_result
+        .is_ok());
+
+    assert_eq!(withheld_in_mint(), 100);
+    let quote_market_ata_lamports = mollusk
+        .account_store
+        .borrow()
+        .get(&market_ctx.quote_market_ata)
+        .map_or(0, |account| account.lamports);
+    assert_eq!(quote_market_ata_lamports, 0);
+
+    Ok(())
+}
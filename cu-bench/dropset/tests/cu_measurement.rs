@@ -15,10 +15,12 @@ use dropset_interface::{
     error::DropsetError,
     instructions::{
         BatchReplaceInstructionData,
+        BatchReplaceMode,
         CancelOrderInstructionData,
-        MarketOrderInstructionData,
+        OrderExpiry,
         PostOrderInstructionData,
-        UnvalidatedOrders,
+        SelfTradeBehavior,
+        UnvalidatedOrder,
     },
 };
 use price::{
@@ -31,7 +33,7 @@ use price::{
 };
 
 /// Max orders per side in a single BatchReplace.
-const BATCH_AMOUNTS: &[u64] = &[1, 5, MAX_ORDERS_USIZE as u64];
+const BATCH_AMOUNTS: &[u64] = &[1, 5, 10, MAX_ORDERS_USIZE as u64];
 /// Number of resting asks crossed by a single MarketOrder.
 const SWAP_FILL_AMOUNTS: &[u64] = &[1, 10, 50];
 
@@ -90,6 +92,10 @@ fn cu_post_order() -> anyhow::Result<()> {
                 OrderInfoArgs::new_unscaled(ASK_PRICES[0], 1),
                 false,
                 f.seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
             ),
         ),
     );
@@ -116,7 +122,15 @@ fn cu_cancel_order() -> anyhow::Result<()> {
     // Setup: place the ask (not measured).
     let res = f.ctx.process_instruction_chain(&[f.market_ctx.post_order(
         f.maker,
-        PostOrderInstructionData::new(order_args, false, f.seat_index),
+        PostOrderInstructionData::new(
+            order_args,
+            false,
+            f.seat_index,
+            true,
+            OrderExpiry::NONE,
+            SelfTradeBehavior::Abort as u8,
+            0,
+        ),
     )]);
     assert!(res.program_result.is_ok(), "setup PostOrder failed");
 
@@ -133,6 +147,32 @@ fn cu_cancel_order() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Every state-changing instruction emits its events and flushes them with a `FlushEvents`
+/// self-CPI. An empty BatchReplace does no book work, so its CU is roughly the fixed cost that
+/// event emission adds on top of account validation.
+#[test]
+fn cu_event_emission() -> anyhow::Result<()> {
+    let mut logs = String::new();
+    fmt_header(&mut logs, "Event emission");
+
+    let f = new_bench_fixture();
+    expand_market(&f);
+
+    let cu = measure_cu(
+        &f,
+        f.market_ctx.batch_replace(
+            f.maker,
+            BatchReplaceInstructionData::new(f.seat_index, BatchReplaceMode::Replace as u8),
+            &[],
+            &[],
+        ),
+    );
+
+    fmt_subtable(&mut logs, "Empty batches", &[(1, cu)]);
+    eprintln!("{logs}");
+    Ok(())
+}
+
 // ── Batched benchmarks ───────────────────────────────────────────────────────
 
 #[test]
@@ -174,53 +214,20 @@ fn batch_replace_place(n: u64) -> u64 {
     let f = new_bench_fixture();
     expand_market(&f);
 
-    let cu = match n as usize {
-        1 => {
-            let asks = [OrderInfoArgs::new_unscaled(ASK_PRICES[0], 1)];
-            measure_cu(
-                &f,
-                f.market_ctx.batch_replace(
-                    f.maker,
-                    BatchReplaceInstructionData::new(
-                        f.seat_index,
-                        UnvalidatedOrders::new([]),
-                        UnvalidatedOrders::new(asks),
-                    ),
-                ),
-            )
-        }
-        5 => {
-            let asks: [OrderInfoArgs; 5] =
-                core::array::from_fn(|i| OrderInfoArgs::new_unscaled(ASK_PRICES[i], 1));
-            measure_cu(
-                &f,
-                f.market_ctx.batch_replace(
-                    f.maker,
-                    BatchReplaceInstructionData::new(
-                        f.seat_index,
-                        UnvalidatedOrders::new([]),
-                        UnvalidatedOrders::new(asks),
-                    ),
-                ),
-            )
-        }
-        MAX_ORDERS_USIZE => {
-            let asks: [OrderInfoArgs; MAX_ORDERS_USIZE] =
-                core::array::from_fn(|i| OrderInfoArgs::new_unscaled(ASK_PRICES[i], 1));
-            measure_cu(
-                &f,
-                f.market_ctx.batch_replace(
-                    f.maker,
-                    BatchReplaceInstructionData::new(
-                        f.seat_index,
-                        UnvalidatedOrders::new([]),
-                        UnvalidatedOrders::new(asks),
-                    ),
-                ),
-            )
-        }
-        _ => unreachable!(),
-    };
+    let asks = ASK_PRICES
+        .into_iter()
+        .take(n as usize)
+        .map(|price_mantissa| OrderInfoArgs::new_unscaled(price_mantissa, 1).into())
+        .collect::<Vec<UnvalidatedOrder>>();
+    let cu = measure_cu(
+        &f,
+        f.market_ctx.batch_replace(
+            f.maker,
+            BatchReplaceInstructionData::new(f.seat_index, BatchReplaceMode::Replace as u8),
+            &[],
+            &asks,
+        ),
+    );
 
     cu / n
 }
@@ -238,6 +245,10 @@ fn batch_replace_cancel(n: u64) -> u64 {
                 OrderInfoArgs::new_unscaled(price_mantissa, 1),
                 false,
                 f.seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
             ),
         )]);
         assert!(res.program_result.is_ok(), "setup PostOrder {i} failed");
@@ -248,11 +259,9 @@ fn batch_replace_cancel(n: u64) -> u64 {
         &f,
         f.market_ctx.batch_replace(
             f.maker,
-            BatchReplaceInstructionData::new(
-                f.seat_index,
-                UnvalidatedOrders::new([]),
-                UnvalidatedOrders::new([]),
-            ),
+            BatchReplaceInstructionData::new(f.seat_index, BatchReplaceMode::Replace as u8),
+            &[],
+            &[],
         ),
     );
 
@@ -273,59 +282,30 @@ fn batch_replace_replace(n: u64) -> u64 {
                 OrderInfoArgs::new_unscaled(price_mantissa, 1),
                 false,
                 f.seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
             ),
         )]);
         assert!(res.program_result.is_ok(), "setup PostOrder {i} failed");
     }
 
     // Measure: BatchReplace cancels the n existing asks and places n new ones.
-    let cu = match n as usize {
-        1 => {
-            let asks = [OrderInfoArgs::new_unscaled(ASK_PRICES[0], 1)];
-            measure_cu(
-                &f,
-                f.market_ctx.batch_replace(
-                    f.maker,
-                    BatchReplaceInstructionData::new(
-                        f.seat_index,
-                        UnvalidatedOrders::new([]),
-                        UnvalidatedOrders::new(asks),
-                    ),
-                ),
-            )
-        }
-        5 => {
-            let asks: [OrderInfoArgs; 5] =
-                core::array::from_fn(|i| OrderInfoArgs::new_unscaled(ASK_PRICES[i], 1));
-            measure_cu(
-                &f,
-                f.market_ctx.batch_replace(
-                    f.maker,
-                    BatchReplaceInstructionData::new(
-                        f.seat_index,
-                        UnvalidatedOrders::new([]),
-                        UnvalidatedOrders::new(asks),
-                    ),
-                ),
-            )
-        }
-        MAX_ORDERS_USIZE => {
-            let asks: [OrderInfoArgs; MAX_ORDERS_USIZE] =
-                core::array::from_fn(|i| OrderInfoArgs::new_unscaled(ASK_PRICES[i], 1));
-            measure_cu(
-                &f,
-                f.market_ctx.batch_replace(
-                    f.maker,
-                    BatchReplaceInstructionData::new(
-                        f.seat_index,
-                        UnvalidatedOrders::new([]),
-                        UnvalidatedOrders::new(asks),
-                    ),
-                ),
-            )
-        }
-        _ => unreachable!(),
-    };
+    let asks = ASK_PRICES
+        .into_iter()
+        .take(n as usize)
+        .map(|price_mantissa| OrderInfoArgs::new_unscaled(price_mantissa, 1).into())
+        .collect::<Vec<UnvalidatedOrder>>();
+    let cu = measure_cu(
+        &f,
+        f.market_ctx.batch_replace(
+            f.maker,
+            BatchReplaceInstructionData::new(f.seat_index, BatchReplaceMode::Replace as u8),
+            &[],
+            &asks,
+        ),
+    );
 
     cu / n
 }
@@ -389,6 +369,10 @@ fn individual_place(n: u64) -> u64 {
                         OrderInfoArgs::new_unscaled(ASK_PRICES[i], 1),
                         false,
                         f.seat_index,
+                        true,
+                        OrderExpiry::NONE,
+                        SelfTradeBehavior::Abort as u8,
+                        0,
                     ),
                 ),
             )
@@ -412,6 +396,10 @@ fn individual_cancel(n: u64) -> u64 {
                 OrderInfoArgs::new_unscaled(price_mantissa, 1),
                 false,
                 f.seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
             ),
         )]);
         assert!(res.program_result.is_ok(), "setup PostOrder {i} failed");
@@ -450,6 +438,10 @@ fn individual_cancel_and_place(n: u64) -> u64 {
                 OrderInfoArgs::new_unscaled(price_mantissa, 1),
                 false,
                 f.seat_index,
+                true,
+                OrderExpiry::NONE,
+                SelfTradeBehavior::Abort as u8,
+                0,
             ),
         )]);
         assert!(res.program_result.is_ok(), "setup PostOrder {i} failed");
@@ -483,6 +475,10 @@ fn individual_cancel_and_place(n: u64) -> u64 {
                         OrderInfoArgs::new_unscaled(ASK_PRICES[i], 1),
                         false,
                         f.seat_index,
+                        true,
+                        OrderExpiry::NONE,
+                        SelfTradeBehavior::Abort as u8,
+                        0,
                     ),
                 ),
             )
@@ -516,9 +512,10 @@ fn cu_market_order() -> anyhow::Result<()> {
 fn market_order_fill(n: u64) -> Result<u64, DropsetError> {
     let f = new_bench_fixture();
 
-    // Each order and each maker seat occupies one sector.
-    // Expand enough times to fit all n orders plus ceil(n / MAX_ORDERS_USIZE) maker seats.
-    let sectors_needed = n as usize + (n as usize).div_ceil(MAX_ORDERS_USIZE);
+    // Each order, each maker seat and each maker's overflow order sectors occupies one sector.
+    // Expand enough times to fit all n orders plus ceil(n / MAX_ORDERS_USIZE) maker seats and
+    // their overflow sectors.
+    let sectors_needed = n as usize + 2 * (n as usize).div_ceil(MAX_ORDERS_USIZE);
     for _ in 0..sectors_needed.div_ceil(MAX_PERMITTED_SECTOR_INCREASE) + 1 {
         expand_market(&f);
     }
@@ -543,7 +540,15 @@ fn market_order_fill(n: u64) -> Result<u64, DropsetError> {
         for (i, arg) in chunk.iter().enumerate() {
             let res = f.ctx.process_instruction_chain(&[f.market_ctx.post_order(
                 maker,
-                PostOrderInstructionData::new(arg.clone(), false, seat_index),
+                PostOrderInstructionData::new(
+                    arg.clone(),
+                    false,
+                    seat_index,
+                    true,
+                    OrderExpiry::NONE,
+                    SelfTradeBehavior::Abort as u8,
+                    0,
+                ),
             )]);
             assert!(
                 res.program_result.is_ok(),
@@ -571,10 +576,8 @@ fn market_order_fill(n: u64) -> Result<u64, DropsetError> {
     let base_to_buy = sum_base_necessary(&ask_args)?;
     let cu = measure_cu(
         &f,
-        f.market_ctx.market_order(
-            taker,
-            MarketOrderInstructionData::new(base_to_buy, true, true),
-        ),
+        f.market_ctx
+            .unconstrained_market_order(taker, base_to_buy, true, true),
     );
 
     Ok(cu / n)
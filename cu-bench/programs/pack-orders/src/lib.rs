@@ -3,12 +3,14 @@
 
 #![no_std]
 
+extern crate alloc;
+
+use alloc::vec::Vec;
 use core::hint::black_box;
 
 use pinocchio::{
     account::AccountView,
     error::ProgramError,
-    no_allocator,
     nostd_panic_handler,
     program_entrypoint,
     Address,
@@ -16,10 +18,13 @@ use pinocchio::{
 };
 
 program_entrypoint!(process_instruction);
-no_allocator!();
+// Borsh deserializes the order lists into `Vec`s, so the borsh version needs a heap.
+#[cfg(not(feature = "bench-program-B"))]
+pinocchio::no_allocator!();
+#[cfg(feature = "bench-program-B")]
+pinocchio::default_allocator!();
 nostd_panic_handler!();
 
-use dropset_interface::state::user_order_sectors::MAX_ORDERS_USIZE;
 use price::OrderInfoArgs;
 
 #[derive(PartialEq, Debug)]
@@ -39,8 +44,9 @@ pub struct BorshOrderInfoArgs {
     feature = "borsh-derive",
     derive(borsh::BorshSerialize, borsh::BorshDeserialize)
 )]
-pub struct BorshUnvalidatedOrders {
-    pub order_args: [BorshOrderInfoArgs; MAX_ORDERS_USIZE],
+pub struct BorshUnvalidatedOrder {
+    pub order_args: BorshOrderInfoArgs,
+    pub client_order_id: u64,
 }
 
 #[derive(PartialEq, Debug)]
@@ -50,8 +56,9 @@ pub struct BorshUnvalidatedOrders {
 )]
 pub struct BorshBatchReplaceData {
     pub user_sector_index_hint: u32,
-    pub new_bids: BorshUnvalidatedOrders,
-    pub new_asks: BorshUnvalidatedOrders,
+    pub mode: u8,
+    pub new_bids: Vec<BorshUnvalidatedOrder>,
+    pub new_asks: Vec<BorshUnvalidatedOrder>,
 }
 
 impl From<OrderInfoArgs> for BorshOrderInfoArgs {
@@ -78,8 +85,7 @@ fn process_instruction(
             BatchReplaceInstructionData,
             UnvalidatedOrders,
         };
-        use price::OrderInfoArgs;
-        use static_assertions::const_assert_eq;
+        use instruction_macros_traits::Pack;
 
         let data = BatchReplaceInstructionData::unpack_untagged(instruction_data)?;
 
@@ -87,31 +93,23 @@ fn process_instruction(
             return Err(ProgramError::InvalidInstructionData);
         }
 
-        // Unsafely cast UnvalidatedOrders to [OrderInfoArgs; MAX_ORDERS_USIZE] to get
-        // access to the underlying arrays without having to update the public API.
-        // Const assertion to catch UB if the struct shape ever changes.
-        const_assert_eq!(
-            size_of::<UnvalidatedOrders>(),
-            size_of::<[OrderInfoArgs; MAX_ORDERS_USIZE]>()
-        );
-        let bids_ptr = &data.new_bids as *const _ as *const [OrderInfoArgs; MAX_ORDERS_USIZE];
-        let new_bids = unsafe { &*bids_ptr };
-        let asks_ptr = &data.new_asks as *const _ as *const [OrderInfoArgs; MAX_ORDERS_USIZE];
-        let new_asks = unsafe { &*asks_ptr };
+        let order_lists = &instruction_data[BatchReplaceInstructionData::LEN..];
+        let (new_bids, order_lists) = UnvalidatedOrders::unpack_length_prefixed(order_lists)?;
+        let (new_asks, _) = UnvalidatedOrders::unpack_length_prefixed(order_lists)?;
 
         // Use black_box to prevent the compiler from optimizing away field accesses.
         for o in new_bids.iter() {
-            black_box(o.price_mantissa);
-            black_box(o.base_scalar);
-            black_box(o.base_exponent_biased);
-            black_box(o.quote_exponent_biased);
+            black_box(o.order_info_args.price_mantissa);
+            black_box(o.order_info_args.base_scalar);
+            black_box(o.order_info_args.base_exponent_biased);
+            black_box(o.order_info_args.quote_exponent_biased);
         }
 
         for o in new_asks.iter() {
-            black_box(o.price_mantissa);
-            black_box(o.base_scalar);
-            black_box(o.base_exponent_biased);
-            black_box(o.quote_exponent_biased);
+            black_box(o.order_info_args.price_mantissa);
+            black_box(o.order_info_args.base_scalar);
+            black_box(o.order_info_args.base_exponent_biased);
+            black_box(o.order_info_args.quote_exponent_biased);
         }
     }
 
@@ -126,18 +124,18 @@ fn process_instruction(
         }
 
         // Use black_box to prevent the compiler from optimizing away field accesses.
-        for o in data.new_bids.order_args.iter() {
-            black_box(o.price_mantissa);
-            black_box(o.base_scalar);
-            black_box(o.base_exponent_biased);
-            black_box(o.quote_exponent_biased);
+        for o in data.new_bids.iter() {
+            black_box(o.order_args.price_mantissa);
+            black_box(o.order_args.base_scalar);
+            black_box(o.order_args.base_exponent_biased);
+            black_box(o.order_args.quote_exponent_biased);
         }
 
-        for o in data.new_asks.order_args.iter() {
-            black_box(o.price_mantissa);
-            black_box(o.base_scalar);
-            black_box(o.base_exponent_biased);
-            black_box(o.quote_exponent_biased);
+        for o in data.new_asks.iter() {
+            black_box(o.order_args.price_mantissa);
+            black_box(o.order_args.base_scalar);
+            black_box(o.order_args.base_exponent_biased);
+            black_box(o.order_args.quote_exponent_biased);
         }
     }
 
@@ -1,10 +1,12 @@
 use borsh::BorshDeserialize;
 use cu_bench_pack_orders::{
     BorshBatchReplaceData,
-    BorshUnvalidatedOrders,
+    BorshUnvalidatedOrder,
 };
 use dropset_interface::instructions::{
     BatchReplaceInstructionData,
+    BatchReplaceMode,
+    UnvalidatedOrder,
     UnvalidatedOrders,
 };
 use instruction_macros_traits::{
@@ -13,51 +15,40 @@ use instruction_macros_traits::{
 };
 use price::OrderInfoArgs;
 
+fn to_borsh(order: &UnvalidatedOrder) -> BorshUnvalidatedOrder {
+    BorshUnvalidatedOrder {
+        order_args: order.order_info_args.clone().into(),
+        client_order_id: order.client_order_id,
+    }
+}
+
 #[test]
 fn pack_borsh_round_trip_equivalence() {
-    let bid = OrderInfoArgs::new_unscaled(10_000_000, 1);
+    let bids = [UnvalidatedOrder::new(
+        OrderInfoArgs::new_unscaled(10_000_000, 1),
+        99,
+    )];
     let asks = [
-        OrderInfoArgs::new_unscaled(11_000_000, 1),
-        OrderInfoArgs::new_unscaled(12_000_000, 2),
-        OrderInfoArgs::new_unscaled(13_000_000, 3),
-        OrderInfoArgs::new_unscaled(14_000_000, 4),
-        OrderInfoArgs::new_unscaled(15_000_000, 5),
-        OrderInfoArgs::new(0, 0, 0, 0),
-        OrderInfoArgs::new(0, 0, 0, 0),
-        OrderInfoArgs::new(0, 0, 0, 0),
-        OrderInfoArgs::new(0, 0, 0, 0),
-        OrderInfoArgs::new(0, 0, 0, 0),
+        UnvalidatedOrder::new(OrderInfoArgs::new_unscaled(11_000_000, 1), 1),
+        UnvalidatedOrder::new(OrderInfoArgs::new_unscaled(12_000_000, 2), 2),
+        UnvalidatedOrder::new(OrderInfoArgs::new_unscaled(13_000_000, 3), 3),
+        UnvalidatedOrder::new(OrderInfoArgs::new_unscaled(14_000_000, 4), 4),
+        UnvalidatedOrder::new(OrderInfoArgs::new_unscaled(15_000_000, 5), 5),
     ];
-    // Create test data using Pack
-    let pack_data = BatchReplaceInstructionData::new(
-        42,
-        UnvalidatedOrders::new([bid.clone()]),
-        UnvalidatedOrders::new(asks.clone()),
-    );
 
-    // Serialize with Pack
-    let packed_bytes = pack_data.pack();
+    // Serialize with Pack: the fixed size args followed by the two length-prefixed order lists.
+    let mut packed_bytes = BatchReplaceInstructionData::new(42, BatchReplaceMode::Replace as u8)
+        .pack()
+        .to_vec();
+    UnvalidatedOrders::pack_length_prefixed(&bids, &mut packed_bytes);
+    UnvalidatedOrders::pack_length_prefixed(&asks, &mut packed_bytes);
 
     // Create equivalent Borsh data
     let borsh_data = BorshBatchReplaceData {
         user_sector_index_hint: 42,
-        new_bids: BorshUnvalidatedOrders {
-            order_args: [
-                bid.into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-                OrderInfoArgs::new(0, 0, 0, 0).into(),
-            ],
-        },
-        new_asks: BorshUnvalidatedOrders {
-            order_args: core::array::from_fn(|i| asks[i].clone().into()),
-        },
+        mode: BatchReplaceMode::Replace as u8,
+        new_bids: bids.iter().map(to_borsh).collect(),
+        new_asks: asks.iter().map(to_borsh).collect(),
     };
 
     // Serialize with Borsh
@@ -65,13 +56,12 @@ fn pack_borsh_round_trip_equivalence() {
 
     // Compare byte representations
     assert_eq!(
-        packed_bytes.as_ref(),
-        borsh_bytes.as_slice(),
+        packed_bytes, borsh_bytes,
         "Pack and Borsh serialization should produce identical bytes"
     );
 
     // Round-trip: deserialize Pack bytes with Borsh
-    let borsh_deserialized = BorshBatchReplaceData::try_from_slice(packed_bytes.as_ref())
+    let borsh_deserialized = BorshBatchReplaceData::try_from_slice(&packed_bytes)
         .expect("Borsh deserialization of Pack bytes failed");
 
     assert_eq!(
@@ -82,6 +72,16 @@ fn pack_borsh_round_trip_equivalence() {
     // Round-trip: deserialize Borsh bytes with Pack
     let pack_deserialized = BatchReplaceInstructionData::unpack(&borsh_bytes)
         .expect("Pack deserialization of Borsh bytes failed");
+    assert_eq!(pack_deserialized.user_sector_index_hint, 42);
+    assert_eq!(pack_deserialized.mode, BatchReplaceMode::Replace as u8);
+
+    let order_lists = &borsh_bytes[BatchReplaceInstructionData::LEN..];
+    let (new_bids, order_lists) = UnvalidatedOrders::unpack_length_prefixed(order_lists)
+        .expect("Pack deserialization of Borsh bids failed");
+    let (new_asks, rest) = UnvalidatedOrders::unpack_length_prefixed(order_lists)
+        .expect("Pack deserialization of Borsh asks failed");
 
-    assert_eq!(pack_deserialized.pack().as_ref(), borsh_bytes.as_slice());
+    assert!(rest.is_empty());
+    assert_eq!(new_bids.iter().collect::<Vec<_>>(), bids);
+    assert_eq!(new_asks.iter().collect::<Vec<_>>(), asks);
 }
@@ -1,7 +1,12 @@
 use cu_bench_tests::new_cu_bench_mollusk;
-use dropset_interface::instructions::{
-    BatchReplaceInstructionData,
-    UnvalidatedOrders,
+use dropset_interface::{
+    error::DropsetError,
+    instructions::{
+        BatchReplaceInstructionData,
+        BatchReplaceMode,
+        UnvalidatedOrder,
+        UnvalidatedOrders,
+    },
 };
 use instruction_macros_traits::Pack;
 use price::{
@@ -12,27 +17,49 @@ use price::{
 use solana_address::Address;
 use solana_instruction::Instruction;
 
+/// Packs `orders` as a length-prefixed list.
+fn pack_orders(orders: &[UnvalidatedOrder]) -> Vec<u8> {
+    let mut packed = vec![];
+    UnvalidatedOrders::pack_length_prefixed(orders, &mut packed);
+    packed
+}
+
+/// Packs `orders` with a client order id of `0` and counts the orders that pass validation.
+fn count_valid(orders: &[OrderInfoArgs]) -> usize {
+    let orders = orders.iter().cloned().map(Into::into).collect::<Vec<_>>();
+    let packed = pack_orders(&orders);
+    let (orders, _) = UnvalidatedOrders::unpack_length_prefixed(&packed).unwrap();
+    orders
+        .into_valid_orders_iter()
+        .filter(Result::is_ok)
+        .count()
+}
+
 #[test]
 fn pack_orders_cu() {
     let program_id = Address::new_unique();
     let mollusk: mollusk_svm::Mollusk =
         new_cu_bench_mollusk(&program_id, "cu_bench_pack_orders.so");
 
-    let data = BatchReplaceInstructionData::new(
-        0,
-        UnvalidatedOrders::new([OrderInfoArgs::new_unscaled(11_000_000, 1)]),
-        UnvalidatedOrders::new([
-            OrderInfoArgs::new_unscaled(12_000_000, 1),
-            OrderInfoArgs::new_unscaled(13_000_000, 2),
-            OrderInfoArgs::new_unscaled(14_000_000, 3),
-            OrderInfoArgs::new_unscaled(15_000_000, 4),
-            OrderInfoArgs::new_unscaled(16_000_000, 5),
-        ]),
+    let mut data = BatchReplaceInstructionData::new(0, BatchReplaceMode::Replace as u8)
+        .pack()
+        .to_vec();
+    UnvalidatedOrders::pack_length_prefixed(
+        &[OrderInfoArgs::new_unscaled(11_000_000, 1).into()],
+        &mut data,
+    );
+    UnvalidatedOrders::pack_length_prefixed(
+        &[
+            OrderInfoArgs::new_unscaled(12_000_000, 1).into(),
+            OrderInfoArgs::new_unscaled(13_000_000, 2).into(),
+            OrderInfoArgs::new_unscaled(14_000_000, 3).into(),
+            OrderInfoArgs::new_unscaled(15_000_000, 4).into(),
+            OrderInfoArgs::new_unscaled(16_000_000, 5).into(),
+        ],
+        &mut data,
     );
 
-    let packed = data.pack();
-
-    let instruction = Instruction::new_with_bytes(program_id, packed.as_ref(), vec![]);
+    let instruction = Instruction::new_with_bytes(program_id, &data, vec![]);
 
     let result = mollusk.process_instruction(&instruction, &[]);
     assert!(
@@ -44,19 +71,19 @@ fn pack_orders_cu() {
 }
 
 #[test]
-fn iterator_stops_at_first_invalid_order() {
+fn iterator_validates_each_order() {
     // Test 1: All valid orders (5)
-    let all_valid = UnvalidatedOrders::new([
+    let all_valid = [
         OrderInfoArgs::new_unscaled(11_000_000, 1),
         OrderInfoArgs::new_unscaled(12_000_000, 2),
         OrderInfoArgs::new_unscaled(13_000_000, 3),
         OrderInfoArgs::new_unscaled(14_000_000, 4),
         OrderInfoArgs::new_unscaled(15_000_000, 5),
-    ]);
-    assert_eq!(all_valid.into_valid_order_infos_iter().count(), 5);
+    ];
+    assert_eq!(count_valid(&all_valid), 5);
 
-    // Test 2: Some valid followed by invalid price_mantissa (2 valid)
-    let two_valid = UnvalidatedOrders::new([
+    // Test 2: An invalid price_mantissa in the middle (4 valid)
+    let one_invalid = [
         OrderInfoArgs::new_unscaled(11_000_000, 1),
         OrderInfoArgs::new_unscaled(12_000_000, 2),
         // Invalid: price_mantissa below lower bound
@@ -68,46 +95,27 @@ fn iterator_stops_at_first_invalid_order() {
         },
         OrderInfoArgs::new_unscaled(14_000_000, 4),
         OrderInfoArgs::new_unscaled(15_000_000, 5),
-    ]);
-    assert_eq!(two_valid.into_valid_order_infos_iter().count(), 2);
-
-    // Test 3: First order invalid (0 valid)
-    let none_valid = UnvalidatedOrders::new([
-        // Invalid: price_mantissa above upper bound
-        OrderInfoArgs {
-            price_mantissa: MANTISSA_DIGITS_UPPER_BOUND + 1,
-            base_scalar: 1,
-            base_exponent_biased: 16,
-            quote_exponent_biased: 16,
-        },
-        OrderInfoArgs::new_unscaled(12_000_000, 2),
-        OrderInfoArgs::new_unscaled(13_000_000, 3),
-        OrderInfoArgs::new_unscaled(14_000_000, 4),
-        OrderInfoArgs::new_unscaled(15_000_000, 5),
-    ]);
-    assert_eq!(none_valid.into_valid_order_infos_iter().count(), 0);
+    ];
+    assert_eq!(count_valid(&one_invalid), 4);
 
-    // Test 4: One valid order
-    let one_valid = UnvalidatedOrders::new([OrderInfoArgs::new_unscaled(11_000_000, 1)]);
-    assert_eq!(one_valid.into_valid_order_infos_iter().count(), 1);
+    // Test 3: An invalid order yields an error instead of ending iteration.
+    let packed = pack_orders(&one_invalid.map(UnvalidatedOrder::from));
+    let (orders, _) = UnvalidatedOrders::unpack_length_prefixed(&packed).unwrap();
+    let results = orders.into_valid_orders_iter().collect::<Vec<_>>();
+    assert_eq!(results.len(), 5);
+    assert!(matches!(
+        results[2],
+        Err(DropsetError::InvalidPriceMantissa)
+    ));
 
-    // Test 5: Zero-initialized orders (0 valid, since 0 < lower bound)
-    let zero_initialized = UnvalidatedOrders::new([]);
-    assert_eq!(zero_initialized.into_valid_order_infos_iter().count(), 0);
+    // Test 4: Zero-initialized orders are invalid, since 0 < lower bound.
+    assert_eq!(count_valid(&[OrderInfoArgs::new(0, 0, 0, 0)]), 0);
 
-    // Test 6: Three valid orders followed by zero-initialized
-    let three_valid_then_zeros = UnvalidatedOrders::new([
-        OrderInfoArgs::new_unscaled(11_000_000, 1),
-        OrderInfoArgs::new_unscaled(12_000_000, 2),
-        OrderInfoArgs::new_unscaled(13_000_000, 3),
-    ]);
-    assert_eq!(
-        three_valid_then_zeros.into_valid_order_infos_iter().count(),
-        3
-    );
+    // Test 5: No orders
+    assert_eq!(count_valid(&[]), 0);
 
-    // Test 7: Valid at boundaries
-    let boundary_valid = UnvalidatedOrders::new([
+    // Test 6: Valid at boundaries
+    let boundary_valid = [
         // Lower bound (valid)
         OrderInfoArgs {
             price_mantissa: MANTISSA_DIGITS_LOWER_BOUND,
@@ -122,6 +130,66 @@ fn iterator_stops_at_first_invalid_order() {
             base_exponent_biased: 16,
             quote_exponent_biased: 16,
         },
+    ];
+    assert_eq!(count_valid(&boundary_valid), 2);
+}
+
+#[test]
+fn iterator_pairs_orders_with_client_order_ids() {
+    let packed = pack_orders(&[
+        UnvalidatedOrder::new(OrderInfoArgs::new_unscaled(11_000_000, 1), 7),
+        UnvalidatedOrder::new(OrderInfoArgs::new_unscaled(12_000_000, 2), 8),
     ]);
-    assert_eq!(boundary_valid.into_valid_order_infos_iter().count(), 2);
+    let (orders, _) = UnvalidatedOrders::unpack_length_prefixed(&packed).unwrap();
+    let client_order_ids = orders
+        .into_valid_orders_iter()
+        .map(|order| order.map(|(_, client_order_id)| client_order_id))
+        .collect::<Result<Vec<_>, _>>();
+    assert_eq!(client_order_ids, Ok(vec![7, 8]));
+
+    // Orders created from order info args alone default to a client order id of `0`.
+    let packed = pack_orders(&[OrderInfoArgs::new_unscaled(11_000_000, 1).into()]);
+    let (orders, _) = UnvalidatedOrders::unpack_length_prefixed(&packed).unwrap();
+    assert!(orders.iter().all(|order| order.client_order_id == 0));
+}
+
+#[test]
+fn unpack_length_prefixed_lists() {
+    let first = [
+        OrderInfoArgs::new_unscaled(11_000_000, 1).into(),
+        OrderInfoArgs::new_unscaled(12_000_000, 2).into(),
+    ];
+    let second = [OrderInfoArgs::new_unscaled(13_000_000, 3).into()];
+    let mut packed = pack_orders(&first);
+    UnvalidatedOrders::pack_length_prefixed(&second, &mut packed);
+
+    // Each list is read from the front of the data, returning the bytes after it.
+    let (orders, rest) = UnvalidatedOrders::unpack_length_prefixed(&packed).unwrap();
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders.iter().collect::<Vec<_>>(), first);
+    let (orders, rest) = UnvalidatedOrders::unpack_length_prefixed(rest).unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders.iter().collect::<Vec<_>>(), second);
+    assert!(rest.is_empty());
+
+    // Lists longer than the old fixed cap of 10 orders are supported.
+    let long = (0..25)
+        .map(|i| OrderInfoArgs::new_unscaled(11_000_000 + i, 1).into())
+        .collect::<Vec<UnvalidatedOrder>>();
+    let packed = pack_orders(&long);
+    let (orders, _) = UnvalidatedOrders::unpack_length_prefixed(&packed).unwrap();
+    assert_eq!(orders.len(), 25);
+    assert!(orders.into_valid_orders_iter().all(|order| order.is_ok()));
+
+    // The length prefix is missing.
+    assert!(UnvalidatedOrders::unpack_length_prefixed(&[0; 3]).is_err());
+
+    // The length prefix claims more orders than there are bytes for.
+    let truncated = &packed[..packed.len() - 1];
+    assert!(UnvalidatedOrders::unpack_length_prefixed(truncated).is_err());
+
+    // An empty list only needs the length prefix.
+    let (orders, rest) = UnvalidatedOrders::unpack_length_prefixed(&[0; 4]).unwrap();
+    assert!(orders.is_empty());
+    assert!(rest.is_empty());
 }
@@ -15,10 +15,11 @@ use crate::{
     render::Feature,
 };
 
-/// Render the account loader function.
+/// Render the account loader functions.
 ///
-/// The account loader function fallibly attempts to structure a slice of `AccountView`s into the
-/// corresponding struct of ordered accounts.
+/// The account loader functions fallibly attempt to structure a slice of `AccountView`s into the
+/// corresponding struct of ordered accounts. `load_accounts_with_remaining` additionally permits
+/// trailing accounts and returns them, which is how instructions accept optional accounts.
 pub fn render_account_loader(
     feature: Feature,
     instruction_variant: &InstructionVariant,
@@ -50,5 +51,21 @@ pub fn render_account_loader(
                 #(#accounts),*
             })
         }
+
+        #[inline(always)]
+        pub fn load_accounts_with_remaining(
+            accounts: #lifetimed_ref [#account_field_type],
+        ) -> Result<(Self, #lifetimed_ref [#account_field_type]), #base> {
+            let [ #(#accounts,)* remaining @ .. ] = accounts else {
+                return Err(#base::#variant);
+            };
+
+            Ok((
+                Self {
+                    #(#accounts),*
+                },
+                remaining,
+            ))
+        }
     }
 }
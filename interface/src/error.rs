@@ -47,6 +47,30 @@ pub enum DropsetError {
     PostOnlyWouldImmediatelyFill,
     AmountFilledVsTransferredMismatch,
     OrdersNotSorted,
+    SlippageExceeded,
+    InvalidTimeInForce,
+    FillOrKillNotFilled,
+    InvalidFeeSchedule,
+    IncorrectFeeRecipient,
+    InvalidMarketConfig,
+    OrderSizeBelowMinimum,
+    PriceNotOnTick,
+    IncorrectMarketAuthority,
+    InvalidMarketStatus,
+    MarketStatusForbidsInstruction,
+    MarketIsClosed,
+    MarketNotEmpty,
+    InvalidSideFilter,
+    InvalidExpiryKind,
+    OrderAlreadyExpired,
+    InvalidSelfTradeBehavior,
+    SelfTradeAborted,
+    InvalidBatchReplaceMode,
+    SeatHasOpenOrders,
+    InvalidNumFreeSectors,
+    UnsupportedMintExtension,
+    InvalidReturnData,
+    UnauthorizedSeatCloser,
 }
 
 impl From<DropsetError> for ProgramError {
@@ -115,6 +139,40 @@ impl From<DropsetError> for &'static str {
                 "The amount filled doesn't match the amount transferred."
             }
             DropsetError::OrdersNotSorted => "Passed orders aren't properly sorted",
+            DropsetError::SlippageExceeded => "Amount filled is less than the minimum amount out",
+            DropsetError::InvalidTimeInForce => "Invalid time in force",
+            DropsetError::FillOrKillNotFilled => "Fill or kill order couldn't be completely filled",
+            DropsetError::InvalidFeeSchedule => "Invalid market fee schedule",
+            DropsetError::IncorrectFeeRecipient => "Incorrect market fee recipient",
+            DropsetError::InvalidMarketConfig => "Invalid market config",
+            DropsetError::OrderSizeBelowMinimum => "Order size is below the market minimum",
+            DropsetError::PriceNotOnTick => "Price mantissa isn't a multiple of the market tick",
+            DropsetError::IncorrectMarketAuthority => "Incorrect market authority",
+            DropsetError::InvalidMarketStatus => "Invalid market status",
+            DropsetError::MarketStatusForbidsInstruction => {
+                "The market's current status doesn't allow this instruction"
+            }
+            DropsetError::MarketIsClosed => "The market is closed",
+            DropsetError::MarketNotEmpty => {
+                "The market still has seats, open orders or uncollected fees"
+            }
+            DropsetError::InvalidSideFilter => "Invalid side filter",
+            DropsetError::InvalidExpiryKind => "Invalid order expiry kind",
+            DropsetError::OrderAlreadyExpired => "The order's expiry has already passed",
+            DropsetError::InvalidSelfTradeBehavior => "Invalid self-trade behavior",
+            DropsetError::SelfTradeAborted => "The order would match against the user's own order",
+            DropsetError::InvalidBatchReplaceMode => "Invalid batch replace mode",
+            DropsetError::SeatHasOpenOrders => "The seat still has open orders",
+            DropsetError::InvalidNumFreeSectors => {
+                "The market doesn't have enough free sectors to keep"
+            }
+            DropsetError::UnsupportedMintExtension => {
+                "The token 2022 mint has an extension the market doesn't support"
+            }
+            DropsetError::InvalidReturnData => "The return data isn't valid dropset return data",
+            DropsetError::UnauthorizedSeatCloser => {
+                "Only the seat's user or the market authority can close a seat that isn't abandoned"
+            }
         }
     }
 }
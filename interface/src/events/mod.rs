@@ -32,6 +32,7 @@ pub enum DropsetEventTag {
     WithdrawEvent,
 
     #[args(market: Address, "The newly registered market.")]
+    #[args(market_id: u16, "The market ID used to derive the market PDA.")]
     RegisterMarketEvent,
 
     #[args(is_bid: bool, "Whether or not the order is a bid. If false, the order is an ask.")]
@@ -39,10 +40,16 @@ pub enum DropsetEventTag {
     #[args(order_sector_index: u32, "The posted order's sector index.")]
     #[args(base_atoms: u64, "The size of the order's base atoms to fill.")]
     #[args(quote_atoms: u64, "The size of the order's quote atoms to fill.")]
+    #[args(base_filled: u64, "The amount of base atoms filled against crossing orders before posting.")]
+    #[args(quote_filled: u64, "The amount of quote atoms filled against crossing orders before posting.")]
+    #[args(taker_fee: u64, "The taker fee charged on the filled amount, in atoms of the asset the taker received.")]
+    #[args(base_dropped: u64, "The base atoms left after filling that were dropped instead of posted, because the remainder was below the market's minimum order size or too small to be represented in both base and quote.")]
+    #[args(client_order_id: u64, "The order's client-provided order id.")]
     PostOrderEvent,
 
     #[args(is_bid: bool, "Whether or not the order is a bid. If false, the order is an ask.")]
     #[args(user_seat_sector_index: u32, "The user's market seat sector index.")]
+    #[args(client_order_id: u64, "The canceled order's client-provided order id.")]
     CancelOrderEvent,
 
     #[args(order_size: u64, "The order size in atoms.")]
@@ -50,6 +57,9 @@ pub enum DropsetEventTag {
     #[args(is_base: bool, "Whether or not the order size is denominated in base. If not, it's in quote.")]
     #[args(base_filled: u64, "The amount of base atoms filled.")]
     #[args(quote_filled: u64, "The amount of quote atoms filled.")]
+    #[args(time_in_force: u8, "The order's time-in-force policy as a `TimeInForce` discriminant.")]
+    #[args(taker_fee: u64, "The taker fee charged, in atoms of the asset the taker received.")]
+    #[args(maker_rebates: u64, "The total maker rebates paid out of the taker fee.")]
     MarketOrderEvent,
 
     #[args(user_seat_sector_index: u32, "The user's market seat sector index.")]
@@ -57,6 +67,82 @@ pub enum DropsetEventTag {
 
     #[args(num_sectors: u16, "The number of free sectors added to the market account.")]
     ExpandMarketEvent,
+
+    #[args(base_amount: u64, "The amount of base fees collected.")]
+    #[args(quote_amount: u64, "The amount of quote fees collected.")]
+    CollectFeesEvent,
+
+    #[args(maker_seat_sector_index: u32, "The filled maker's market seat sector index.")]
+    #[args(order_sector_index: u32, "The filled maker order's sector index.")]
+    #[args(encoded_price: u32, "The filled maker order's encoded price.")]
+    #[args(base_filled: u64, "The amount of base atoms filled.")]
+    #[args(quote_filled: u64, "The amount of quote atoms filled.")]
+    #[args(fully_filled: bool, "Whether or not the maker order was completely filled and removed from the book.")]
+    FillEvent,
+
+    #[args(user_seat_sector_index: u32, "The user's market seat sector index.")]
+    #[args(bids_canceled: u8, "The number of the user's bids canceled.")]
+    #[args(asks_canceled: u8, "The number of the user's asks canceled.")]
+    #[args(bids_posted: u8, "The number of new bids posted.")]
+    #[args(asks_posted: u8, "The number of new asks posted.")]
+    BatchReplaceEvent,
+
+    #[args(min_order_base_atoms: u64, "The new minimum number of base atoms in a posted order.")]
+    #[args(price_mantissa_tick: u32, "The new price mantissa tick.")]
+    #[args(max_orders_per_user: u8, "The new max number of orders per user on each side of the book.")]
+    #[args(fee_recipient: Address, "The new fee recipient.")]
+    #[args(taker_fee_bps: u16, "The new taker fee in basis points.")]
+    #[args(maker_rebate_bps: u16, "The new maker rebate in basis points.")]
+    UpdateMarketConfigEvent,
+
+    #[args(status: u8, "The market's new status as a `MarketStatus` discriminant.")]
+    SetMarketStatusEvent,
+
+    #[args(lamports_reclaimed: u64, "The lamports reclaimed from the market account and its token accounts.")]
+    CloseMarketEvent,
+
+    #[args(is_bid: bool, "Whether or not the expired order was a bid. If false, it was an ask.")]
+    #[args(maker_seat_sector_index: u32, "The expired order maker's market seat sector index.")]
+    #[args(order_sector_index: u32, "The expired order's sector index.")]
+    #[args(encoded_price: u32, "The expired order's encoded price.")]
+    #[args(base_remaining: u64, "The base atoms left in the order when it was removed.")]
+    #[args(quote_remaining: u64, "The quote atoms left in the order when it was removed.")]
+    ExpireOrderEvent,
+
+    #[args(self_trade_behavior: u8, "The taker's `SelfTradeBehavior` discriminant that was applied.")]
+    #[args(maker_seat_sector_index: u32, "The taker's own market seat sector index.")]
+    #[args(order_sector_index: u32, "The self-matched resting order's sector index.")]
+    #[args(encoded_price: u32, "The self-matched resting order's encoded price.")]
+    #[args(base_decremented: u64, "The base atoms removed from the resting order without a fill.")]
+    #[args(quote_decremented: u64, "The quote atoms removed from the resting order without a fill.")]
+    #[args(order_removed: bool, "Whether or not the resting order was removed from the book.")]
+    SelfTradeEvent,
+
+    #[args(is_bid: bool, "Whether or not the order is a bid. If false, the order is an ask.")]
+    #[args(user_seat_sector_index: u32, "The user's market seat sector index.")]
+    #[args(order_sector_index: u32, "The amended order's sector index.")]
+    #[args(old_encoded_price: u32, "The order's encoded price before it was amended.")]
+    #[args(new_encoded_price: u32, "The order's encoded price after it was amended.")]
+    #[args(base_atoms: u64, "The amended order's base atoms left to fill.")]
+    #[args(quote_atoms: u64, "The amended order's quote atoms left to fill.")]
+    #[args(kept_priority: bool, "Whether or not the order kept its time priority at its price level.")]
+    #[args(client_order_id: u64, "The order's client-provided order id.")]
+    AmendOrderEvent,
+
+    #[args(num_sectors_removed: u32, "The number of free sectors truncated from the market account.")]
+    #[args(lamports_refunded: u64, "The excess rent lamports refunded to the market authority.")]
+    CompactMarketEvent,
+
+    #[args(seat_sector_index: u32, "The user's market seat sector index.")]
+    #[args(delegate: Address, "The seat's new delegate. The default address means the seat has no delegate.")]
+    SetSeatDelegateEvent,
+
+    #[args(amount: u64, "The amount deposited.")]
+    #[args(is_base: bool, "Which token, i.e., `true` => base token, `false` => quote token.")]
+    #[args(seat_sector_index: u32, "The beneficiary's (possibly newly registered) market seat sector index.")]
+    #[args(funder: Address, "The account that funded the deposit.")]
+    #[args(beneficiary: Address, "The user whose seat was credited with the deposit.")]
+    DepositForEvent,
 }
 
 pub use private::DropsetEventMarker;
@@ -78,4 +164,16 @@ mod private {
     impl DropsetEventMarker for MarketOrderEventInstructionData {}
     impl DropsetEventMarker for CloseSeatEventInstructionData {}
     impl DropsetEventMarker for ExpandMarketEventInstructionData {}
+    impl DropsetEventMarker for CollectFeesEventInstructionData {}
+    impl DropsetEventMarker for FillEventInstructionData {}
+    impl DropsetEventMarker for BatchReplaceEventInstructionData {}
+    impl DropsetEventMarker for UpdateMarketConfigEventInstructionData {}
+    impl DropsetEventMarker for SetMarketStatusEventInstructionData {}
+    impl DropsetEventMarker for CloseMarketEventInstructionData {}
+    impl DropsetEventMarker for ExpireOrderEventInstructionData {}
+    impl DropsetEventMarker for SelfTradeEventInstructionData {}
+    impl DropsetEventMarker for AmendOrderEventInstructionData {}
+    impl DropsetEventMarker for CompactMarketEventInstructionData {}
+    impl DropsetEventMarker for SetSeatDelegateEventInstructionData {}
+    impl DropsetEventMarker for DepositForEventInstructionData {}
 }
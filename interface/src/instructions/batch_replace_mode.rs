@@ -0,0 +1,54 @@
+use crate::error::DropsetError;
+
+/// How a `BatchReplace` instruction applies the new orders to the user's current orders, passed as
+/// a raw `u8` in instruction data.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(strum_macros::FromRepr, strum_macros::EnumIter))]
+pub enum BatchReplaceMode {
+    /// Cancel all of the user's orders on each side, then post the new orders.
+    Replace,
+    /// Only change the user's orders that differ from the new orders:
+    /// - Orders with the same price and size as a new order are left untouched.
+    /// - Orders with the same price and a larger size than a new order are reduced in place.
+    /// - All other orders are canceled, and new orders without a matching order are posted.
+    ///
+    /// Orders that are left untouched or reduced in place keep their time priority.
+    Incremental,
+}
+
+impl TryFrom<u8> for BatchReplaceMode {
+    type Error = DropsetError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Replace),
+            1 => Ok(Self::Incremental),
+            _ => Err(DropsetError::InvalidBatchReplaceMode),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn batch_replace_mode_try_from_u8_exhaustive() {
+        for v in 0..=u8::MAX {
+            match BatchReplaceMode::from_repr(v) {
+                Some(variant) => assert_eq!(BatchReplaceMode::try_from(v), Ok(variant)),
+                None => assert_eq!(
+                    BatchReplaceMode::try_from(v),
+                    Err(DropsetError::InvalidBatchReplaceMode)
+                ),
+            }
+        }
+        for variant in BatchReplaceMode::iter() {
+            assert_eq!(BatchReplaceMode::try_from(variant as u8), Ok(variant));
+        }
+    }
+}
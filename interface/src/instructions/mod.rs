@@ -7,11 +7,21 @@
 //! The `program` feature: [`crate::instructions::generated_program`]
 //! The `client` feature: [`crate::instructions::generated_client`]
 
+mod batch_replace_mode;
+mod order_expiry;
 mod orders;
+mod self_trade_behavior;
+mod side_filter;
+mod time_in_force;
 
+pub use batch_replace_mode::*;
 use instruction_macros::ProgramInstruction;
+pub use order_expiry::*;
 pub use orders::*;
 use price::OrderInfoArgs;
+pub use self_trade_behavior::*;
+pub use side_filter::*;
+pub use time_in_force::*;
 
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, ProgramInstruction)]
@@ -35,6 +45,9 @@ pub enum DropsetInstruction {
     #[args(sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
     CloseSeat,
 
+    // Deposit optionally takes a trailing `[WRITE, SIGNER]` payer account that funds the market
+    // expansion when registering a new seat on a market with no free sectors. The user pays for the
+    // expansion if it's omitted.
     #[account(0,                   name = "event_authority", desc = "The event authority PDA signer.")]
     #[account(1, signer, writable, name = "user",            desc = "The user depositing or registering their seat.")]
     #[account(2, writable,         name = "market_account",  desc = "The market account PDA.")]
@@ -73,8 +86,19 @@ pub enum DropsetInstruction {
     #[account(10,          name = "system_program",      desc = "The system program.")]
     #[account(11,          name = "dropset_program",     desc = "The dropset program.")]
     #[args(num_sectors: u16, "The number of sectors to preallocate for the market.")]
+    #[args(market_id: u16, "The market ID used to derive the market PDA, allowing multiple markets per base/quote pair.")]
+    #[args(fee_recipient: Address, "The address allowed to collect the market's accrued fees.")]
+    #[args(taker_fee_bps: u16, "The taker fee in basis points.")]
+    #[args(maker_rebate_bps: u16, "The maker rebate in basis points, paid out of the taker fee.")]
+    #[args(authority: Address, "The address allowed to update the market's config.")]
+    #[args(min_order_base_atoms: u64, "The minimum number of base atoms in a posted order.")]
+    #[args(price_mantissa_tick: u32, "The tick that every posted order's price mantissa must be a multiple of.")]
+    #[args(max_orders_per_user: u8, "The max number of orders a user can have on each side of the book.")]
     RegisterMarket,
 
+    // PostOrder optionally takes a trailing `[WRITE, SIGNER]` payer account and the system program.
+    // If they're passed and the market has no free sectors for the posted order, the payer funds a
+    // market expansion instead of the instruction failing.
     #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
     #[account(1, signer,   name = "user",            desc = "The user posting an order.")]
     #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
@@ -82,6 +106,10 @@ pub enum DropsetInstruction {
     #[args(order_info_args: OrderInfoArgs, "The order info arguments.")]
     #[args(is_bid: bool, "Whether or not the order is a bid. If false, the order is an ask.")]
     #[args(user_sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
+    #[args(post_only: bool, "Whether or not the order must rest on the book without filling. If not, it fills against crossing orders first and posts the remainder.")]
+    #[args(expiry: OrderExpiry, "The optional slot or unix timestamp after which the posted order can no longer be filled.")]
+    #[args(self_trade_behavior: u8, "What to do if the order crosses one of the user's own orders as a `SelfTradeBehavior` discriminant.")]
+    #[args(client_order_id: u64, "An opaque, client-provided id stored on the posted order.")]
     PostOrder,
 
     #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
@@ -93,13 +121,15 @@ pub enum DropsetInstruction {
     #[args(user_sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
     CancelOrder,
 
+    // BatchReplace's order lists have a dynamic length, so they aren't generated args. The new
+    // bids and then the new asks follow the fixed size args as two length-prefixed
+    // `UnvalidatedOrders` lists, each replacing the user's current orders on that side.
     #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
     #[account(1, signer,   name = "user",            desc = "The user batch replacing orders.")]
     #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
     #[account(3,           name = "dropset_program", desc = "The dropset program.")]
     #[args(user_sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
-    #[args(new_bids: UnvalidatedOrders, "The new bids to replace the user's current bids.")]
-    #[args(new_asks: UnvalidatedOrders, "The new asks to replace the user's current asks.")]
+    #[args(mode: u8, "How the new orders replace the user's current orders as a `BatchReplaceMode` discriminant.")]
     BatchReplace,
 
     #[account(0,           name = "event_authority",     desc = "The event authority PDA signer.")]
@@ -117,6 +147,10 @@ pub enum DropsetInstruction {
     #[args(order_size: u64, "The order size; aka the number of atoms to fill.")]
     #[args(is_buy: bool, "Whether or not the order is a market buy. If not, it's a market sell.")]
     #[args(is_base: bool, "Whether or not the order size is denominated in base. If not, it's in quote.")]
+    #[args(limit_price: u32, "The worst encoded price the order may fill at. Use infinity for an unconstrained buy and zero for an unconstrained sell.")]
+    #[args(min_amount_out: u64, "The minimum amount of atoms the taker must receive; base for a buy and quote for a sell.")]
+    #[args(time_in_force: u8, "The order's time-in-force policy as a `TimeInForce` discriminant.")]
+    #[args(self_trade_behavior: u8, "What to do if the order matches one of the user's own orders as a `SelfTradeBehavior` discriminant.")]
     MarketOrder,
 
     // FlushEvents is an internal instruction and can only be called by the program. It does have
@@ -131,6 +165,210 @@ pub enum DropsetInstruction {
     #[account(4,                   name = "system_program",      desc = "The system program.")]
     #[args(num_sectors: u16, "The number of free sectors to add to the market account.")]
     ExpandMarket,
+
+    #[account(0,           name = "event_authority",     desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "fee_recipient",       desc = "The market's fee recipient.")]
+    #[account(2, writable, name = "market_account",      desc = "The market account PDA.")]
+    #[account(3, writable, name = "base_recipient_ata",  desc = "The fee recipient's associated base token account.")]
+    #[account(4, writable, name = "quote_recipient_ata", desc = "The fee recipient's associated quote token account.")]
+    #[account(5, writable, name = "base_market_ata",     desc = "The market's associated base token account.")]
+    #[account(6, writable, name = "quote_market_ata",    desc = "The market's associated quote token account.")]
+    #[account(7,           name = "base_mint",           desc = "The base token mint account.")]
+    #[account(8,           name = "quote_mint",          desc = "The quote token mint account.")]
+    #[account(9,           name = "base_token_program",  desc = "The base mint's token program.")]
+    #[account(10,          name = "quote_token_program", desc = "The quote mint's token program.")]
+    #[account(11,          name = "dropset_program",     desc = "The dropset program.")]
+    CollectFees,
+
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "authority",       desc = "The market authority.")]
+    #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(3,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(min_order_base_atoms: u64, "The new minimum number of base atoms in a posted order.")]
+    #[args(price_mantissa_tick: u32, "The new tick that every posted order's price mantissa must be a multiple of.")]
+    #[args(max_orders_per_user: u8, "The new max number of orders a user can have on each side of the book.")]
+    #[args(fee_recipient: Address, "The new address allowed to collect the market's accrued fees, including fees accrued before the update.")]
+    #[args(taker_fee_bps: u16, "The new taker fee in basis points.")]
+    #[args(maker_rebate_bps: u16, "The new maker rebate in basis points, paid out of the taker fee.")]
+    UpdateMarketConfig,
+
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "authority",       desc = "The market authority.")]
+    #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(3,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(status: u8, "The market's new status as a `MarketStatus` discriminant.")]
+    SetMarketStatus,
+
+    #[account(0,                   name = "event_authority",     desc = "The event authority PDA signer.")]
+    #[account(1, signer, writable, name = "authority",           desc = "The market authority, which receives the reclaimed rent.")]
+    #[account(2, writable,         name = "market_account",      desc = "The market account PDA.")]
+    #[account(3, writable,         name = "base_authority_ata",  desc = "The authority's associated base token account, which receives any leftover base tokens.")]
+    #[account(4, writable,         name = "quote_authority_ata", desc = "The authority's associated quote token account, which receives any leftover quote tokens.")]
+    #[account(5, writable,         name = "base_market_ata",     desc = "The market's associated base token account.")]
+    #[account(6, writable,         name = "quote_market_ata",    desc = "The market's associated quote token account.")]
+    #[account(7, writable,         name = "base_mint",           desc = "The base token mint account, which receives any transfer fees withheld in the market's base token account.")]
+    #[account(8, writable,         name = "quote_mint",          desc = "The quote token mint account, which receives any transfer fees withheld in the market's quote token account.")]
+    #[account(9,                   name = "base_token_program",  desc = "The base mint's token program.")]
+    #[account(10,                  name = "quote_token_program", desc = "The quote mint's token program.")]
+    #[account(11,                  name = "dropset_program",     desc = "The dropset program.")]
+    CloseMarket,
+
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "user",            desc = "The user canceling their orders.")]
+    #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(3,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(user_sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
+    #[args(side_filter: u8, "Which side(s) of the book to cancel orders on as a `SideFilter` discriminant.")]
+    CancelAllOrders,
+
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(2,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(max_orders: u16, "The max number of expired orders to prune, to bound the compute used.")]
+    PruneExpiredOrders,
+
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "user",            desc = "The user canceling an order.")]
+    #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(3,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(client_order_id: u64, "The client order id of the order to cancel.")]
+    #[args(is_bid: bool, "Whether or not the order is a bid. If false, the order is an ask.")]
+    #[args(user_sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
+    CancelOrderByClientId,
+
+    // AmendOrder keeps the order's time priority when it only reduces the order's size at the same
+    // price. Otherwise, the order is atomically canceled and reposted as a post-only order.
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "user",            desc = "The user amending an order.")]
+    #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(3,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(encoded_price: u32, "The encoded price of the order to amend.")]
+    #[args(is_bid: bool, "Whether or not the order is a bid. If false, the order is an ask.")]
+    #[args(user_sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
+    #[args(order_info_args: OrderInfoArgs, "The amended order's price and size.")]
+    AmendOrder,
+
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(2,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(max_seats: u16, "The max number of empty seats to close, to bound the compute used.")]
+    CloseEmptySeats,
+
+    // SettleAndCloseSeat lets the seat's owner or the market authority close the seat without the
+    // other's signature, and lets anyone settle and close an abandoned seat, i.e., one with only
+    // dust available and no delegate. The seat must not have any open orders, and its remaining
+    // balances are transferred to the owner's token accounts.
+    #[account(0,           name = "event_authority",      desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "closer",               desc = "The seat's owner, the market authority, or anyone for an abandoned seat.")]
+    #[account(2,           name = "user",                 desc = "The owner of the seat being closed.")]
+    #[account(3, writable, name = "market_account",       desc = "The market account PDA.")]
+    #[account(4, writable, name = "base_user_ata",        desc = "The seat owner's associated base token account.")]
+    #[account(5, writable, name = "quote_user_ata",       desc = "The seat owner's associated quote token account.")]
+    #[account(6, writable, name = "base_market_ata",      desc = "The market's associated base token account.")]
+    #[account(7, writable, name = "quote_market_ata",     desc = "The market's associated quote token account.")]
+    #[account(8,           name = "base_mint",            desc = "The base token mint account.")]
+    #[account(9,           name = "quote_mint",           desc = "The quote token mint account.")]
+    #[account(10,          name = "base_token_program",   desc = "The base mint's token program.")]
+    #[account(11,          name = "quote_token_program",  desc = "The quote mint's token program.")]
+    #[account(12,          name = "dropset_program",      desc = "The dropset program.")]
+    #[args(sector_index_hint: u32, "A hint indicating which sector the owner's seat resides in.")]
+    SettleAndCloseSeat,
+
+    // CompactMarket relocates every live sector to the front of the market account, truncates the
+    // free sectors past them and refunds the excess rent to the authority.
+    //
+    // Relocated seats and orders aren't reported individually, so every seat and order sector index
+    // a client cached before compacting, e.g. a seat's `user_sector_index_hint`, may be stale and
+    // must be looked up again from the market account afterwards.
+    #[account(0,                   name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer, writable, name = "authority",       desc = "The market authority, which receives the refunded rent.")]
+    #[account(2, writable,         name = "market_account",  desc = "The market account PDA.")]
+    #[account(3,                   name = "dropset_program", desc = "The dropset program.")]
+    #[args(num_free_sectors: u32, "The number of free sectors to keep after compacting the market.")]
+    CompactMarket,
+
+    // SeatMarketOrder settles the taker's side of the fill against the taker's seat balances
+    // instead of their token accounts, so no token transfers are made.
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "user",            desc = "The user creating the market order, aka the taker.")]
+    #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(3,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(order_size: u64, "The order size; aka the number of atoms to fill.")]
+    #[args(is_buy: bool, "Whether or not the order is a market buy. If not, it's a market sell.")]
+    #[args(is_base: bool, "Whether or not the order size is denominated in base. If not, it's in quote.")]
+    #[args(limit_price: u32, "The worst encoded price the order may fill at. Use infinity for an unconstrained buy and zero for an unconstrained sell.")]
+    #[args(min_amount_out: u64, "The minimum amount of atoms the taker's seat must receive; base for a buy and quote for a sell.")]
+    #[args(time_in_force: u8, "The order's time-in-force policy as a `TimeInForce` discriminant.")]
+    #[args(self_trade_behavior: u8, "What to do if the order matches one of the user's own orders as a `SelfTradeBehavior` discriminant.")]
+    #[args(user_sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
+    SeatMarketOrder,
+
+    // SetSeatDelegate can only be signed by the seat's user. The delegate can place and cancel
+    // orders for the seat but can't withdraw from or close it.
+    #[account(0,           name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "user",            desc = "The user setting their seat's delegate.")]
+    #[account(2, writable, name = "market_account",  desc = "The market account PDA.")]
+    #[account(3,           name = "dropset_program", desc = "The dropset program.")]
+    #[args(user_sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
+    #[args(delegate: Address, "The seat's new delegate. The default address removes the current delegate.")]
+    SetSeatDelegate,
+
+    // DepositBoth deposits base and quote in a single instruction. A zero amount skips that token,
+    // but at least one amount must be non-zero. Like Deposit, it optionally takes a trailing
+    // `[WRITE, SIGNER]` payer account that funds the market expansion when registering a new seat.
+    #[account(0,                   name = "event_authority",     desc = "The event authority PDA signer.")]
+    #[account(1, signer, writable, name = "user",                desc = "The user depositing or registering their seat.")]
+    #[account(2, writable,         name = "market_account",      desc = "The market account PDA.")]
+    #[account(3, writable,         name = "base_user_ata",       desc = "The user's associated base token account.")]
+    #[account(4, writable,         name = "quote_user_ata",      desc = "The user's associated quote token account.")]
+    #[account(5, writable,         name = "base_market_ata",     desc = "The market's associated base token account.")]
+    #[account(6, writable,         name = "quote_market_ata",    desc = "The market's associated quote token account.")]
+    #[account(7,                   name = "base_mint",           desc = "The base token mint account.")]
+    #[account(8,                   name = "quote_mint",          desc = "The quote token mint account.")]
+    #[account(9,                   name = "base_token_program",  desc = "The base mint's token program.")]
+    #[account(10,                  name = "quote_token_program", desc = "The quote mint's token program.")]
+    #[account(11,                  name = "system_program",      desc = "The system program.")]
+    #[account(12,                  name = "dropset_program",     desc = "The dropset program.")]
+    #[args(base_amount: u64, "The amount of base to deposit.")]
+    #[args(quote_amount: u64, "The amount of quote to deposit.")]
+    #[args(sector_index_hint: u32, "A hint indicating which sector the user's seat resides in (pass `NIL` when registering a new seat).")]
+    DepositBoth,
+
+    // WithdrawBoth withdraws base and quote in a single instruction. A zero amount skips that token,
+    // but at least one amount must be non-zero.
+    #[account(0,           name = "event_authority",     desc = "The event authority PDA signer.")]
+    #[account(1, signer,   name = "user",                desc = "The user withdrawing.")]
+    #[account(2, writable, name = "market_account",      desc = "The market account PDA.")]
+    #[account(3, writable, name = "base_user_ata",       desc = "The user's associated base token account.")]
+    #[account(4, writable, name = "quote_user_ata",      desc = "The user's associated quote token account.")]
+    #[account(5, writable, name = "base_market_ata",     desc = "The market's associated base token account.")]
+    #[account(6, writable, name = "quote_market_ata",    desc = "The market's associated quote token account.")]
+    #[account(7,           name = "base_mint",           desc = "The base token mint account.")]
+    #[account(8,           name = "quote_mint",          desc = "The quote token mint account.")]
+    #[account(9,           name = "base_token_program",  desc = "The base mint's token program.")]
+    #[account(10,          name = "quote_token_program", desc = "The quote mint's token program.")]
+    #[account(11,          name = "dropset_program",     desc = "The dropset program.")]
+    #[args(base_amount: u64, "The amount of base to withdraw.")]
+    #[args(quote_amount: u64, "The amount of quote to withdraw.")]
+    #[args(sector_index_hint: u32, "A hint indicating which sector the user's seat resides in.")]
+    WithdrawBoth,
+
+    // DepositFor deposits from the funder's token account into the beneficiary's seat, registering
+    // the seat for the beneficiary if `sector_index_hint` is `NIL`. The funder pays for the market
+    // expansion if registering the seat requires one. The beneficiary doesn't sign.
+    #[account(0,                   name = "event_authority", desc = "The event authority PDA signer.")]
+    #[account(1, signer, writable, name = "funder",          desc = "The account funding the deposit.")]
+    #[account(2, writable,         name = "market_account",  desc = "The market account PDA.")]
+    #[account(3, writable,         name = "funder_ata",      desc = "The funder's associated token account.")]
+    #[account(4, writable,         name = "market_ata",      desc = "The market's associated token account.")]
+    #[account(5,                   name = "mint",            desc = "The token mint account.")]
+    #[account(6,                   name = "token_program",   desc = "The mint's token program.")]
+    #[account(7,                   name = "system_program",  desc = "The system program.")]
+    #[account(8,                   name = "dropset_program", desc = "The dropset program.")]
+    #[args(amount: u64, "The amount to deposit.")]
+    #[args(sector_index_hint: u32, "A hint indicating which sector the beneficiary's seat resides in (pass `NIL` when registering a new seat).")]
+    #[args(beneficiary: Address, "The user whose seat is credited with the deposit.")]
+    DepositFor,
 }
 
 #[cfg(test)]
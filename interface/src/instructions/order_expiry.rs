@@ -0,0 +1,170 @@
+use instruction_macros::{
+    Pack,
+    Unpack,
+};
+
+use crate::error::{
+    DropsetError,
+    DropsetResult,
+};
+
+/// The clock an order's expiry is measured against, passed as a raw `u8` in [`OrderExpiry`].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(strum_macros::FromRepr, strum_macros::EnumIter))]
+pub enum ExpiryKind {
+    /// The order never expires.
+    None,
+    /// The order expires once the current slot reaches the expiry value.
+    Slot,
+    /// The order expires once the current unix timestamp reaches the expiry value.
+    UnixTimestamp,
+}
+
+impl TryFrom<u8> for ExpiryKind {
+    type Error = DropsetError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Slot),
+            2 => Ok(Self::UnixTimestamp),
+            _ => Err(DropsetError::InvalidExpiryKind),
+        }
+    }
+}
+
+/// An optional slot or unix timestamp after which a resting order can no longer be matched.
+///
+/// Expired orders are skipped and removed from the book when a taker reaches them, and can be
+/// pruned by anyone with [`crate::instructions::DropsetInstruction::PruneExpiredOrders`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pack, Unpack, PartialEq, Eq)]
+pub struct OrderExpiry {
+    /// The [`ExpiryKind`] discriminant.
+    pub kind: u8,
+    /// The slot or unix timestamp at which the order expires. Ignored if the kind is
+    /// [`ExpiryKind::None`].
+    pub expires_at: u64,
+}
+
+impl OrderExpiry {
+    /// An expiry for an order that never expires.
+    pub const NONE: Self = Self {
+        kind: ExpiryKind::None as u8,
+        expires_at: 0,
+    };
+
+    /// Creates an expiry for an order that expires once the current slot reaches `slot`.
+    #[inline(always)]
+    pub const fn at_slot(slot: u64) -> Self {
+        Self {
+            kind: ExpiryKind::Slot as u8,
+            expires_at: slot,
+        }
+    }
+
+    /// Creates an expiry for an order that expires once the current unix timestamp reaches
+    /// `unix_timestamp`.
+    #[inline(always)]
+    pub const fn at_unix_timestamp(unix_timestamp: u64) -> Self {
+        Self {
+            kind: ExpiryKind::UnixTimestamp as u8,
+            expires_at: unix_timestamp,
+        }
+    }
+
+    /// Checks that the expiry kind is valid and that the expiry hasn't already passed.
+    #[inline(always)]
+    pub fn validate(&self, slot: u64, unix_timestamp: i64) -> DropsetResult {
+        ExpiryKind::try_from(self.kind)?;
+        if self.is_expired(slot, unix_timestamp) {
+            return Err(DropsetError::OrderAlreadyExpired);
+        }
+        Ok(())
+    }
+
+    /// Whether or not the expiry has been reached at the given slot and unix timestamp.
+    ///
+    /// An invalid expiry kind is treated the same as [`ExpiryKind::None`]; the program only ever
+    /// stores validated expiries.
+    #[inline(always)]
+    pub const fn is_expired(&self, slot: u64, unix_timestamp: i64) -> bool {
+        const SLOT: u8 = ExpiryKind::Slot as u8;
+        const UNIX_TIMESTAMP: u8 = ExpiryKind::UnixTimestamp as u8;
+
+        match self.kind {
+            SLOT => slot >= self.expires_at,
+            UNIX_TIMESTAMP => unix_timestamp >= 0 && unix_timestamp as u64 >= self.expires_at,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn expiry_kind_try_from_u8_exhaustive() {
+        for v in 0..=u8::MAX {
+            match ExpiryKind::from_repr(v) {
+                Some(variant) => assert_eq!(ExpiryKind::try_from(v), Ok(variant)),
+                None => assert_eq!(
+                    ExpiryKind::try_from(v),
+                    Err(DropsetError::InvalidExpiryKind)
+                ),
+            }
+        }
+        for variant in ExpiryKind::iter() {
+            assert_eq!(ExpiryKind::try_from(variant as u8), Ok(variant));
+        }
+    }
+
+    #[test]
+    fn order_expiry_is_expired() {
+        assert!(!OrderExpiry::NONE.is_expired(u64::MAX, i64::MAX));
+
+        let at_slot = OrderExpiry::at_slot(100);
+        assert!(!at_slot.is_expired(99, i64::MAX));
+        assert!(at_slot.is_expired(100, 0));
+        assert!(at_slot.is_expired(101, 0));
+
+        let at_timestamp = OrderExpiry::at_unix_timestamp(1_000);
+        assert!(!at_timestamp.is_expired(u64::MAX, 999));
+        assert!(!at_timestamp.is_expired(u64::MAX, -1));
+        assert!(at_timestamp.is_expired(0, 1_000));
+        assert!(at_timestamp.is_expired(0, 1_001));
+
+        let invalid_kind = OrderExpiry {
+            kind: 3,
+            expires_at: 0,
+        };
+        assert!(!invalid_kind.is_expired(u64::MAX, i64::MAX));
+    }
+
+    #[test]
+    fn order_expiry_validate() {
+        assert_eq!(OrderExpiry::NONE.validate(10, 10), Ok(()));
+        assert_eq!(OrderExpiry::at_slot(11).validate(10, 10), Ok(()));
+        assert_eq!(
+            OrderExpiry::at_slot(10).validate(10, 10),
+            Err(DropsetError::OrderAlreadyExpired)
+        );
+        assert_eq!(
+            OrderExpiry::at_unix_timestamp(10).validate(10, 10),
+            Err(DropsetError::OrderAlreadyExpired)
+        );
+        assert_eq!(
+            OrderExpiry {
+                kind: 3,
+                expires_at: 100,
+            }
+            .validate(10, 10),
+            Err(DropsetError::InvalidExpiryKind)
+        );
+    }
+}
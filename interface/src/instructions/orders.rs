@@ -1,7 +1,3 @@
-#![allow(rustdoc::private_intra_doc_links)]
-
-use core::mem::MaybeUninit;
-
 use instruction_macros::{
     Pack,
     Unpack,
@@ -12,183 +8,127 @@ use price::{
     OrderInfoArgs,
 };
 use solana_program_error::ProgramError;
-use static_assertions::const_assert_eq;
 
 use crate::{
-    instructions::orders::private::UpToTen,
-    state::user_order_sectors::{
-        MAX_ORDERS,
-        MAX_ORDERS_USIZE,
-    },
+    error::DropsetError,
+    state::U32_SIZE,
 };
 
-/// A static array of unvalidated orders args with up to [MAX_ORDERS] valid [OrderInfoArgs].
-///
-/// Each element is validated in the [Self::into_valid_order_infos_iter] iterator when passed to
-/// [price::to_order_info].
+/// A single unvalidated order in an [`UnvalidatedOrders`] list: the order's info args and the
+/// client order id to store on the posted order.
 #[repr(C)]
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct UnvalidatedOrders {
-    /// Instruction data that isn't read is free, so it's simpler to always use [MAX_ORDERS]
-    /// elements in the array and simply ignore elements at and after the first invalid element
-    /// than to use a slice with a dynamic length.
-    order_args: [OrderInfoArgs; MAX_ORDERS_USIZE],
+#[derive(Debug, Clone, Pack, Unpack, PartialEq, Eq)]
+pub struct UnvalidatedOrder {
+    /// The order info arguments, validated with [`price::to_order_info`] when the list is read.
+    pub order_info_args: OrderInfoArgs,
+    /// An opaque, client-provided id stored on the posted order.
+    pub client_order_id: u64,
 }
 
-impl UnvalidatedOrders {
+impl UnvalidatedOrder {
     #[inline(always)]
-    pub fn new<const N: usize>(orders: [OrderInfoArgs; N]) -> Self
-    where
-        [OrderInfoArgs; N]: UpToTen<N>,
-    {
-        let mut res: [MaybeUninit<OrderInfoArgs>; MAX_ORDERS_USIZE] =
-            [const { MaybeUninit::uninit() }; MAX_ORDERS_USIZE];
-
-        unsafe {
-            // Copy the orders passed in. This initializes `res[0..N]`.
-            //
-            // Safety:
-            // - `orders` is valid for `N` reads.
-            // - `res` is valid for `MAX_ORDERS` writes, and `MAX_ORDERS` >= `N`.
-            // - Both pointers are aligned and do not overlap.
-            core::ptr::copy_nonoverlapping(
-                orders.as_ptr(),
-                res.as_mut_ptr() as *mut OrderInfoArgs,
-                N,
-            );
-
-            // Write zeros to the remaining elements. This initializes `res[N..MAX_ORDERS]`.
-            //
-            // Safety:
-            // - `res.as_mut_ptr().add(N)` is valid for up to `MAX_ORDERS - N` writes and `N` is
-            //   guaranteed to be <= `MAX_ORDERS`.
-            // - Zero is a valid value for all field types.
-            core::ptr::write_bytes(
-                (res.as_mut_ptr() as *mut OrderInfoArgs).add(N),
-                0u8,
-                MAX_ORDERS_USIZE - N,
-            );
-        }
-
+    pub fn new(order_info_args: OrderInfoArgs, client_order_id: u64) -> Self {
         Self {
-            // Safety: The array has been fully initialized with `MAX_ORDERS` elements.
-            order_args: unsafe {
-                core::mem::transmute::<
-                    [MaybeUninit<OrderInfoArgs>; MAX_ORDERS_USIZE],
-                    [OrderInfoArgs; MAX_ORDERS_USIZE],
-                >(res)
-            },
+            order_info_args,
+            client_order_id,
         }
     }
+}
 
-    /// Converts and validates [Self::order_args] into an owned iterator of [OrderInfo].
-    /// Iteration stops at the first invalid element.
+impl From<OrderInfoArgs> for UnvalidatedOrder {
+    /// Creates an unvalidated order with a client order id of `0`.
     #[inline(always)]
-    pub fn into_valid_order_infos_iter(self) -> impl Iterator<Item = OrderInfo> {
-        self.order_args
-            .into_iter()
-            .map_while(|args| to_order_info(args).ok())
+    fn from(order_info_args: OrderInfoArgs) -> Self {
+        Self::new(order_info_args, 0)
     }
 }
 
-unsafe impl Pack for UnvalidatedOrders {
-    type Packed = [u8; OrderInfoArgs::LEN * MAX_ORDERS_USIZE];
+/// A length-prefixed list of [`UnvalidatedOrder`]s, read in place from instruction data.
+///
+/// The packed layout is a u32 LE order count followed by that many packed [`UnvalidatedOrder`]s,
+/// which is the same layout Borsh uses for a `Vec<UnvalidatedOrder>`.
+///
+/// Since the list has a dynamic length, it can't be an argument generated by the
+/// [`instruction_macros::ProgramInstruction`] macro. Instead, instructions that take order lists
+/// read them from the instruction data trailing the fixed size arguments with
+/// [`Self::unpack_length_prefixed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnvalidatedOrders<'a> {
+    /// The packed orders, excluding the length prefix. Always an exact multiple of
+    /// [`UnvalidatedOrder::LEN`] bytes.
+    packed_orders: &'a [u8],
+}
 
-    /// # Safety
+impl<'a> UnvalidatedOrders<'a> {
+    /// Reads a length-prefixed list of orders from the front of `data`.
     ///
-    /// Writes [OrderInfoArgs::LEN] bytes to `dst` [MAX_ORDERS] times, with each write
-    /// destination offset increased by the amount written each time.
+    /// Returns the list and the bytes remaining after it.
     #[inline(always)]
-    unsafe fn write_bytes(&self, dst: *mut u8) {
-        // This implementation was written according to a specific max number of orders. If that
-        // amount changes, this implementation needs to be updated to account for the new amount.
-        const_assert_eq!(MAX_ORDERS, 10);
+    pub fn unpack_length_prefixed(data: &'a [u8]) -> Result<(Self, &'a [u8]), ProgramError> {
+        let Some((num_orders, rest)) = data.split_first_chunk::<U32_SIZE>() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        let packed_len = (u32::from_le_bytes(*num_orders) as usize)
+            .checked_mul(UnvalidatedOrder::LEN)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        if rest.len() < packed_len {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (packed_orders, rest) = rest.split_at(packed_len);
 
-        self.order_args[0].write_bytes(dst);
-        self.order_args[1].write_bytes(dst.add(OrderInfoArgs::LEN));
-        self.order_args[2].write_bytes(dst.add(OrderInfoArgs::LEN * 2));
-        self.order_args[3].write_bytes(dst.add(OrderInfoArgs::LEN * 3));
-        self.order_args[4].write_bytes(dst.add(OrderInfoArgs::LEN * 4));
-        self.order_args[5].write_bytes(dst.add(OrderInfoArgs::LEN * 5));
-        self.order_args[6].write_bytes(dst.add(OrderInfoArgs::LEN * 6));
-        self.order_args[7].write_bytes(dst.add(OrderInfoArgs::LEN * 7));
-        self.order_args[8].write_bytes(dst.add(OrderInfoArgs::LEN * 8));
-        self.order_args[9].write_bytes(dst.add(OrderInfoArgs::LEN * 9));
+        Ok((Self { packed_orders }, rest))
     }
 
+    /// The number of orders in the list.
     #[inline(always)]
-    fn pack(&self) -> Self::Packed {
-        let mut data: [MaybeUninit<u8>; Self::LEN] = [MaybeUninit::uninit(); Self::LEN];
-        let dst = data.as_mut_ptr() as *mut u8;
-        // Safety: `dst` points to `Self::LEN` contiguous, writable bytes.
-        unsafe { self.write_bytes(dst) };
-
-        // Safety: All bytes are initialized during the construction above.
-        unsafe { *(data.as_ptr() as *const [u8; Self::LEN]) }
+    pub fn len(&self) -> usize {
+        self.packed_orders.len() / UnvalidatedOrder::LEN
     }
-}
 
-unsafe impl Unpack for UnvalidatedOrders {
-    /// # Safety (implementor)
-    ///
-    /// - Reads [OrderInfoArgs::LEN] bytes from `src` [MAX_ORDERS] times.
-    /// - `src` is read from as an unaligned pointer.
-    ///
-    /// # Safety (caller)
-    ///
-    /// Caller must guarantee `src` points to at least [Self::LEN] bytes of readable memory.
     #[inline(always)]
-    unsafe fn read_bytes(src: *const u8) -> Result<Self, solana_program_error::ProgramError> {
-        // This implementation was written according to a specific max number of orders. If that
-        // amount changes, this implementation needs to be updated to account for the new amount.
-        const_assert_eq!(MAX_ORDERS, 10);
-
-        Ok(Self {
-            order_args: [
-                OrderInfoArgs::read_bytes(src)?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN))?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN * 2))?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN * 3))?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN * 4))?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN * 5))?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN * 6))?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN * 7))?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN * 8))?,
-                OrderInfoArgs::read_bytes(src.add(OrderInfoArgs::LEN * 9))?,
-            ],
-        })
+    pub fn is_empty(&self) -> bool {
+        self.packed_orders.is_empty()
     }
 
+    /// Returns an iterator over the unvalidated orders in the list.
     #[inline(always)]
-    fn unpack(data: &[u8]) -> Result<Self, solana_program_error::ProgramError> {
-        if data.len() < Self::LEN {
-            return Err(ProgramError::InvalidInstructionData);
-        }
-
-        // Safety: `data` has at least `Self::LEN` bytes.
-        unsafe { Self::read_bytes(data.as_ptr()) }
+    pub fn iter(&self) -> impl Iterator<Item = UnvalidatedOrder> + 'a {
+        self.packed_orders
+            .chunks_exact(UnvalidatedOrder::LEN)
+            .map(|packed| {
+                // Safety: Each chunk is exactly `UnvalidatedOrder::LEN` bytes, and every field in
+                // an `UnvalidatedOrder` is valid for any bit pattern, so reading it
+                // can't fail.
+                unsafe { UnvalidatedOrder::read_bytes(packed.as_ptr()).unwrap_unchecked() }
+            })
     }
-}
 
-mod private {
-    use super::*;
-
-    // The trait implementations below were written according to a specific max number of orders.
-    // If this number changes, the trait needs to change to account for the different size.
-    const_assert_eq!(MAX_ORDERS, 10);
-
-    /// Marker trait: implemented only for arrays of length 0..=[MAX_ORDERS].
-    pub trait UpToTen<const N: usize> {}
+    /// Converts and validates each order into its [`OrderInfo`] paired with the order's client
+    /// order id.
+    ///
+    /// Yields an error for each order with invalid order info args.
+    #[inline(always)]
+    pub fn into_valid_orders_iter(
+        self,
+    ) -> impl Iterator<Item = Result<(OrderInfo, u64), DropsetError>> + 'a {
+        self.iter().map(
+            |UnvalidatedOrder {
+                 order_info_args,
+                 client_order_id,
+             }| {
+                let order_info = to_order_info(order_info_args)?;
+                Ok((order_info, client_order_id))
+            },
+        )
+    }
 
-    impl UpToTen<0> for [OrderInfoArgs; 0] {}
-    impl UpToTen<1> for [OrderInfoArgs; 1] {}
-    impl UpToTen<2> for [OrderInfoArgs; 2] {}
-    impl UpToTen<3> for [OrderInfoArgs; 3] {}
-    impl UpToTen<4> for [OrderInfoArgs; 4] {}
-    impl UpToTen<5> for [OrderInfoArgs; 5] {}
-    impl UpToTen<6> for [OrderInfoArgs; 6] {}
-    impl UpToTen<7> for [OrderInfoArgs; 7] {}
-    impl UpToTen<8> for [OrderInfoArgs; 8] {}
-    impl UpToTen<9> for [OrderInfoArgs; 9] {}
-    impl UpToTen<10> for [OrderInfoArgs; 10] {}
+    /// Packs `orders` as a length-prefixed list and appends it to `dst`.
+    #[cfg(feature = "std")]
+    pub fn pack_length_prefixed(orders: &[UnvalidatedOrder], dst: &mut std::vec::Vec<u8>) {
+        let num_orders = u32::try_from(orders.len()).expect("Order count should fit in a u32");
+        dst.extend_from_slice(&num_orders.to_le_bytes());
+        for order in orders {
+            dst.extend_from_slice(&order.pack());
+        }
+    }
 }
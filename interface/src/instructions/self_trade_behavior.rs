@@ -0,0 +1,53 @@
+use crate::error::DropsetError;
+
+/// What to do when a taker order would match against one of the taker's own resting orders, passed
+/// as a raw `u8` in instruction data.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(strum_macros::FromRepr, strum_macros::EnumIter))]
+pub enum SelfTradeBehavior {
+    /// Fail the instruction.
+    Abort,
+    /// Cancel the taker's resting order, return its collateral to their seat and keep matching.
+    CancelResting,
+    /// Decrement both the resting order and the taker order by the overlapping amount without
+    /// transferring anything, returning the resting order's decremented collateral to the seat.
+    DecrementBoth,
+}
+
+impl TryFrom<u8> for SelfTradeBehavior {
+    type Error = DropsetError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Abort),
+            1 => Ok(Self::CancelResting),
+            2 => Ok(Self::DecrementBoth),
+            _ => Err(DropsetError::InvalidSelfTradeBehavior),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn self_trade_behavior_try_from_u8_exhaustive() {
+        for v in 0..=u8::MAX {
+            match SelfTradeBehavior::from_repr(v) {
+                Some(variant) => assert_eq!(SelfTradeBehavior::try_from(v), Ok(variant)),
+                None => assert_eq!(
+                    SelfTradeBehavior::try_from(v),
+                    Err(DropsetError::InvalidSelfTradeBehavior)
+                ),
+            }
+        }
+        for variant in SelfTradeBehavior::iter() {
+            assert_eq!(SelfTradeBehavior::try_from(variant as u8), Ok(variant));
+        }
+    }
+}
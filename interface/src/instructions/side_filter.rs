@@ -0,0 +1,63 @@
+use crate::error::DropsetError;
+
+/// Which side(s) of the book an instruction applies to, passed as a raw `u8` in instruction data.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(strum_macros::FromRepr, strum_macros::EnumIter))]
+pub enum SideFilter {
+    /// Both bids and asks.
+    Both,
+    /// Only bids.
+    Bids,
+    /// Only asks.
+    Asks,
+}
+
+impl SideFilter {
+    #[inline(always)]
+    pub fn includes_bids(self) -> bool {
+        !matches!(self, Self::Asks)
+    }
+
+    #[inline(always)]
+    pub fn includes_asks(self) -> bool {
+        !matches!(self, Self::Bids)
+    }
+}
+
+impl TryFrom<u8> for SideFilter {
+    type Error = DropsetError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Both),
+            1 => Ok(Self::Bids),
+            2 => Ok(Self::Asks),
+            _ => Err(DropsetError::InvalidSideFilter),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn side_filter_try_from_u8_exhaustive() {
+        for v in 0..=u8::MAX {
+            match SideFilter::from_repr(v) {
+                Some(variant) => assert_eq!(SideFilter::try_from(v), Ok(variant)),
+                None => assert_eq!(
+                    SideFilter::try_from(v),
+                    Err(DropsetError::InvalidSideFilter)
+                ),
+            }
+        }
+        for variant in SideFilter::iter() {
+            assert_eq!(SideFilter::try_from(variant as u8), Ok(variant));
+        }
+    }
+}
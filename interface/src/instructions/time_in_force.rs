@@ -0,0 +1,51 @@
+use crate::error::DropsetError;
+
+/// The time-in-force policy for a taker order, passed as a raw `u8` in instruction data.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(strum_macros::FromRepr, strum_macros::EnumIter))]
+pub enum TimeInForce {
+    /// Fill as much of the order as possible immediately and cancel the unfilled remainder.
+    ImmediateOrCancel,
+    /// Fill the entire order size immediately or fail the instruction. Size decremented by a
+    /// [`SelfTradeBehavior::DecrementBoth`] self trade counts toward the order size.
+    ///
+    /// [`SelfTradeBehavior::DecrementBoth`]: crate::instructions::SelfTradeBehavior::DecrementBoth
+    FillOrKill,
+}
+
+impl TryFrom<u8> for TimeInForce {
+    type Error = DropsetError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::ImmediateOrCancel),
+            1 => Ok(Self::FillOrKill),
+            _ => Err(DropsetError::InvalidTimeInForce),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn time_in_force_try_from_u8_exhaustive() {
+        for v in 0..=u8::MAX {
+            match TimeInForce::from_repr(v) {
+                Some(variant) => assert_eq!(TimeInForce::try_from(v), Ok(variant)),
+                None => assert_eq!(
+                    TimeInForce::try_from(v),
+                    Err(DropsetError::InvalidTimeInForce)
+                ),
+            }
+        }
+        for variant in TimeInForce::iter() {
+            assert_eq!(TimeInForce::try_from(variant as u8), Ok(variant));
+        }
+    }
+}
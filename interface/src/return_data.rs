@@ -0,0 +1,178 @@
+//! Return data set by the `dropset` taker instructions so that programs invoking them through a
+//! CPI can read the amounts filled without re-reading token or seat balances.
+
+use crate::error::DropsetError;
+
+/// The return data set by the `MarketOrder` and `SeatMarketOrder` instructions, and by the
+/// `PostOrder` instruction for orders that aren't post-only.
+///
+/// All amounts are in atoms. The taker fee is denominated in the asset the taker receives; i.e.,
+/// base for a market buy or bid and quote for a market sell or ask. For a `PostOrder`, the amounts
+/// only cover the crossing portion of the order, not any remainder posted to the book.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TakerReturnData {
+    pub base_filled: u64,
+    pub quote_filled: u64,
+    pub taker_fee: u64,
+}
+
+impl TakerReturnData {
+    pub const LEN: usize = 24;
+
+    #[inline(always)]
+    pub fn pack(&self) -> [u8; Self::LEN] {
+        let mut data = [0; Self::LEN];
+        data[0..8].copy_from_slice(&self.base_filled.to_le_bytes());
+        data[8..16].copy_from_slice(&self.quote_filled.to_le_bytes());
+        data[16..24].copy_from_slice(&self.taker_fee.to_le_bytes());
+        data
+    }
+
+    /// Decodes the taker return data. The data must be exactly [`Self::LEN`] bytes.
+    #[inline(always)]
+    pub fn unpack(data: &[u8]) -> Result<Self, DropsetError> {
+        let data: &[u8; Self::LEN] = data
+            .try_into()
+            .map_err(|_| DropsetError::InvalidReturnData)?;
+        let read_u64 = |offset: usize| {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(&data[offset..offset + 8]);
+            u64::from_le_bytes(bytes)
+        };
+
+        Ok(Self {
+            base_filled: read_u64(0),
+            quote_filled: read_u64(8),
+            taker_fee: read_u64(16),
+        })
+    }
+
+    /// Decodes the taker return data set by the most recently invoked program, checking that it was
+    /// set by the `dropset` program.
+    #[cfg(feature = "program")]
+    #[inline(always)]
+    pub fn from_cpi_return_data() -> Result<Self, DropsetError> {
+        let return_data = solana_instruction_view::cpi::get_return_data()
+            .ok_or(DropsetError::InvalidReturnData)?;
+        if !solana_address::address_eq(return_data.program_id(), &crate::program::ID) {
+            return Err(DropsetError::InvalidReturnData);
+        }
+        Self::unpack(return_data.as_slice())
+    }
+}
+
+/// CPI helpers for programs that route taker orders through `dropset`, extending the generated
+/// [`crate::instructions::generated_program`] instruction account structs.
+#[cfg(feature = "program")]
+mod cpi {
+    use pinocchio::error::ProgramError;
+    use solana_instruction_view::cpi::Signer;
+
+    use super::TakerReturnData;
+    use crate::instructions::{
+        generated_program::{
+            MarketOrder,
+            PostOrder,
+            SeatMarketOrder,
+        },
+        MarketOrderInstructionData,
+        PostOrderInstructionData,
+        SeatMarketOrderInstructionData,
+    };
+
+    impl MarketOrder<'_> {
+        /// Invokes the market order and decodes the amounts filled from its return data.
+        #[inline(always)]
+        pub fn invoke_with_return_data(
+            self,
+            data: MarketOrderInstructionData,
+        ) -> Result<TakerReturnData, ProgramError> {
+            self.invoke_signed_with_return_data(&[], data)
+        }
+
+        /// Invokes the market order with `signers_seeds` and decodes the amounts filled from its
+        /// return data.
+        #[inline(always)]
+        pub fn invoke_signed_with_return_data(
+            self,
+            signers_seeds: &[Signer],
+            data: MarketOrderInstructionData,
+        ) -> Result<TakerReturnData, ProgramError> {
+            self.invoke_signed(signers_seeds, data)?;
+            Ok(TakerReturnData::from_cpi_return_data()?)
+        }
+    }
+
+    impl SeatMarketOrder<'_> {
+        /// Invokes the seat market order and decodes the amounts filled from its return data.
+        #[inline(always)]
+        pub fn invoke_with_return_data(
+            self,
+            data: SeatMarketOrderInstructionData,
+        ) -> Result<TakerReturnData, ProgramError> {
+            self.invoke_signed_with_return_data(&[], data)
+        }
+
+        /// Invokes the seat market order with `signers_seeds` and decodes the amounts filled from
+        /// its return data.
+        #[inline(always)]
+        pub fn invoke_signed_with_return_data(
+            self,
+            signers_seeds: &[Signer],
+            data: SeatMarketOrderInstructionData,
+        ) -> Result<TakerReturnData, ProgramError> {
+            self.invoke_signed(signers_seeds, data)?;
+            Ok(TakerReturnData::from_cpi_return_data()?)
+        }
+    }
+
+    impl PostOrder<'_> {
+        /// Invokes the post order and decodes the amounts filled by its crossing portion from its
+        /// return data. Post-only orders don't set return data, so this fails for them.
+        #[inline(always)]
+        pub fn invoke_with_return_data(
+            self,
+            data: PostOrderInstructionData,
+        ) -> Result<TakerReturnData, ProgramError> {
+            self.invoke_signed_with_return_data(&[], data)
+        }
+
+        /// Invokes the post order with `signers_seeds` and decodes the amounts filled by its
+        /// crossing portion from its return data.
+        #[inline(always)]
+        pub fn invoke_signed_with_return_data(
+            self,
+            signers_seeds: &[Signer],
+            data: PostOrderInstructionData,
+        ) -> Result<TakerReturnData, ProgramError> {
+            self.invoke_signed(signers_seeds, data)?;
+            Ok(TakerReturnData::from_cpi_return_data()?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn taker_return_data_round_trip() {
+        let return_data = TakerReturnData {
+            base_filled: 1,
+            quote_filled: u64::MAX,
+            taker_fee: 0x0102_0304_0506_0708,
+        };
+        let packed = return_data.pack();
+        assert_eq!(TakerReturnData::unpack(&packed), Ok(return_data));
+        assert_eq!(
+            TakerReturnData::unpack(&packed[..TakerReturnData::LEN - 1]),
+            Err(DropsetError::InvalidReturnData)
+        );
+        let mut too_long = [0; TakerReturnData::LEN + 1];
+        too_long[..TakerReturnData::LEN].copy_from_slice(&packed);
+        assert_eq!(
+            TakerReturnData::unpack(&too_long),
+            Err(DropsetError::InvalidReturnData)
+        );
+    }
+}
@@ -63,33 +63,37 @@ impl OrdersCollection for AskOrders {
         NextSectorIndex(NIL)
     }
 
-    /// A post-only ask order can only be posted if the input price > the highest bid, because it
-    /// would immediately take otherwise.
+    /// A post-only ask order can only be posted if the input price > the highest unexpired bid,
+    /// because it would immediately take otherwise.
     ///
-    /// If this condition is satisfied or if the bid side is empty, the order cannot cross and may
-    /// be posted.
+    /// If this condition is satisfied or if the bid side has no unexpired crossing orders, the
+    /// order cannot cross and may be posted.
     #[inline(always)]
-    fn post_only_crossing_check<H, S>(order: &Order, market: &Market<H, S>) -> DropsetResult
+    fn post_only_crossing_check<H, S>(
+        order: &Order,
+        market: &Market<H, S>,
+        slot: u64,
+        unix_timestamp: i64,
+    ) -> DropsetResult
     where
         H: AsRef<MarketHeader>,
         S: AsRef<[u8]>,
     {
         let ask_price = order.encoded_price();
-        let first_bid_sector = market.iter_bids().next();
-        match first_bid_sector {
-            // Check that the ask wouldn't immediately take (and is thus post only) by ensuring its
-            // price is greater than the first/highest bid.
-            Some((_idx, bid_sector)) => {
-                let highest_bid = bid_sector.load_payload::<Order>();
-                if ask_price > highest_bid.encoded_price() {
-                    Ok(())
-                } else {
-                    Err(DropsetError::PostOnlyWouldImmediatelyFill)
-                }
+        for (_idx, bid_sector) in market.iter_bids() {
+            let bid = bid_sector.load_payload::<Order>();
+            // The bids are sorted by price priority, so once the ask no longer crosses one, it
+            // can't cross any that follow.
+            if ask_price > bid.encoded_price() {
+                return Ok(());
+            }
+            // An expired bid can't be filled, so only an unexpired one makes the ask take.
+            if !bid.is_expired(slot, unix_timestamp) {
+                return Err(DropsetError::PostOnlyWouldImmediatelyFill);
             }
-            // There are no bid orders, so the ask cannot cross and may be posted.
-            None => Ok(()),
         }
+        // There are no unexpired crossing bid orders, so the ask cannot cross and may be posted.
+        Ok(())
     }
 
     /// Users put up base as collateral when posting asks. Returns the base remaining in an ask.
@@ -63,33 +63,37 @@ impl OrdersCollection for BidOrders {
         NextSectorIndex(NIL)
     }
 
-    /// A post-only bid order can only be posted if the input price < the lowest ask, because it
-    /// would immediately take otherwise.
+    /// A post-only bid order can only be posted if the input price < the lowest unexpired ask,
+    /// because it would immediately take otherwise.
     ///
-    /// If this condition is satisfied or if the ask side is empty, the order cannot cross and may
-    /// be posted.
+    /// If this condition is satisfied or if the ask side has no unexpired crossing orders, the
+    /// order cannot cross and may be posted.
     #[inline(always)]
-    fn post_only_crossing_check<H, S>(order: &Order, market: &Market<H, S>) -> DropsetResult
+    fn post_only_crossing_check<H, S>(
+        order: &Order,
+        market: &Market<H, S>,
+        slot: u64,
+        unix_timestamp: i64,
+    ) -> DropsetResult
     where
         H: AsRef<MarketHeader>,
         S: AsRef<[u8]>,
     {
         let bid_price = order.encoded_price();
-        let first_ask_sector = market.iter_asks().next();
-        match first_ask_sector {
-            // Check that the bid wouldn't immediately take (and is thus post only) by ensuring its
-            // price is less than the first/lowest ask.
-            Some((_idx, ask_sector)) => {
-                let lowest_ask = ask_sector.load_payload::<Order>();
-                if bid_price < lowest_ask.encoded_price() {
-                    Ok(())
-                } else {
-                    Err(DropsetError::PostOnlyWouldImmediatelyFill)
-                }
+        for (_idx, ask_sector) in market.iter_asks() {
+            let ask = ask_sector.load_payload::<Order>();
+            // The asks are sorted by price priority, so once the bid no longer crosses one, it
+            // can't cross any that follow.
+            if bid_price < ask.encoded_price() {
+                return Ok(());
+            }
+            // An expired ask can't be filled, so only an unexpired one makes the bid take.
+            if !ask.is_expired(slot, unix_timestamp) {
+                return Err(DropsetError::PostOnlyWouldImmediatelyFill);
             }
-            // There are no ask orders, so the bid cannot cross and may be posted.
-            None => Ok(()),
         }
+        // There are no unexpired crossing ask orders, so the bid cannot cross and may be posted.
+        Ok(())
     }
 
     /// Users put up quote as collateral when posting bids.
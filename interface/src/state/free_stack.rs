@@ -159,6 +159,48 @@ impl<'a> Stack<'a> {
         Ok(free_index)
     }
 
+    /// Removes every free sector with a sector index greater than or equal to `end` from the stack,
+    /// keeping the remaining free sectors in the same order.
+    ///
+    /// This is used to truncate the sectors region, since the removed sectors become unreachable.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees every sector in the free stack is in-bounds of the sector bytes.
+    pub unsafe fn retain_below(&mut self, end: SectorIndex) {
+        let mut last_kept = NIL;
+        let mut index = self.top();
+
+        while index != NIL {
+            // Safety: The caller guarantees every free sector is in-bounds.
+            let sector = unsafe { Sector::from_sector_index_mut(self.sectors, index) };
+            let next_index = sector.next();
+
+            if index < end {
+                match last_kept {
+                    NIL => self.set_top(index),
+                    // Safety: `last_kept` is a non-NIL sector index from the free stack.
+                    last_kept => unsafe {
+                        Sector::from_sector_index_mut(self.sectors, last_kept).set_next(index);
+                    },
+                }
+                last_kept = index;
+            } else {
+                self.header.decrement_num_free_sectors();
+            }
+
+            index = next_index;
+        }
+
+        match last_kept {
+            NIL => self.set_top(NIL),
+            // Safety: `last_kept` is a non-NIL sector index from the free stack.
+            last_kept => unsafe {
+                Sector::from_sector_index_mut(self.sectors, last_kept).set_next(NIL);
+            },
+        }
+    }
+
     #[inline(always)]
     pub fn top(&self) -> SectorIndex {
         self.header.free_stack_top()
@@ -11,6 +11,7 @@ use crate::{
             NIL,
             PAYLOAD_SIZE,
         },
+        transmutable::Transmutable,
     },
 };
 
@@ -194,6 +195,50 @@ impl<'a, T: LinkedListHeaderOperations> LinkedList<'a, T> {
         free_stack.push_free_sector(index);
     }
 
+    /// Moves the sector at index `from` to the free sector at index `to`, updating its neighbors'
+    /// links and the list's head and tail where necessary. The sector at `from` is left as is and
+    /// should be considered garbage data.
+    ///
+    /// This only moves the sector within the list; any other references to the sector's index must
+    /// be updated by the caller.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees:
+    /// - `from` is a sector in this list and `to` is a free sector already removed from the free
+    ///   stack.
+    /// - `from` and `to` are distinct, in-bounds sector indices.
+    pub unsafe fn relocate(&mut self, from: SectorIndex, to: SectorIndex) {
+        // Safety: Caller guarantees both indices are distinct and in-bounds, so the source and
+        // destination sectors don't overlap.
+        let (prev_index, next_index) = unsafe {
+            let sectors = self.sectors.as_mut_ptr();
+            core::ptr::copy_nonoverlapping(
+                sectors.add(from as usize * Sector::LEN),
+                sectors.add(to as usize * Sector::LEN),
+                Sector::LEN,
+            );
+            let sector = Sector::from_sector_index(self.sectors, to);
+            (sector.prev(), sector.next())
+        };
+
+        match prev_index {
+            NIL => T::set_head(self.header, to),
+            // Safety: `prev_index` matched against non-NIL and came from a sector directly.
+            prev_index => unsafe {
+                Sector::from_sector_index_mut(self.sectors, prev_index).set_next(to);
+            },
+        }
+
+        match next_index {
+            NIL => T::set_tail(self.header, to),
+            // Safety: `next_index` matched against non-NIL and came from a sector directly.
+            next_index => unsafe {
+                Sector::from_sector_index_mut(self.sectors, next_index).set_prev(to);
+            },
+        }
+    }
+
     pub fn iter(&self) -> LinkedListIter<'_> {
         LinkedListIter {
             curr: T::head(self.header),
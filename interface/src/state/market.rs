@@ -15,8 +15,12 @@ use crate::state::{
     },
     order::OrdersCollection,
     seats_dll::SeatsLinkedList,
-    sector::SECTOR_SIZE,
+    sector::{
+        SectorIndex,
+        SECTOR_SIZE,
+    },
     transmutable::Transmutable,
+    user_order_sectors::SeatOrderSectors,
 };
 
 pub struct Market<Header, SectorBytes> {
@@ -107,6 +111,19 @@ impl<'a> MarketRefMut<'a> {
     pub fn orders<T: OrdersCollection>(&mut self) -> LinkedList<'_, T> {
         LinkedList::new_from_parts(self.header, self.sectors)
     }
+
+    /// Returns the `Side` of the seat's price -> order sectors mapping.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees `seat_index` is the in-bounds sector index of a seat in the market.
+    #[inline(always)]
+    pub unsafe fn seat_order_sectors<Side: OrdersCollection>(
+        &mut self,
+        seat_index: SectorIndex,
+    ) -> SeatOrderSectors<'_, Side> {
+        unsafe { SeatOrderSectors::new_from_parts(self.header, self.sectors, seat_index) }
+    }
 }
 
 impl<H: AsRef<MarketHeader>, S: AsRef<[u8]>> Market<H, S> {
@@ -1,5 +1,10 @@
 //! See [`MarketHeader`].
 
+use price::{
+    EncodedPrice,
+    MANTISSA_DIGITS_UPPER_BOUND,
+    PRICE_MANTISSA_MASK,
+};
 use solana_address::Address;
 use static_assertions::const_assert_eq;
 
@@ -9,14 +14,18 @@ use crate::{
         DropsetResult,
     },
     state::{
+        market_status::MarketStatus,
         sector::{
             LeSectorIndex,
             SectorIndex,
             LE_NIL,
         },
         transmutable::Transmutable,
+        user_order_sectors::MAX_ORDERS,
+        LeU16,
         LeU32,
         LeU64,
+        U16_SIZE,
         U32_SIZE,
         U64_SIZE,
     },
@@ -24,6 +33,12 @@ use crate::{
 
 pub const MARKET_ACCOUNT_DISCRIMINANT: u64 = 0xd00d00b00b00f00du64;
 
+/// The denominator for fees expressed in basis points, i.e., 10,000 bps == 100%.
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+/// The maximum taker fee a market can charge in basis points.
+pub const MAX_TAKER_FEE_BPS: u16 = 1_000;
+
 /// The lightweight header for each market account. This header contains metadata used to interpret
 /// a market's account data properly.
 ///
@@ -70,10 +85,34 @@ pub struct MarketHeader {
     pub quote_mint: Address,
     /// The bump for the market PDA.
     pub market_bump: u8,
+    /// The u16 market ID used to derive the market PDA as LE bytes. This distinguishes multiple
+    /// markets for the same base/quote pair.
+    market_id: LeU16,
     /// The u64 number of events as LE bytes.
     num_events: LeU64,
+    /// The address allowed to collect the market's accrued fees.
+    pub fee_recipient: Address,
+    /// The u16 taker fee in basis points as LE bytes.
+    taker_fee_bps: LeU16,
+    /// The u16 maker rebate in basis points as LE bytes. Always <= the taker fee.
+    maker_rebate_bps: LeU16,
+    /// The u64 base atoms of accrued, uncollected fees as LE bytes.
+    base_fees_accrued: LeU64,
+    /// The u64 quote atoms of accrued, uncollected fees as LE bytes.
+    quote_fees_accrued: LeU64,
+    /// The address allowed to update the market's config.
+    pub authority: Address,
+    /// The u64 minimum number of base atoms in a posted order as LE bytes.
+    min_order_base_atoms: LeU64,
+    /// The u32 tick that every posted order's price mantissa must be a multiple of as LE bytes.
+    price_mantissa_tick: LeU32,
+    /// The max number of orders a user can have on each side of the book. Always <=
+    /// [`MAX_ORDERS`].
+    max_orders_per_user: u8,
+    /// The market's [`MarketStatus`] as a raw `u8`.
+    status: u8,
     // Although not necessary, add extra padding to make this alignment 8.
-    _padding: [u8; 3],
+    _padding: [u8; 7],
 }
 
 // Safety:
@@ -84,26 +123,38 @@ pub struct MarketHeader {
 unsafe impl Transmutable for MarketHeader {
     #[allow(clippy::identity_op)]
     const LEN: usize = 0
-    /* discriminant */     + size_of::<LeU64>()
-    /* num_seats */        + size_of::<LeU32>()
-    /* num_bids */         + size_of::<LeU32>()
-    /* num_asks */         + size_of::<LeU32>()
-    /* num_free_sectors */ + size_of::<LeU32>()
-    /* free_stack_top */   + size_of::<LeSectorIndex>()
-    /* seats_dll_head */   + size_of::<LeSectorIndex>()
-    /* seats_dll_tail */   + size_of::<LeSectorIndex>()
-    /* bids_dll_head */    + size_of::<LeSectorIndex>()
-    /* bids_dll_tail */    + size_of::<LeSectorIndex>()
-    /* asks_dll_head */    + size_of::<LeSectorIndex>()
-    /* asks_dll_tail */    + size_of::<LeSectorIndex>()
-    /* base_mint */        + size_of::<Address>()
-    /* quote_mint */       + size_of::<Address>()
-    /* market_bump */      + size_of::<u8>()
-    /* num_events */       + size_of::<LeU64>()
-    /* _padding */         + size_of::<[u8; 3]>();
+    /* discriminant */         + size_of::<LeU64>()
+    /* num_seats */            + size_of::<LeU32>()
+    /* num_bids */             + size_of::<LeU32>()
+    /* num_asks */             + size_of::<LeU32>()
+    /* num_free_sectors */     + size_of::<LeU32>()
+    /* free_stack_top */       + size_of::<LeSectorIndex>()
+    /* seats_dll_head */       + size_of::<LeSectorIndex>()
+    /* seats_dll_tail */       + size_of::<LeSectorIndex>()
+    /* bids_dll_head */        + size_of::<LeSectorIndex>()
+    /* bids_dll_tail */        + size_of::<LeSectorIndex>()
+    /* asks_dll_head */        + size_of::<LeSectorIndex>()
+    /* asks_dll_tail */        + size_of::<LeSectorIndex>()
+    /* base_mint */            + size_of::<Address>()
+    /* quote_mint */           + size_of::<Address>()
+    /* market_bump */          + size_of::<u8>()
+    /* market_id */            + size_of::<LeU16>()
+    /* num_events */           + size_of::<LeU64>()
+    /* fee_recipient */        + size_of::<Address>()
+    /* taker_fee_bps */        + size_of::<LeU16>()
+    /* maker_rebate_bps */     + size_of::<LeU16>()
+    /* base_fees_accrued */    + size_of::<LeU64>()
+    /* quote_fees_accrued */   + size_of::<LeU64>()
+    /* authority */            + size_of::<Address>()
+    /* min_order_base_atoms */ + size_of::<LeU64>()
+    /* price_mantissa_tick */  + size_of::<LeU32>()
+    /* max_orders_per_user */  + size_of::<u8>()
+    /* status */               + size_of::<u8>()
+    /* _padding */             + size_of::<[u8; 7]>();
 
     fn validate_bit_patterns(_bytes: &[u8]) -> DropsetResult {
-        // All bit patterns are valid: no enums, bools, or other types with invalid states.
+        // All bit patterns are valid: the status is stored as a raw `u8` and is only converted to a
+        // `MarketStatus` when read.
         Ok(())
     }
 }
@@ -207,6 +258,7 @@ impl MarketHeader {
     pub unsafe fn init(
         header_dst_ptr: *mut MarketHeader,
         market_bump: u8,
+        market_id: u16,
         base_mint: &Address,
         quote_mint: &Address,
     ) {
@@ -226,8 +278,19 @@ impl MarketHeader {
             base_mint: *base_mint,
             quote_mint: *quote_mint,
             market_bump,
+            market_id: market_id.to_le_bytes(),
             num_events: [0; U64_SIZE],
-            _padding: [0; 3],
+            fee_recipient: Address::default(),
+            taker_fee_bps: [0; U16_SIZE],
+            maker_rebate_bps: [0; U16_SIZE],
+            base_fees_accrued: [0; U64_SIZE],
+            quote_fees_accrued: [0; U64_SIZE],
+            authority: Address::default(),
+            min_order_base_atoms: [0; U64_SIZE],
+            price_mantissa_tick: [0; U32_SIZE],
+            max_orders_per_user: 0,
+            status: MarketStatus::Active as u8,
+            _padding: [0; 7],
         };
         core::ptr::write(header_dst_ptr, header);
     }
@@ -245,6 +308,11 @@ impl MarketHeader {
         u64::from_le_bytes(self.discriminant)
     }
 
+    #[inline(always)]
+    pub fn market_id(&self) -> u16 {
+        u16::from_le_bytes(self.market_id)
+    }
+
     #[inline(always)]
     pub fn num_events(&self) -> u64 {
         u64::from_le_bytes(self.num_events)
@@ -254,4 +322,169 @@ impl MarketHeader {
     pub fn increment_num_events_by(&mut self, amount: u64) {
         self.num_events = (self.num_events().saturating_add(amount)).to_le_bytes();
     }
+
+    #[inline(always)]
+    pub fn taker_fee_bps(&self) -> u16 {
+        u16::from_le_bytes(self.taker_fee_bps)
+    }
+
+    #[inline(always)]
+    pub fn maker_rebate_bps(&self) -> u16 {
+        u16::from_le_bytes(self.maker_rebate_bps)
+    }
+
+    /// Sets the market's fee recipient and fee schedule.
+    ///
+    /// Fails if the taker fee exceeds [`MAX_TAKER_FEE_BPS`] or if the maker rebate exceeds the
+    /// taker fee, since rebates are paid out of the taker fees charged on each fill.
+    #[inline(always)]
+    pub fn set_fee_schedule(
+        &mut self,
+        fee_recipient: &Address,
+        taker_fee_bps: u16,
+        maker_rebate_bps: u16,
+    ) -> DropsetResult {
+        if taker_fee_bps > MAX_TAKER_FEE_BPS || maker_rebate_bps > taker_fee_bps {
+            return Err(DropsetError::InvalidFeeSchedule);
+        }
+        self.fee_recipient = *fee_recipient;
+        self.taker_fee_bps = taker_fee_bps.to_le_bytes();
+        self.maker_rebate_bps = maker_rebate_bps.to_le_bytes();
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn base_fees_accrued(&self) -> u64 {
+        u64::from_le_bytes(self.base_fees_accrued)
+    }
+
+    #[inline(always)]
+    pub fn quote_fees_accrued(&self) -> u64 {
+        u64::from_le_bytes(self.quote_fees_accrued)
+    }
+
+    #[inline(always)]
+    pub fn try_accrue_base_fees(&mut self, amount: u64) -> DropsetResult {
+        let new_amount = self
+            .base_fees_accrued()
+            .checked_add(amount)
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+        self.base_fees_accrued = new_amount.to_le_bytes();
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn try_accrue_quote_fees(&mut self, amount: u64) -> DropsetResult {
+        let new_amount = self
+            .quote_fees_accrued()
+            .checked_add(amount)
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+        self.quote_fees_accrued = new_amount.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// Resets the accrued fees to zero and returns the base and quote amounts that were accrued.
+    #[inline(always)]
+    pub fn take_fees_accrued(&mut self) -> (u64, u64) {
+        let fees = (self.base_fees_accrued(), self.quote_fees_accrued());
+        self.base_fees_accrued = [0; U64_SIZE];
+        self.quote_fees_accrued = [0; U64_SIZE];
+        fees
+    }
+
+    #[inline(always)]
+    pub fn min_order_base_atoms(&self) -> u64 {
+        u64::from_le_bytes(self.min_order_base_atoms)
+    }
+
+    #[inline(always)]
+    pub fn price_mantissa_tick(&self) -> u32 {
+        u32::from_le_bytes(self.price_mantissa_tick)
+    }
+
+    #[inline(always)]
+    pub fn max_orders_per_user(&self) -> u8 {
+        self.max_orders_per_user
+    }
+
+    /// Sets the market's tunable order parameters.
+    ///
+    /// Fails if the price mantissa tick is zero or larger than any valid price mantissa, or if the
+    /// max orders per user is zero or exceeds [`MAX_ORDERS`].
+    #[inline(always)]
+    pub fn set_market_config(
+        &mut self,
+        min_order_base_atoms: u64,
+        price_mantissa_tick: u32,
+        max_orders_per_user: u8,
+    ) -> DropsetResult {
+        if price_mantissa_tick == 0
+            || price_mantissa_tick > MANTISSA_DIGITS_UPPER_BOUND
+            || max_orders_per_user == 0
+            || max_orders_per_user > MAX_ORDERS
+        {
+            return Err(DropsetError::InvalidMarketConfig);
+        }
+        self.min_order_base_atoms = min_order_base_atoms.to_le_bytes();
+        self.price_mantissa_tick = price_mantissa_tick.to_le_bytes();
+        self.max_orders_per_user = max_orders_per_user;
+
+        Ok(())
+    }
+
+    /// Checks that an order to be posted meets the market's minimum order size and that its price
+    /// mantissa lands on the market's price mantissa tick.
+    #[inline(always)]
+    pub fn check_order_against_config(
+        &self,
+        encoded_price: &EncodedPrice,
+        base_atoms: u64,
+    ) -> DropsetResult {
+        if base_atoms < self.min_order_base_atoms() {
+            return Err(DropsetError::OrderSizeBelowMinimum);
+        }
+        let price_mantissa = encoded_price.as_u32() & PRICE_MANTISSA_MASK;
+        if price_mantissa % self.price_mantissa_tick() != 0 {
+            return Err(DropsetError::PriceNotOnTick);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the market's current [`MarketStatus`].
+    #[inline(always)]
+    pub fn status(&self) -> Result<MarketStatus, DropsetError> {
+        MarketStatus::try_from(self.status)
+    }
+
+    /// Transitions the market to a new status. [`MarketStatus::Closed`] is final, so a closed
+    /// market's status can never change again.
+    #[inline(always)]
+    pub fn set_status(&mut self, status: MarketStatus) -> DropsetResult {
+        if self.status()? == MarketStatus::Closed {
+            return Err(DropsetError::MarketIsClosed);
+        }
+        self.status = status as u8;
+
+        Ok(())
+    }
+
+    /// Checks that the market has no seats, no open orders and no uncollected fees, i.e., that no
+    /// funds are owed to anyone and the market can be closed.
+    #[inline(always)]
+    pub fn check_is_empty(&self) -> DropsetResult {
+        if self.num_seats() != 0
+            || self.num_bids() != 0
+            || self.num_asks() != 0
+            || self.base_fees_accrued() != 0
+            || self.quote_fees_accrued() != 0
+        {
+            return Err(DropsetError::MarketNotEmpty);
+        }
+
+        Ok(())
+    }
 }
@@ -1,6 +1,9 @@
 //! See [`MarketSeat`].
 
-use solana_address::Address;
+use solana_address::{
+    address_eq,
+    Address,
+};
 use static_assertions::const_assert_eq;
 
 use crate::{
@@ -21,6 +24,10 @@ use crate::{
     },
 };
 
+/// The most base or quote atoms an abandoned seat can have available; see
+/// [`MarketSeat::is_abandoned`].
+pub const MAX_DUST_ATOMS: u64 = 1_000;
+
 /// Represents a user's position within a market.
 #[repr(C)]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -32,8 +39,14 @@ pub struct MarketSeat {
     /// The u64 amount of quote the maker can withdraw as LE bytes.
     quote_available: [u8; U64_SIZE],
     /// The mapping for a user's order prices to order sector indices.
-    /// This facilitates O(1) indexing from a user's seat -> their orders.
+    /// This facilitates O(1) indexing from a user's seat -> their orders. Orders past the inline
+    /// capacity are mapped in an overflow sector; see [`SeatOrderSectors`].
+    ///
+    /// [`SeatOrderSectors`]: crate::state::user_order_sectors::SeatOrderSectors
     pub user_order_sectors: UserOrderSectors,
+    /// The address allowed to place and cancel orders on the user's behalf. It can't withdraw or
+    /// close the seat. The default address means the seat has no delegate.
+    delegate: Address,
 }
 
 impl MarketSeat {
@@ -43,9 +56,33 @@ impl MarketSeat {
             base_available: base.to_le_bytes(),
             quote_available: quote.to_le_bytes(),
             user_order_sectors: UserOrderSectors::default(),
+            delegate: Address::default(),
         }
     }
 
+    /// The seat's delegate, if it has one.
+    #[inline(always)]
+    pub fn delegate(&self) -> Option<&Address> {
+        if address_eq(&self.delegate, &Address::default()) {
+            None
+        } else {
+            Some(&self.delegate)
+        }
+    }
+
+    /// Sets the seat's delegate. Passing `None` removes the current delegate.
+    #[inline(always)]
+    pub fn set_delegate(&mut self, delegate: Option<Address>) {
+        self.delegate = delegate.unwrap_or_default();
+    }
+
+    /// Whether or not `signer` can place and cancel orders for this seat; i.e., it's the seat's
+    /// user or delegate.
+    #[inline(always)]
+    pub fn is_user_or_delegate(&self, signer: &Address) -> bool {
+        address_eq(signer, &self.user) || self.delegate().is_some_and(|d| address_eq(signer, d))
+    }
+
     #[inline(always)]
     pub fn base_available(&self) -> u64 {
         u64::from_le_bytes(self.base_available)
@@ -128,6 +165,29 @@ impl MarketSeat {
         T::try_decrement_seat_collateral_available(self, amount)
     }
 
+    /// Whether or not the user has any open bids or asks.
+    #[inline(always)]
+    pub fn has_open_orders(&self) -> bool {
+        !self.user_order_sectors.bids.is_empty() || !self.user_order_sectors.asks.is_empty()
+    }
+
+    /// Whether or not the seat has no base or quote available and no open orders, meaning it can be
+    /// closed without moving any funds.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.base_available() == 0 && self.quote_available() == 0 && !self.has_open_orders()
+    }
+
+    /// Whether or not the seat only has dust available, i.e., at most [`MAX_DUST_ATOMS`] of both
+    /// base and quote, and has no open orders or delegate, meaning anyone can settle and close it.
+    #[inline(always)]
+    pub fn is_abandoned(&self) -> bool {
+        self.base_available() <= MAX_DUST_ATOMS
+            && self.quote_available() <= MAX_DUST_ATOMS
+            && !self.has_open_orders()
+            && self.delegate().is_none()
+    }
+
     /// This method is sound because:
     ///
     /// - `Self` is exactly `Self::LEN` bytes.
@@ -145,8 +205,11 @@ impl MarketSeat {
 // - `size_of` and `align_of` are checked below.
 // - All bit patterns are valid.
 unsafe impl Transmutable for MarketSeat {
-    const LEN: usize =
-        size_of::<Address>() + size_of::<u64>() + size_of::<u64>() + UserOrderSectors::LEN;
+    const LEN: usize = size_of::<Address>()
+        + size_of::<u64>()
+        + size_of::<u64>()
+        + UserOrderSectors::LEN
+        + size_of::<Address>();
 
     #[inline(always)]
     fn validate_bit_patterns(_bytes: &[u8]) -> crate::error::DropsetResult {
@@ -0,0 +1,91 @@
+//! See [`MarketStatus`].
+
+use crate::error::{
+    DropsetError,
+    DropsetResult,
+};
+
+/// The lifecycle status of a market, stored as a raw `u8` in the market header.
+///
+/// - [`MarketStatus::Active`]: all instructions are allowed.
+/// - [`MarketStatus::CancelOnly`]: users can cancel orders and manage their seats, but can't post
+///   or take orders.
+/// - [`MarketStatus::Paused`]: the order book is frozen; orders can't be posted, taken or canceled.
+/// - [`MarketStatus::Closed`]: like [`MarketStatus::CancelOnly`], but new deposits are rejected so
+///   the market can be fully drained and closed. This status is final.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(strum_macros::FromRepr, strum_macros::EnumIter))]
+pub enum MarketStatus {
+    Active,
+    CancelOnly,
+    Paused,
+    Closed,
+}
+
+impl TryFrom<u8> for MarketStatus {
+    type Error = DropsetError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Active),
+            1 => Ok(Self::CancelOnly),
+            2 => Ok(Self::Paused),
+            3 => Ok(Self::Closed),
+            _ => Err(DropsetError::InvalidMarketStatus),
+        }
+    }
+}
+
+impl MarketStatus {
+    /// Checks that orders can be posted to or taken from the book.
+    #[inline(always)]
+    pub fn check_allows_new_orders(self) -> DropsetResult {
+        match self {
+            Self::Active => Ok(()),
+            _ => Err(DropsetError::MarketStatusForbidsInstruction),
+        }
+    }
+
+    /// Checks that resting orders can be canceled.
+    #[inline(always)]
+    pub fn check_allows_cancels(self) -> DropsetResult {
+        match self {
+            Self::Paused => Err(DropsetError::MarketStatusForbidsInstruction),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that users can deposit funds or register new seats.
+    #[inline(always)]
+    pub fn check_allows_deposits(self) -> DropsetResult {
+        match self {
+            Self::Closed => Err(DropsetError::MarketStatusForbidsInstruction),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn market_status_try_from_u8_exhaustive() {
+        for v in 0..=u8::MAX {
+            match MarketStatus::from_repr(v) {
+                Some(variant) => assert_eq!(MarketStatus::try_from(v), Ok(variant)),
+                None => assert_eq!(
+                    MarketStatus::try_from(v),
+                    Err(DropsetError::InvalidMarketStatus)
+                ),
+            }
+        }
+        for variant in MarketStatus::iter() {
+            assert_eq!(MarketStatus::try_from(variant as u8), Ok(variant));
+        }
+    }
+}
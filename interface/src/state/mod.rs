@@ -8,6 +8,7 @@ pub mod linked_list;
 pub mod market;
 pub mod market_header;
 pub mod market_seat;
+pub mod market_status;
 pub mod order;
 pub mod seats_dll;
 pub mod sector;
@@ -7,6 +7,7 @@ use static_assertions::const_assert_eq;
 
 use crate::{
     error::DropsetResult,
+    instructions::OrderExpiry,
     state::{
         linked_list::{
             LinkedListHeaderOperations,
@@ -66,8 +67,14 @@ pub trait OrdersCollection: LinkedListHeaderOperations {
     ) -> NextSectorIndex;
 
     /// A post-only order must not execute immediately, so it must fail if it would cross the book
-    /// and match against resting liquidity.
-    fn post_only_crossing_check<H, S>(order: &Order, market: &Market<H, S>) -> DropsetResult
+    /// and match against resting liquidity. Resting orders expired at the given slot and unix
+    /// timestamp can't be filled, so they don't count as crossing.
+    fn post_only_crossing_check<H, S>(
+        order: &Order,
+        market: &Market<H, S>,
+        slot: u64,
+        unix_timestamp: i64,
+    ) -> DropsetResult
     where
         H: AsRef<MarketHeader>,
         S: AsRef<[u8]>;
@@ -94,8 +101,14 @@ pub trait OrdersCollection: LinkedListHeaderOperations {
     fn has_higher_price_priority(a: &EncodedPrice, b: &EncodedPrice) -> bool;
 }
 
-const ORDER_PADDING: usize =
-    PAYLOAD_SIZE - (size_of::<LeEncodedPrice>() + size_of::<LeSectorIndex>() + U64_SIZE + U64_SIZE);
+const ORDER_PADDING: usize = PAYLOAD_SIZE
+    - (size_of::<LeEncodedPrice>()
+        + size_of::<LeSectorIndex>()
+        + U64_SIZE
+        + U64_SIZE
+        + size_of::<u8>()
+        + U64_SIZE
+        + U64_SIZE);
 
 /// Represents a maker order in the orderbook.
 #[repr(C)]
@@ -109,19 +122,41 @@ pub struct Order {
     base_remaining: [u8; U64_SIZE],
     /// The u64 number of quote atoms left remaining to fill as LE bytes.
     quote_remaining: [u8; U64_SIZE],
+    /// The order's [`crate::instructions::ExpiryKind`] discriminant.
+    expiry_kind: u8,
+    /// The u64 slot or unix timestamp at which the order expires as LE bytes.
+    expires_at: [u8; U64_SIZE],
+    /// The u64 client-provided order id as LE bytes. It's opaque to the program and isn't
+    /// required to be unique.
+    client_order_id: [u8; U64_SIZE],
     /// Padding to fill the rest of the sector payload size.
     _padding: [u8; ORDER_PADDING],
 }
 
 impl Order {
-    /// Create a new order from the order info and the user seat.
+    /// Create a new order that never expires from the order info and the user seat.
     #[inline(always)]
     pub fn new(order_info: OrderInfo, user_seat_index: SectorIndex) -> Self {
+        Self::new_with_expiry(order_info, user_seat_index, OrderExpiry::NONE)
+    }
+
+    /// Create a new order from the order info, the user seat and the order's expiry.
+    ///
+    /// The expiry should already be validated with [`OrderExpiry::validate`].
+    #[inline(always)]
+    pub fn new_with_expiry(
+        order_info: OrderInfo,
+        user_seat_index: SectorIndex,
+        expiry: OrderExpiry,
+    ) -> Self {
         Self {
             encoded_price: order_info.encoded_price.into(),
             user_seat_index: user_seat_index.to_le_bytes(),
             base_remaining: order_info.base_atoms.to_le_bytes(),
             quote_remaining: order_info.quote_atoms.to_le_bytes(),
+            expiry_kind: expiry.kind,
+            expires_at: expiry.expires_at.to_le_bytes(),
+            client_order_id: [0; U64_SIZE],
             _padding: [0u8; ORDER_PADDING],
         }
     }
@@ -145,6 +180,11 @@ impl Order {
         u32::from_le_bytes(self.user_seat_index)
     }
 
+    #[inline(always)]
+    pub fn set_user_seat(&mut self, user_seat_index: SectorIndex) {
+        self.user_seat_index = user_seat_index.to_le_bytes();
+    }
+
     #[inline(always)]
     pub fn base_remaining(&self) -> u64 {
         u64::from_le_bytes(self.base_remaining)
@@ -165,6 +205,30 @@ impl Order {
         self.quote_remaining = amount.to_le_bytes();
     }
 
+    #[inline(always)]
+    pub fn expiry(&self) -> OrderExpiry {
+        OrderExpiry {
+            kind: self.expiry_kind,
+            expires_at: u64::from_le_bytes(self.expires_at),
+        }
+    }
+
+    #[inline(always)]
+    pub fn client_order_id(&self) -> u64 {
+        u64::from_le_bytes(self.client_order_id)
+    }
+
+    #[inline(always)]
+    pub fn set_client_order_id(&mut self, client_order_id: u64) {
+        self.client_order_id = client_order_id.to_le_bytes();
+    }
+
+    /// Whether or not the order has expired at the given slot and unix timestamp.
+    #[inline(always)]
+    pub fn is_expired(&self, slot: u64, unix_timestamp: i64) -> bool {
+        self.expiry().is_expired(slot, unix_timestamp)
+    }
+
     #[inline(always)]
     pub fn collateral_amount<T: OrdersCollection>(&self) -> u64 {
         T::get_order_collateral(self)
@@ -215,6 +279,7 @@ mod tests {
     };
 
     use super::*;
+    use crate::instructions::ExpiryKind;
 
     #[test]
     fn new_order_happy_path() {
@@ -229,6 +294,20 @@ mod tests {
         assert_eq!(quote_in_order, order.quote_remaining());
         assert_eq!(encoded_price_in_order, order.encoded_price());
         assert_eq!(user_seat, order.user_seat());
+        assert_eq!(OrderExpiry::NONE, order.expiry());
+        assert!(!order.is_expired(u64::MAX, i64::MAX));
+        assert_eq!(0, order.client_order_id());
+    }
+
+    #[test]
+    fn order_with_expiry() {
+        let order_info =
+            to_order_info((10_000_000, 5, 8, 0).into()).expect("Should create order info");
+        let expiry = OrderExpiry::at_unix_timestamp(1_700_000_000);
+        let order = Order::new_with_expiry(order_info, 17, expiry);
+        assert_eq!(expiry, order.expiry());
+        assert!(!order.is_expired(0, 1_699_999_999));
+        assert!(order.is_expired(0, 1_700_000_000));
     }
 
     #[test]
@@ -246,10 +325,13 @@ mod tests {
         assert_eq!(order.quote_remaining(), 50_000_000);
         let base_after = 111_111_111;
         let quote_after: u64 = 222_222_222;
+        let client_order_id = 333_333_333;
         order.set_base_remaining(base_after);
         order.set_quote_remaining(quote_after);
+        order.set_client_order_id(client_order_id);
         assert_eq!(order.base_remaining(), base_after);
         assert_eq!(order.quote_remaining(), quote_after);
+        assert_eq!(order.client_order_id(), client_order_id);
     }
 
     #[test]
@@ -262,14 +344,21 @@ mod tests {
             quote_atoms: QUOTE_ATOMS,
         };
         const USER_SEAT: SectorIndex = 9191;
-        let order = Order::new(order_info, USER_SEAT);
+        const EXPIRES_AT: u64 = 5555;
+        const CLIENT_ORDER_ID: u64 = 7777;
+        let mut order =
+            Order::new_with_expiry(order_info, USER_SEAT, OrderExpiry::at_slot(EXPIRES_AT));
+        order.set_client_order_id(CLIENT_ORDER_ID);
         assert_eq!(
             [
-                &0u32.to_le_bytes(),                // Encoded price.
-                &USER_SEAT.to_le_bytes(),           // User seat.
-                BASE_ATOMS.to_le_bytes().as_ref(),  // Base remaining.
-                QUOTE_ATOMS.to_le_bytes().as_ref(), // Quote remaining.
-                [0u8; ORDER_PADDING].as_ref(),      // Padding.
+                &0u32.to_le_bytes(),                    // Encoded price.
+                &USER_SEAT.to_le_bytes(),               // User seat.
+                BASE_ATOMS.to_le_bytes().as_ref(),      // Base remaining.
+                QUOTE_ATOMS.to_le_bytes().as_ref(),     // Quote remaining.
+                &[ExpiryKind::Slot as u8],              // Expiry kind.
+                EXPIRES_AT.to_le_bytes().as_ref(),      // Expires at.
+                CLIENT_ORDER_ID.to_le_bytes().as_ref(), // Client order id.
+                [0u8; ORDER_PADDING].as_ref(),          // Padding.
             ]
             .concat(),
             order.as_bytes()
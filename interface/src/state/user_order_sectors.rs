@@ -1,10 +1,13 @@
-use core::ops::Range;
+use core::marker::PhantomData;
 
 use price::{
     EncodedPrice,
     LeEncodedPrice,
 };
-use static_assertions::const_assert_eq;
+use static_assertions::{
+    const_assert,
+    const_assert_eq,
+};
 
 use crate::{
     error::{
@@ -12,11 +15,19 @@ use crate::{
         DropsetResult,
     },
     state::{
+        free_stack::Stack,
+        market_header::MarketHeader,
+        market_seat::MarketSeat,
         order::OrdersCollection,
         sector::{
+            AllBitPatternsValid,
             LeSectorIndex,
+            Payload,
+            Sector,
             SectorIndex,
             LE_NIL,
+            NIL,
+            PAYLOAD_SIZE,
         },
         transmutable::Transmutable,
     },
@@ -24,11 +35,28 @@ use crate::{
 
 /// The max number of orders a single user/address can have for a single market for bids or asks.
 /// That is, each user can have [`MAX_ORDERS`] bids and [`MAX_ORDERS`] asks for a single market.
-pub const MAX_ORDERS: u8 = 10;
+///
+/// This is an upper bound on each market's configurable `max_orders_per_user`, which is the cap
+/// actually enforced at runtime. Only the first [`MAX_INLINE_ORDERS`] orders of each side are
+/// mapped in the seat itself; the rest are mapped in the side's [`OverflowOrderSectors`] sector, so
+/// raising this value doesn't change the size of a sector.
+pub const MAX_ORDERS: u8 = 32;
 
 /// Helper const for [`MAX_ORDERS`] as a usize.
 pub const MAX_ORDERS_USIZE: usize = MAX_ORDERS as usize;
 
+/// The number of orders of each side mapped directly in a user's seat.
+///
+/// Every sector is sized to fit a seat, so raising this value increases the size of every sector in
+/// every market account.
+pub const MAX_INLINE_ORDERS: usize = 10;
+
+/// The number of orders mapped in a single [`OverflowOrderSectors`] sector.
+pub const MAX_OVERFLOW_ORDERS: usize = PAYLOAD_SIZE / PriceToIndexEntry::LEN;
+
+// A side's inline entries and its single overflow sector must be able to map every order.
+const_assert!(MAX_INLINE_ORDERS + MAX_OVERFLOW_ORDERS >= MAX_ORDERS_USIZE);
+
 /// The [`OrderSectors`] that maps the prices of a user's bids and asks to their corresponding
 /// orders' sector indices in the market account data.
 ///
@@ -52,44 +80,227 @@ impl UserOrderSectors {
     }
 }
 
-/// An array of [`MAX_ORDERS`] [`PriceToIndexEntry`]s that maps unique prices to a sector index.
+/// The part of a seat's price -> sector index mapping for one side of the book that's stored in the
+/// seat: [`MAX_INLINE_ORDERS`] [`PriceToIndexEntry`]s and the sector index of the side's
+/// [`OverflowOrderSectors`].
+///
+/// The overflow sector is only allocated once the inline entries are full and is freed as soon as
+/// it no longer maps any orders. Mutating the mapping goes through [`SeatOrderSectors`], since it
+/// may need to allocate or free the overflow sector.
 ///
 /// By default, each [`PriceToIndexEntry`] represents an unused item by mapping an encoded price u32
-/// value of `0` to the [`LE_NIL`] sector index.
-#[repr(transparent)]
+/// value of `0` to the [`LE_NIL`] sector index, and there is no overflow sector.
+#[repr(C)]
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct OrderSectors([PriceToIndexEntry; MAX_ORDERS_USIZE]);
+pub struct OrderSectors {
+    entries: [PriceToIndexEntry; MAX_INLINE_ORDERS],
+    /// The LE bytes of the overflow sector's [`SectorIndex`], or [`LE_NIL`] if it has none.
+    overflow_index: LeSectorIndex,
+}
 
 impl Default for OrderSectors {
     fn default() -> Self {
-        Self([PriceToIndexEntry::new_free(); MAX_ORDERS_USIZE])
+        Self {
+            entries: [PriceToIndexEntry::new_free(); MAX_INLINE_ORDERS],
+            overflow_index: LE_NIL,
+        }
     }
 }
 
 impl OrderSectors {
+    /// The sector index of the [`OverflowOrderSectors`], or [`NIL`] if there isn't one.
+    #[inline(always)]
+    pub fn overflow_index(&self) -> SectorIndex {
+        SectorIndex::from_le_bytes(self.overflow_index)
+    }
+
+    /// Updates the sector index of the [`OverflowOrderSectors`].
+    ///
+    /// Caller should ensure the index is [`NIL`] or the index of a sector with a valid
+    /// [`OverflowOrderSectors`] payload that isn't used for anything else.
+    #[inline(always)]
+    pub fn set_overflow_index(&mut self, index: SectorIndex) {
+        self.overflow_index = index.to_le_bytes();
+    }
+
+    /// Whether or not there are any mapped orders.
+    ///
+    /// This doesn't need to read the overflow sector, since it's freed as soon as it's empty.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.overflow_index == LE_NIL && self.entries.iter().all(|e| e.is_free())
+    }
+
+    /// Whether or not mapping another order would allocate a new overflow sector, i.e., whether the
+    /// inline entries are full and there is no overflow sector yet.
+    #[inline(always)]
+    pub fn needs_overflow_sector(&self) -> bool {
+        self.overflow_index == LE_NIL && self.entries.iter().all(|e| !e.is_free())
+    }
+
+    /// Returns an iterator over every mapped (non-free) entry, inline entries first.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees `sectors` is the sectors region of the market these order sectors belong
+    /// to.
+    #[inline(always)]
+    pub unsafe fn iter<'a>(&'a self, sectors: &'a [u8]) -> OrderSectorsIter<'a> {
+        let overflow: &[PriceToIndexEntry] = match self.overflow_index() {
+            NIL => &[],
+            // Safety: A non-NIL overflow index is always an in-bounds overflow sector.
+            index => unsafe {
+                &Sector::from_sector_index(sectors, index)
+                    .load_payload::<OverflowOrderSectors>()
+                    .entries
+            },
+        };
+
+        OrderSectorsIter {
+            entries: self.entries.iter().chain(overflow.iter()),
+        }
+    }
+}
+
+/// An iterator over the mapped entries of a seat's [`OrderSectors`] and its overflow sector.
+pub struct OrderSectorsIter<'a> {
+    entries: core::iter::Chain<
+        core::slice::Iter<'a, PriceToIndexEntry>,
+        core::slice::Iter<'a, PriceToIndexEntry>,
+    >,
+}
+
+impl<'a> Iterator for OrderSectorsIter<'a> {
+    type Item = &'a PriceToIndexEntry;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.find(|e| !e.is_free())
+    }
+}
+
+const OVERFLOW_PADDING: usize = PAYLOAD_SIZE - PriceToIndexEntry::LEN * MAX_OVERFLOW_ORDERS;
+
+/// The sector payload holding the [`PriceToIndexEntry`]s of a seat side that don't fit in its
+/// inline [`OrderSectors`].
+///
+/// An overflow sector isn't part of any linked list, so its `next` and `prev` are unused.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverflowOrderSectors {
+    entries: [PriceToIndexEntry; MAX_OVERFLOW_ORDERS],
+    /// Padding to fill the rest of the sector payload size.
+    _padding: [u8; OVERFLOW_PADDING],
+}
+
+/// Mutable access to one side of a seat's price -> order sectors mapping, including its overflow
+/// sector.
+///
+/// Holds the market's header and sectors so that the overflow sector can be allocated from and
+/// returned to the free stack as needed.
+pub struct SeatOrderSectors<'a, Side: OrdersCollection> {
+    header: &'a mut MarketHeader,
+    sectors: &'a mut [u8],
+    seat_index: SectorIndex,
+    _side: PhantomData<Side>,
+}
+
+impl<'a, Side: OrdersCollection> SeatOrderSectors<'a, Side> {
+    /// # Safety
+    ///
+    /// Caller guarantees `seat_index` is the in-bounds sector index of a seat in `sectors`.
+    #[inline(always)]
+    pub unsafe fn new_from_parts(
+        header: &'a mut MarketHeader,
+        sectors: &'a mut [u8],
+        seat_index: SectorIndex,
+    ) -> Self {
+        Self {
+            header,
+            sectors,
+            seat_index,
+            _side: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    fn order_sectors(&self) -> &OrderSectors {
+        // Safety: The seat index is a valid seat's sector index, guaranteed at construction.
+        unsafe { Sector::from_sector_index(self.sectors, self.seat_index) }
+            .load_payload::<MarketSeat>()
+            .user_order_sectors
+            .order_sectors::<Side>()
+    }
+
+    #[inline(always)]
+    fn order_sectors_mut(&mut self) -> &mut OrderSectors {
+        // Safety: The seat index is a valid seat's sector index, guaranteed at construction.
+        unsafe { Sector::from_sector_index_mut(self.sectors, self.seat_index) }
+            .load_payload_mut::<MarketSeat>()
+            .user_order_sectors
+            .order_sectors_mut::<Side>()
+    }
+
+    /// # Safety
+    ///
+    /// Caller guarantees `index` is the seat side's non-NIL overflow index.
+    #[inline(always)]
+    unsafe fn overflow_mut(&mut self, index: SectorIndex) -> &mut OverflowOrderSectors {
+        unsafe { Sector::from_sector_index_mut(self.sectors, index) }
+            .load_payload_mut::<OverflowOrderSectors>()
+    }
+
+    /// Returns an iterator over every mapped (non-free) entry.
+    #[inline(always)]
+    pub fn iter(&self) -> OrderSectorsIter<'_> {
+        // Safety: The order sectors belong to this market's seat.
+        unsafe { self.order_sectors().iter(self.sectors) }
+    }
+
     /// Attempt to find and return the sector index for the order corresponding to the passed
     /// encoded price.
     #[inline(always)]
     pub fn get(&self, target_price: &LeEncodedPrice) -> Option<SectorIndex> {
-        self.0.iter().find_map(
-            |PriceToIndexEntry {
-                 encoded_price,
-                 sector_index,
-             }| {
-                match encoded_price.as_slice() == target_price.as_slice() {
-                    true => Some(u32::from_le_bytes(*sector_index)),
-                    false => None,
-                }
-            },
-        )
+        self.iter()
+            .find(|e| e.encoded_price.as_slice() == target_price.as_slice())
+            .map(|e| SectorIndex::from_le_bytes(e.sector_index))
+    }
+
+    /// Returns the number of mapped entries, i.e., the number of the user's open orders.
+    #[inline(always)]
+    pub fn num_orders(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns an array of copied sector indices from the mapped entries, followed by [`NIL`] for
+    /// each unused slot.
+    #[inline(always)]
+    pub fn to_sector_indices(&self) -> [SectorIndex; MAX_ORDERS_USIZE] {
+        let mut res = [NIL; MAX_ORDERS_USIZE];
+        for (index, entry) in res.iter_mut().zip(self.iter()) {
+            *index = SectorIndex::from_le_bytes(entry.sector_index);
+        }
+        res
+    }
+
+    /// Returns an array of the copied mapped entries, followed by a free entry for each unused
+    /// slot.
+    #[inline(always)]
+    pub fn to_entries(&self) -> [PriceToIndexEntry; MAX_ORDERS_USIZE] {
+        let mut res = [PriceToIndexEntry::new_free(); MAX_ORDERS_USIZE];
+        for (res_entry, entry) in res.iter_mut().zip(self.iter()) {
+            *res_entry = *entry;
+        }
+        res
     }
 
     /// Fallibly add a [`PriceToIndexEntry`] to a user's orders.
     ///
-    /// Fails if the user already has [`MAX_ORDERS`] or the price already has an existing order.
+    /// Fails if the user already has [`MAX_ORDERS`], the price already has an existing order, or an
+    /// overflow sector is needed and there are no free sectors left.
     ///
-    /// The order's sector index passed should be non-NIL or the [`crate::state::sector::Sector`]
-    /// after mutation will continue to be treated as if it were free.
+    /// The order's sector index passed should be non-NIL or the entry after mutation will continue
+    /// to be treated as if it were free.
     #[inline(always)]
     pub fn add(
         &mut self,
@@ -97,25 +308,50 @@ impl OrderSectors {
         order_index: &LeSectorIndex,
     ) -> DropsetResult {
         // Check if the price already exists in an entry and fail early if it does.
-        if self
-            .iter()
-            .any(|e| e.encoded_price.as_slice() == new_price.as_slice())
+        let mut num_orders = 0;
+        for entry in self.iter() {
+            if entry.encoded_price.as_slice() == new_price.as_slice() {
+                return Err(DropsetError::OrderWithPriceAlreadyExists);
+            }
+            num_orders += 1;
+        }
+        if num_orders >= MAX_ORDERS_USIZE {
+            return Err(DropsetError::UserHasMaxOrders);
+        }
+
+        let new_entry = PriceToIndexEntry {
+            encoded_price: *new_price,
+            sector_index: *order_index,
+        };
+
+        if let Some(entry) = self
+            .order_sectors_mut()
+            .entries
+            .iter_mut()
+            .find(|e| e.is_free())
         {
-            return Err(DropsetError::OrderWithPriceAlreadyExists);
+            *entry = new_entry;
+            return Ok(());
         }
 
-        let entry = self
+        let overflow_index = match self.order_sectors().overflow_index() {
+            NIL => self.allocate_overflow_sector()?,
+            index => index,
+        };
+
+        // Safety: `overflow_index` is the seat side's non-NIL overflow index.
+        let entry = unsafe { self.overflow_mut(overflow_index) }
+            .entries
             .iter_mut()
             .find(|e| e.is_free())
             .ok_or(DropsetError::UserHasMaxOrders)?;
-
-        entry.encoded_price = *new_price;
-        entry.sector_index = *order_index;
+        *entry = new_entry;
 
         Ok(())
     }
 
-    /// Fallibly remove a [`PriceToIndexEntry`] from a user's orders.
+    /// Fallibly remove a [`PriceToIndexEntry`] from a user's orders, freeing the overflow sector if
+    /// it no longer maps any orders.
     ///
     /// Fails if the user does not have an order corresponding to the passed encoded price.
     ///
@@ -125,64 +361,116 @@ impl OrderSectors {
     /// Returns the mapped order's sector index.
     #[inline(always)]
     pub fn find_remove(&mut self, encoded_price: u32) -> Result<LeSectorIndex, DropsetError> {
-        let entry = self
-            .0
+        let target = encoded_price.to_le_bytes();
+        let is_target = |e: &&mut PriceToIndexEntry| {
+            !e.is_free() && e.encoded_price.as_slice() == target.as_slice()
+        };
+
+        if let Some(entry) = self.order_sectors_mut().entries.iter_mut().find(is_target) {
+            let sector_index = entry.sector_index;
+            mark_as_free(entry);
+            return Ok(sector_index);
+        }
+
+        let overflow_index = self.order_sectors().overflow_index();
+        if overflow_index == NIL {
+            return Err(DropsetError::OrderNotFound);
+        }
+
+        // Safety: `overflow_index` is the seat side's non-NIL overflow index.
+        let overflow = unsafe { self.overflow_mut(overflow_index) };
+        let entry = overflow
+            .entries
             .iter_mut()
-            .find(|e| e.encoded_price.as_slice() == &encoded_price.to_le_bytes())
+            .find(is_target)
             .ok_or(DropsetError::OrderNotFound)?;
-
         let sector_index = entry.sector_index;
-
         mark_as_free(entry);
 
+        if overflow.entries.iter().all(|e| e.is_free()) {
+            // Safety: `overflow_index` is the seat side's non-NIL overflow index.
+            unsafe { self.free_overflow_sector(overflow_index) };
+        }
+
         Ok(sector_index)
     }
 
-    /// Removes/frees the [PriceToIndexEntry] entries spanning the passed `range`.
-    ///
-    /// # Safety
-    ///
-    /// Caller must guarantee all indices in `range` are less than [MAX_ORDERS_USIZE].
+    /// Removes every entry and frees the overflow sector, if there is one.
     #[inline(always)]
-    pub unsafe fn remove_range(&mut self, range: Range<usize>) {
-        for index in range {
-            // Safety: All indices in `range` are less than the max index.
-            let entry = unsafe { self.0.get_unchecked_mut(index) };
-            mark_as_free(entry);
+    pub fn clear(&mut self) {
+        self.order_sectors_mut()
+            .entries
+            .iter_mut()
+            .for_each(mark_as_free);
+
+        let overflow_index = self.order_sectors().overflow_index();
+        if overflow_index != NIL {
+            // Safety: `overflow_index` is the seat side's non-NIL overflow index.
+            unsafe { self.free_overflow_sector(overflow_index) };
         }
     }
 
-    /// Returns the mutable entry at the passed index.
-    ///
-    /// # Safety
-    ///
-    /// - Caller must guarantee the index passed is less than [MAX_ORDERS_USIZE].
+    /// Updates the entry mapped to the order at `old_index` to map to `new_index` instead, e.g.
+    /// after the order's sector is relocated.
     ///
-    /// Although it will not necessarily result in undefined behavior, caller should ensure that
-    /// mutating the entry will not result in the [OrderSectors] that it belongs to having duplicate
-    /// non-free price entries.
+    /// Does nothing if no entry maps to `old_index`.
     #[inline(always)]
-    pub unsafe fn get_mut_entry_at(&mut self, index: usize) -> &mut PriceToIndexEntry {
-        self.0.get_unchecked_mut(index)
-    }
+    pub fn replace_sector_index(&mut self, old_index: SectorIndex, new_index: SectorIndex) {
+        let (old_index, new_index) = (old_index.to_le_bytes(), new_index.to_le_bytes());
 
-    /// Returns an array of copied sector indices from the mapped entries.
-    #[inline(always)]
-    pub fn to_sector_indices(&self) -> [SectorIndex; MAX_ORDERS_USIZE] {
-        core::array::from_fn(|i| {
-            let item = self.0[i];
-            SectorIndex::from_le_bytes(item.sector_index)
-        })
+        if let Some(entry) = self
+            .order_sectors_mut()
+            .entries
+            .iter_mut()
+            .find(|e| e.sector_index == old_index)
+        {
+            entry.sector_index = new_index;
+            return;
+        }
+
+        let overflow_index = self.order_sectors().overflow_index();
+        if overflow_index == NIL {
+            return;
+        }
+
+        // Safety: `overflow_index` is the seat side's non-NIL overflow index.
+        if let Some(entry) = unsafe { self.overflow_mut(overflow_index) }
+            .entries
+            .iter_mut()
+            .find(|e| e.sector_index == old_index)
+        {
+            entry.sector_index = new_index;
+        }
     }
 
+    /// Pops a sector off the free stack and initializes it as the seat side's empty overflow
+    /// sector.
     #[inline(always)]
-    pub fn iter(&self) -> core::slice::Iter<'_, PriceToIndexEntry> {
-        self.0.iter()
+    fn allocate_overflow_sector(&mut self) -> Result<SectorIndex, DropsetError> {
+        let index = Stack::new_from_parts(self.header, self.sectors).pop_free_sector()?;
+
+        // Safety: The index was just popped from the free stack, so it's in-bounds.
+        let sector = unsafe { Sector::from_sector_index_mut(self.sectors, index) };
+        sector.set_prev(NIL);
+        sector.set_next(NIL);
+        sector.load_payload_mut::<OverflowOrderSectors>().entries =
+            [PriceToIndexEntry::new_free(); MAX_OVERFLOW_ORDERS];
+
+        self.order_sectors_mut().set_overflow_index(index);
+
+        Ok(index)
     }
 
+    /// Pushes the overflow sector back onto the free stack and unlinks it from the seat.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees `index` is the seat side's non-NIL overflow index.
     #[inline(always)]
-    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, PriceToIndexEntry> {
-        self.0.iter_mut()
+    unsafe fn free_overflow_sector(&mut self, index: SectorIndex) {
+        self.order_sectors_mut().set_overflow_index(NIL);
+        // Safety: The overflow index is in-bounds.
+        unsafe { Stack::new_from_parts(self.header, self.sectors).push_free_sector(index) };
     }
 }
 
@@ -241,7 +529,7 @@ pub fn mark_as_free(entry: &mut PriceToIndexEntry) {
 // - `size_of` and `align_of` are checked below.
 // - All bit patterns are valid.
 unsafe impl Transmutable for UserOrderSectors {
-    const LEN: usize = size_of::<PriceToIndexEntry>() * (MAX_ORDERS * 2) as usize;
+    const LEN: usize = OrderSectors::LEN * 2;
 
     #[inline(always)]
     fn validate_bit_patterns(_bytes: &[u8]) -> crate::error::DropsetResult {
@@ -259,7 +547,8 @@ const_assert_eq!(align_of::<UserOrderSectors>(), 1);
 // - `size_of` and `align_of` are checked below.
 // - All bit patterns are valid.
 unsafe impl Transmutable for OrderSectors {
-    const LEN: usize = size_of::<PriceToIndexEntry>() * MAX_ORDERS_USIZE;
+    const LEN: usize =
+        size_of::<PriceToIndexEntry>() * MAX_INLINE_ORDERS + size_of::<LeSectorIndex>();
 
     #[inline(always)]
     fn validate_bit_patterns(_bytes: &[u8]) -> crate::error::DropsetResult {
@@ -271,6 +560,30 @@ unsafe impl Transmutable for OrderSectors {
 const_assert_eq!(OrderSectors::LEN, size_of::<OrderSectors>());
 const_assert_eq!(align_of::<OrderSectors>(), 1);
 
+// Safety:
+//
+// - Stable layout with `#[repr(C)]`.
+// - `size_of` and `align_of` are checked below.
+// - All bit patterns are valid.
+unsafe impl Transmutable for OverflowOrderSectors {
+    const LEN: usize = PAYLOAD_SIZE;
+
+    #[inline(always)]
+    fn validate_bit_patterns(_bytes: &[u8]) -> crate::error::DropsetResult {
+        // All bit patterns are valid.
+        Ok(())
+    }
+}
+
+const_assert_eq!(OverflowOrderSectors::LEN, size_of::<OverflowOrderSectors>());
+const_assert_eq!(align_of::<OverflowOrderSectors>(), 1);
+
+// Safety: OverflowOrderSectors's size is checked above.
+unsafe impl Payload for OverflowOrderSectors {}
+
+// Safety: All bit patterns are valid.
+unsafe impl AllBitPatternsValid for OverflowOrderSectors {}
+
 // Safety:
 //
 // - Stable layout with `#[repr(C)]`.
@@ -325,20 +638,29 @@ mod tests {
         LeEncodedPrice,
         ValidatedPriceMantissa,
     };
+    use solana_address::Address;
 
     use crate::{
         error::DropsetError,
         state::{
+            asks_dll::AskOrders,
+            bids_dll::BidOrders,
+            market::MarketRefMut,
+            market_header::MarketHeader,
+            market_seat::MarketSeat,
             sector::{
                 LeSectorIndex,
+                Sector,
                 SectorIndex,
                 LE_NIL,
+                NIL,
             },
             transmutable::Transmutable,
             user_order_sectors::{
                 OrderSectors,
                 PriceToIndexEntry,
                 UserOrderSectors,
+                MAX_INLINE_ORDERS,
                 MAX_ORDERS,
                 MAX_ORDERS_USIZE,
             },
@@ -348,14 +670,52 @@ mod tests {
 
     extern crate std;
 
-    use std::vec::Vec;
+    use std::{
+        vec,
+        vec::Vec,
+    };
+
+    /// A seat and enough free sectors for an overflow sector on each side.
+    const N_SECTORS: usize = 3;
+
+    /// Initializes `bytes` as a market with a single seat and returns the market and seat index.
+    fn market_with_seat(bytes: &mut [u8]) -> (MarketRefMut<'_>, SectorIndex) {
+        // Safety: `bytes` is always longer than the market header.
+        let mut market = unsafe { MarketRefMut::from_bytes_mut(bytes) };
+        // Safety: The bytes are zeroed and the range is in-bounds.
+        unsafe {
+            market
+                .free_stack()
+                .convert_zeroed_bytes_to_free_sectors(0, N_SECTORS as u32)
+        }
+        .unwrap();
+        let seat_index = market.free_stack().pop_free_sector().unwrap();
+        // Safety: The seat index was just popped from the free stack.
+        unsafe { Sector::from_sector_index_mut(market.sectors, seat_index) }
+            .set_payload(MarketSeat::new(Address::default(), 0, 0).as_bytes());
+
+        (market, seat_index)
+    }
+
+    fn market_bytes() -> Vec<u8> {
+        vec![0u8; MarketHeader::LEN + Sector::LEN * N_SECTORS]
+    }
+
+    fn price(i: u32) -> EncodedPrice {
+        EncodedPrice::new(
+            ValidatedPriceMantissa::try_from(10_000_000 + i).unwrap(),
+            biased_exponent!(0),
+        )
+    }
 
     #[test]
     fn new_all_free() {
         let order_sectors = UserOrderSectors::default();
         // All bids and asks should be free.
-        assert!(order_sectors.asks.iter().all(|ask| ask.is_free()));
-        assert!(order_sectors.bids.iter().all(|bid| bid.is_free()));
+        assert!(order_sectors.asks.is_empty());
+        assert!(order_sectors.bids.is_empty());
+        assert_eq!(order_sectors.bids.overflow_index(), NIL);
+        assert_eq!(order_sectors.asks.overflow_index(), NIL);
     }
 
     #[test]
@@ -374,167 +734,191 @@ mod tests {
     #[test]
     fn free_orders_transmutable_bytes() {
         let free_bytes_vec = [[0; U32_SIZE], LE_NIL].concat();
-        let max_orders_all_freed: [u8; PriceToIndexEntry::LEN * MAX_ORDERS_USIZE] = (0..MAX_ORDERS)
+        let all_freed: [u8; OrderSectors::LEN] = (0..MAX_INLINE_ORDERS)
             .flat_map(|_| free_bytes_vec.iter().cloned())
+            .chain(LE_NIL)
             .collect::<Vec<u8>>()
             .try_into()
             .unwrap();
 
-        let new_max_orders_all_freed_from_transmute =
-            OrderSectors::load(&max_orders_all_freed).expect("Should transmute");
-
-        assert_eq!(
-            new_max_orders_all_freed_from_transmute,
-            &OrderSectors::default()
-        );
-    }
+        let new_all_freed_from_transmute =
+            OrderSectors::load(&all_freed).expect("Should transmute");
 
-    #[test]
-    fn happy_path_free_orders() {
-        let order_sectors = UserOrderSectors::default();
-        assert_eq!(order_sectors.bids, OrderSectors::default());
-        assert_eq!(order_sectors.asks, OrderSectors::default());
+        assert_eq!(new_all_freed_from_transmute, &OrderSectors::default());
     }
 
     #[test]
     fn happy_path_one_bid_one_ask() {
-        let mut order_sectors = UserOrderSectors::default();
+        let mut bytes = market_bytes();
+        let (mut market, seat_index) = market_with_seat(&mut bytes);
         let bid_encoded_price = encoded_price!(12_345_678, 1);
         let (bid_index, ask_index): (SectorIndex, SectorIndex) = (10, 11);
         let ask_encoded_price = encoded_price!(87_654_321, 2);
         let new_bid_price: &LeEncodedPrice = &bid_encoded_price.into();
         let new_ask_price: &LeEncodedPrice = &ask_encoded_price.into();
 
-        order_sectors
-            .bids
-            .add(new_bid_price, &bid_index.to_le_bytes())
-            .expect("Should add the mapping");
-        order_sectors
-            .asks
-            .add(new_ask_price, &ask_index.to_le_bytes())
-            .expect("Should add the mapping");
-        assert_eq!(order_sectors.bids.get(new_bid_price).unwrap(), bid_index);
-        assert_eq!(order_sectors.asks.get(new_ask_price).unwrap(), ask_index);
+        unsafe {
+            let mut bids = market.seat_order_sectors::<BidOrders>(seat_index);
+            bids.add(new_bid_price, &bid_index.to_le_bytes())
+                .expect("Should add the mapping");
+            assert_eq!(bids.get(new_bid_price).unwrap(), bid_index);
+            assert!(bids.get(new_ask_price).is_none());
+
+            let mut asks = market.seat_order_sectors::<AskOrders>(seat_index);
+            asks.add(new_ask_price, &ask_index.to_le_bytes())
+                .expect("Should add the mapping");
+            assert_eq!(asks.get(new_ask_price).unwrap(), ask_index);
+            assert!(asks.get(new_bid_price).is_none());
+        }
     }
 
     #[test]
     fn duplicate_bid_error() {
-        let mut order_sectors = UserOrderSectors::default();
-        let bid_encoded_price = encoded_price!(12_345_678, 1);
-        let bid_index = 10u32;
-        let bid_index_le_bytes = &bid_index.to_le_bytes();
-        let bid_encoded_le_price: &LeEncodedPrice = &bid_encoded_price.into();
-        order_sectors
-            .bids
-            .add(bid_encoded_le_price, bid_index_le_bytes)
+        let mut bytes = market_bytes();
+        let (mut market, seat_index) = market_with_seat(&mut bytes);
+        let mut bids = unsafe { market.seat_order_sectors::<BidOrders>(seat_index) };
+        let bid_encoded_le_price: &LeEncodedPrice = &encoded_price!(12_345_678, 1).into();
+        let bid_index_le_bytes = &10u32.to_le_bytes();
+        bids.add(bid_encoded_le_price, bid_index_le_bytes)
             .expect("Should add the mapping");
 
-        let failed_add = order_sectors
-            .bids
-            .add(bid_encoded_le_price, bid_index_le_bytes);
-
         assert!(matches!(
-            failed_add,
+            bids.add(bid_encoded_le_price, bid_index_le_bytes),
             Err(DropsetError::OrderWithPriceAlreadyExists)
         ));
     }
 
     #[test]
     fn remove_nonexistent_order_error() {
-        let mut order_sectors = UserOrderSectors::default();
+        let mut bytes = market_bytes();
+        let (mut market, seat_index) = market_with_seat(&mut bytes);
+        let mut bids = unsafe { market.seat_order_sectors::<BidOrders>(seat_index) };
         let bid_encoded_price = encoded_price!(12_345_678, 1);
-        let failed_remove = order_sectors.bids.find_remove(bid_encoded_price.as_u32());
+        let failed_remove = bids.find_remove(bid_encoded_price.as_u32());
         assert!(matches!(failed_remove, Err(DropsetError::OrderNotFound)));
     }
 
     #[test]
     fn remove_order() {
-        let mut order_sectors = UserOrderSectors::default();
-        // All bids should be free.
-        assert!(order_sectors.bids.iter().all(|bid| bid.is_free()));
+        let mut bytes = market_bytes();
+        let (mut market, seat_index) = market_with_seat(&mut bytes);
+        let mut bids = unsafe { market.seat_order_sectors::<BidOrders>(seat_index) };
         let bid_encoded_price = encoded_price!(12_345_678, 1);
-        let bid_index = 10u32;
-        assert!(order_sectors
-            .bids
-            .add(&bid_encoded_price.into(), &bid_index.to_le_bytes())
+        assert!(bids
+            .add(&bid_encoded_price.into(), &10u32.to_le_bytes())
             .is_ok());
-        // Count the number of orders that are in use (not free).
-        let num_orders_in_use = order_sectors
-            .bids
-            .iter()
-            .fold(0, |acc, p| match p.is_free() {
-                true => acc,
-                false => acc + 1,
-            });
-        assert_eq!(num_orders_in_use, 1);
+        assert_eq!(bids.num_orders(), 1);
 
-        assert!(order_sectors
-            .bids
-            .find_remove(bid_encoded_price.as_u32())
-            .is_ok());
-        assert!(order_sectors.bids.iter().all(|bid| bid.is_free()));
+        assert_eq!(
+            bids.find_remove(bid_encoded_price.as_u32()),
+            Ok(10u32.to_le_bytes())
+        );
+        assert_eq!(bids.num_orders(), 0);
+    }
+
+    #[test]
+    fn overflow_sector_allocated_and_freed() {
+        let mut bytes = market_bytes();
+        let (mut market, seat_index) = market_with_seat(&mut bytes);
+        let num_free_sectors = market.header.num_free_sectors();
+
+        let mut bids = unsafe { market.seat_order_sectors::<BidOrders>(seat_index) };
+        for i in 0..MAX_INLINE_ORDERS as u32 {
+            bids.add(&price(i).into(), &i.to_le_bytes()).unwrap();
+        }
+        let seat_bids = |market: &MarketRefMut| {
+            unsafe { Sector::from_sector_index(market.sectors, seat_index) }
+                .load_payload::<MarketSeat>()
+                .user_order_sectors
+                .bids
+                .clone()
+        };
+        assert!(seat_bids(&market).needs_overflow_sector());
+        assert_eq!(market.header.num_free_sectors(), num_free_sectors);
+
+        // The next order is mapped in a newly allocated overflow sector.
+        let overflow_price = price(MAX_INLINE_ORDERS as u32);
+        let mut bids = unsafe { market.seat_order_sectors::<BidOrders>(seat_index) };
+        bids.add(&overflow_price.into(), &100u32.to_le_bytes())
+            .unwrap();
+        assert_eq!(bids.get(&overflow_price.into()), Some(100));
+        assert_eq!(bids.num_orders(), MAX_INLINE_ORDERS + 1);
+        assert_ne!(seat_bids(&market).overflow_index(), NIL);
+        assert!(!seat_bids(&market).needs_overflow_sector());
+        assert_eq!(market.header.num_free_sectors(), num_free_sectors - 1);
+
+        // Removing the only order in the overflow sector frees it.
+        let mut bids = unsafe { market.seat_order_sectors::<BidOrders>(seat_index) };
+        bids.find_remove(overflow_price.as_u32()).unwrap();
+        assert_eq!(seat_bids(&market).overflow_index(), NIL);
+        assert_eq!(market.header.num_free_sectors(), num_free_sectors);
+
+        // Clearing the orders frees the overflow sector too.
+        let mut bids = unsafe { market.seat_order_sectors::<BidOrders>(seat_index) };
+        bids.add(&overflow_price.into(), &100u32.to_le_bytes())
+            .unwrap();
+        bids.clear();
+        assert_eq!(bids.num_orders(), 0);
+        assert!(seat_bids(&market).is_empty());
+        assert_eq!(market.header.num_free_sectors(), num_free_sectors);
     }
 
     #[test]
     fn too_many_orders_error() {
-        let mut order_sectors = UserOrderSectors::default();
+        let mut bytes = market_bytes();
+        let (mut market, seat_index) = market_with_seat(&mut bytes);
         for i in 0..=MAX_ORDERS as u32 {
-            let encoded_price = EncodedPrice::new(
-                ValidatedPriceMantissa::try_from(10_000_000 + i).unwrap(),
-                biased_exponent!(0),
-            );
+            let encoded_price = price(i);
 
             if i != MAX_ORDERS as u32 {
                 // Add each new price to both bids and asks and assert it is successful.
-                assert!(order_sectors
-                    .bids
-                    .add(&encoded_price.into(), &i.to_le_bytes())
-                    .is_ok());
-                assert!(order_sectors
-                    .asks
-                    .add(&encoded_price.into(), &i.to_le_bytes())
-                    .is_ok());
+                unsafe {
+                    assert!(market
+                        .seat_order_sectors::<BidOrders>(seat_index)
+                        .add(&encoded_price.into(), &i.to_le_bytes())
+                        .is_ok());
+                    assert!(market
+                        .seat_order_sectors::<AskOrders>(seat_index)
+                        .add(&encoded_price.into(), &i.to_le_bytes())
+                        .is_ok());
+                }
             } else {
                 // If this is the last order, it should fail, since it's one beyond the max amount.
-                assert!(matches!(
-                    order_sectors
-                        .bids
-                        .add(&encoded_price.into(), &i.to_le_bytes()),
-                    Err(DropsetError::UserHasMaxOrders)
-                ));
-                assert!(matches!(
-                    order_sectors
-                        .asks
-                        .add(&encoded_price.into(), &i.to_le_bytes()),
-                    Err(DropsetError::UserHasMaxOrders)
-                ));
+                unsafe {
+                    assert!(matches!(
+                        market
+                            .seat_order_sectors::<BidOrders>(seat_index)
+                            .add(&encoded_price.into(), &i.to_le_bytes()),
+                        Err(DropsetError::UserHasMaxOrders)
+                    ));
+                    assert!(matches!(
+                        market
+                            .seat_order_sectors::<AskOrders>(seat_index)
+                            .add(&encoded_price.into(), &i.to_le_bytes()),
+                        Err(DropsetError::UserHasMaxOrders)
+                    ));
+                }
             }
         }
     }
 
     #[test]
     fn repost_arbitrary_order() {
-        let mut order_sectors = UserOrderSectors::default();
+        let mut bytes = market_bytes();
+        let (mut market, seat_index) = market_with_seat(&mut bytes);
+        let mut bids = unsafe { market.seat_order_sectors::<BidOrders>(seat_index) };
         let index_and_encoded_price_pairs: [(u32, EncodedPrice); MAX_ORDERS_USIZE] =
-            core::array::from_fn(|i| {
-                let encoded_price = EncodedPrice::new(
-                    ValidatedPriceMantissa::try_from(10_000_000 + i as u32).unwrap(),
-                    biased_exponent!(0),
-                );
-                (i as u32, encoded_price)
-            });
+            core::array::from_fn(|i| (i as u32, price(i as u32)));
 
         for (i, encoded_price) in index_and_encoded_price_pairs.iter() {
-            order_sectors
-                .bids
-                .add(&(*encoded_price).into(), &i.to_le_bytes())
+            bids.add(&(*encoded_price).into(), &i.to_le_bytes())
                 .unwrap();
         }
 
-        // All bids should be in use.
-        assert!(order_sectors.bids.iter().all(|bid| !bid.is_free()));
+        assert_eq!(bids.num_orders(), MAX_ORDERS_USIZE);
 
-        const ELEMENT_REMOVED: usize = 1;
+        // Remove an order from the overflow sector so that it stays allocated.
+        const ELEMENT_REMOVED: usize = MAX_INLINE_ORDERS + 1;
         // Get the sector index and price at the element we are removing.
         let (old_sector_index, old_price) =
             *index_and_encoded_price_pairs.get(ELEMENT_REMOVED).unwrap();
@@ -543,32 +927,25 @@ mod tests {
         let new_price = encoded_price!(50_000_000, 0);
 
         // Ensure the new price doesn't exist in the bids yet.
-        assert!(order_sectors.bids.get(&new_price.into()).is_none());
+        assert!(bids.get(&new_price.into()).is_none());
 
         // Ensure the old sector index doesn't equal the new index it's being updated to so the
         // final check is meaningful and not a misleading equality check.
         assert_ne!(old_sector_index, new_sector_index);
 
         // Remove the old price.
-        assert!(order_sectors.bids.find_remove(old_price.as_u32()).is_ok());
+        assert!(bids.find_remove(old_price.as_u32()).is_ok());
 
         // Add the new price.
-        assert!(order_sectors
-            .bids
+        assert!(bids
             .add(&new_price.into(), &new_sector_index.to_le_bytes())
             .is_ok());
 
         // Ensure the old price has been removed and the new price exists and is mapped to the new
         // sector index.
-        assert!(order_sectors.bids.get(&old_price.into()).is_none());
-        assert!(order_sectors.bids.get(&new_price.into()).is_some());
-        assert_eq!(
-            order_sectors.bids.get(&new_price.into()).unwrap(),
-            new_sector_index
-        );
-
-        // Ensure there are no free bids.
-        assert!(order_sectors.bids.iter().all(|bid| !bid.is_free()));
+        assert!(bids.get(&old_price.into()).is_none());
+        assert_eq!(bids.get(&new_price.into()), Some(new_sector_index));
+        assert_eq!(bids.num_orders(), MAX_ORDERS_USIZE);
 
         // Check the final result in whole.
         let expected_index_and_encoded_price_pairs: [(u32, EncodedPrice); MAX_ORDERS_USIZE] =
@@ -576,22 +953,22 @@ mod tests {
                 if i == ELEMENT_REMOVED {
                     (new_sector_index, new_price)
                 } else {
-                    let encoded_price = EncodedPrice::new(
-                        ValidatedPriceMantissa::try_from(10_000_000 + i as u32).unwrap(),
-                        biased_exponent!(0),
-                    );
-                    (i as u32, encoded_price)
+                    (i as u32, price(i as u32))
                 }
             });
 
         for (expected, result) in expected_index_and_encoded_price_pairs
             .iter()
-            .zip(order_sectors.bids.iter())
+            .zip(bids.iter())
         {
             let (expected_sector_index, expected_encoded_price): (&LeSectorIndex, &LeEncodedPrice) =
                 (&expected.0.to_le_bytes(), &expected.1.into());
             assert_eq!(&result.sector_index, expected_sector_index);
             assert_eq!(&result.encoded_price, expected_encoded_price);
         }
+
+        // Relocating an order updates its mapped sector index.
+        bids.replace_sector_index(new_sector_index, 60);
+        assert_eq!(bids.get(&new_price.into()), Some(60));
     }
 }
@@ -12,6 +12,8 @@ use crate::validation::market_account_view::MarketAccountView;
 /// passed in is a valid dropset account.
 #[derive(Clone)]
 pub struct BatchReplaceContext<'a> {
+    // The event authority is validated by the inevitable `FlushEvents` self-CPI.
+    pub event_authority: &'a AccountView,
     pub user: &'a AccountView,
     pub market_account: MarketAccountView<'a>,
 }
@@ -25,7 +27,7 @@ impl<'a> BatchReplaceContext<'a> {
         accounts: &'a [AccountView],
     ) -> Result<BatchReplaceContext<'a>, ProgramError> {
         let BatchReplace {
-            event_authority: _,
+            event_authority,
             user,
             market_account,
             dropset_program: _,
@@ -35,6 +37,7 @@ impl<'a> BatchReplaceContext<'a> {
         let market_account = unsafe { MarketAccountView::new(market_account) }?;
 
         Ok(Self {
+            event_authority,
             user,
             market_account,
         })
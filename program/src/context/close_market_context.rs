@@ -0,0 +1,106 @@
+//! See [`CloseMarketContext`].
+
+use dropset_interface::instructions::generated_program::CloseMarket;
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::validation::{
+    market_account_view::MarketAccountView,
+    mint_account_view::MintAccountView,
+    token_account_view::TokenAccountView,
+};
+
+/// The contextual, validated account infos required to close a market and its token accounts.
+///
+/// Note that the authority is checked against the market header in the instruction handler.
+#[derive(Clone)]
+pub struct CloseMarketContext<'a> {
+    // The event authority is validated by the `FlushEvents` self-CPI.
+    pub event_authority: &'a AccountView,
+    pub authority: &'a AccountView,
+    pub market_account: MarketAccountView<'a>,
+    pub base_authority_ata: TokenAccountView<'a>,
+    pub quote_authority_ata: TokenAccountView<'a>,
+    pub base_market_ata: TokenAccountView<'a>,
+    pub quote_market_ata: TokenAccountView<'a>,
+    pub base_mint: MintAccountView<'a>,
+    pub quote_mint: MintAccountView<'a>,
+}
+
+impl<'a> CloseMarketContext<'a> {
+    /// # Safety
+    ///
+    /// Caller guarantees no accounts passed have their data borrowed in any capacity. This is a
+    /// more restrictive safety contract than is necessary for soundness but is much simpler.
+    pub unsafe fn load(
+        accounts: &'a [AccountView],
+    ) -> Result<CloseMarketContext<'a>, ProgramError> {
+        let CloseMarket {
+            event_authority,
+            authority,
+            market_account,
+            base_authority_ata,
+            quote_authority_ata,
+            base_market_ata,
+            quote_market_ata,
+            base_mint,
+            quote_mint,
+            base_token_program: _,
+            quote_token_program: _,
+            dropset_program: _,
+        } = CloseMarket::load_accounts(accounts)?;
+
+        // Safety: Scoped borrow of market account data.
+        let (market_account, base_mint, quote_mint) = unsafe {
+            let market_account = MarketAccountView::new(market_account)?;
+            let market = market_account.load_unchecked();
+            let (base_mint, quote_mint) =
+                MintAccountView::new_base_and_quote(base_mint, quote_mint, market)?;
+            (market_account, base_mint, quote_mint)
+        };
+
+        // Safety: Scoped borrows of the authority token accounts and market token accounts.
+        let (base_authority_ata, base_market_ata, quote_authority_ata, quote_market_ata) = unsafe {
+            let base_authority_ata = TokenAccountView::new(
+                base_authority_ata,
+                base_mint.account.address(),
+                authority.address(),
+            )?;
+            let base_market_ata = TokenAccountView::new(
+                base_market_ata,
+                base_mint.account.address(),
+                market_account.account().address(),
+            )?;
+            let quote_authority_ata = TokenAccountView::new(
+                quote_authority_ata,
+                quote_mint.account.address(),
+                authority.address(),
+            )?;
+            let quote_market_ata = TokenAccountView::new(
+                quote_market_ata,
+                quote_mint.account.address(),
+                market_account.account().address(),
+            )?;
+            (
+                base_authority_ata,
+                base_market_ata,
+                quote_authority_ata,
+                quote_market_ata,
+            )
+        };
+
+        Ok(Self {
+            event_authority,
+            authority,
+            market_account,
+            base_authority_ata,
+            quote_authority_ata,
+            base_market_ata,
+            quote_market_ata,
+            base_mint,
+            quote_mint,
+        })
+    }
+}
@@ -25,6 +25,10 @@ pub struct DepositContext<'a> {
     pub user_ata: TokenAccountView<'a>,
     pub market_ata: TokenAccountView<'a>,
     pub mint: MintAccountView<'a>,
+    /// The account that funds a market expansion when registering a new seat on a market with no
+    /// free sectors. This is the optional trailing payer account if it's passed, and the user if
+    /// not.
+    pub expansion_payer: &'a AccountView,
 }
 
 impl<'a> DepositContext<'a> {
@@ -33,17 +37,26 @@ impl<'a> DepositContext<'a> {
     /// Caller guarantees no accounts passed have their data borrowed in any capacity. This is a
     /// more restrictive safety contract than is necessary for soundness but is much simpler.
     pub unsafe fn load(accounts: &'a [AccountView]) -> Result<DepositContext<'a>, ProgramError> {
-        let Deposit {
-            event_authority,
-            user,
-            market_account,
-            user_ata,
-            market_ata,
-            mint,
-            token_program: _,
-            system_program: _,
-            dropset_program: _,
-        } = Deposit::load_accounts(accounts)?;
+        let (
+            Deposit {
+                event_authority,
+                user,
+                market_account,
+                user_ata,
+                market_ata,
+                mint,
+                token_program: _,
+                system_program: _,
+                dropset_program: _,
+            },
+            remaining,
+        ) = Deposit::load_accounts_with_remaining(accounts)?;
+
+        let expansion_payer = match remaining {
+            [] => user,
+            [payer] => payer,
+            _ => return Err(ProgramError::InvalidArgument),
+        };
 
         // Safety: Scoped borrow of market account data.
         let (market_account, mint) = unsafe {
@@ -71,6 +84,7 @@ impl<'a> DepositContext<'a> {
             user_ata,
             market_ata,
             mint,
+            expansion_payer,
         })
     }
 }
@@ -0,0 +1,77 @@
+//! See [`DepositForContext`].
+
+use dropset_interface::instructions::generated_program::DepositFor;
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::validation::{
+    market_account_view::MarketAccountView,
+    mint_account_view::MintAccountView,
+    token_account_view::TokenAccountView,
+};
+
+/// The account context for the [`DepositFor`] instruction, verifying the funder's token
+/// ownership, mint consistency, and associated token account correctness.
+///
+/// The beneficiary is passed as instruction data, so it has no account to validate.
+#[derive(Clone)]
+pub struct DepositForContext<'a> {
+    // The event authority is validated by the inevitable `FlushEvents` self-CPI.
+    pub event_authority: &'a AccountView,
+    pub funder: &'a AccountView,
+    pub market_account: MarketAccountView<'a>,
+    pub funder_ata: TokenAccountView<'a>,
+    pub market_ata: TokenAccountView<'a>,
+    pub mint: MintAccountView<'a>,
+}
+
+impl<'a> DepositForContext<'a> {
+    /// # Safety
+    ///
+    /// Caller guarantees no accounts passed have their data borrowed in any capacity. This is a
+    /// more restrictive safety contract than is necessary for soundness but is much simpler.
+    pub unsafe fn load(accounts: &'a [AccountView]) -> Result<DepositForContext<'a>, ProgramError> {
+        let DepositFor {
+            event_authority,
+            funder,
+            market_account,
+            funder_ata,
+            market_ata,
+            mint,
+            token_program: _,
+            system_program: _,
+            dropset_program: _,
+        } = DepositFor::load_accounts(accounts)?;
+
+        // Safety: Scoped borrow of market account data.
+        let (market_account, mint) = unsafe {
+            let market_account = MarketAccountView::new(market_account)?;
+            let market = market_account.load_unchecked();
+            let mint = MintAccountView::new(mint, market)?;
+            (market_account, mint)
+        };
+
+        // Safety: Scoped borrows of the funder token account and market token account.
+        let (funder_ata, market_ata) = unsafe {
+            let funder_ata =
+                TokenAccountView::new(funder_ata, mint.account.address(), funder.address())?;
+            let market_ata = TokenAccountView::new(
+                market_ata,
+                mint.account.address(),
+                market_account.account().address(),
+            )?;
+            (funder_ata, market_ata)
+        };
+
+        Ok(Self {
+            event_authority,
+            funder,
+            market_account,
+            funder_ata,
+            market_ata,
+            mint,
+        })
+    }
+}
@@ -3,15 +3,31 @@
 //! Each context groups and validates the accounts required by its corresponding instruction before
 //! execution.
 
+pub mod amend_order_context;
 pub mod batch_replace_context;
+pub mod cancel_all_orders_context;
+pub mod cancel_order_by_client_id_context;
 pub mod cancel_order_context;
+pub mod close_empty_seats_context;
+pub mod close_market_context;
 pub mod close_seat_context;
+pub mod collect_fees_context;
+pub mod compact_market_context;
+pub mod deposit_both_context;
 pub mod deposit_context;
+pub mod deposit_for_context;
 pub mod expand_market_context;
 pub mod flush_events_context;
 pub mod market_order_context;
 pub mod post_order_context;
+pub mod prune_expired_orders_context;
 pub mod register_market_context;
+pub mod seat_market_order_context;
+pub mod set_market_status_context;
+pub mod set_seat_delegate_context;
+pub mod settle_and_close_seat_context;
+pub mod update_market_config_context;
+pub mod withdraw_both_context;
 pub mod withdraw_context;
 
 /// The account infos necessary to emit events with the event buffer.
@@ -18,6 +18,9 @@ pub struct PostOrderContext<'a> {
     pub event_authority: &'a AccountView,
     pub user: &'a AccountView,
     pub market_account: MarketAccountView<'a>,
+    /// The optional trailing payer that funds a market expansion if the market has no free
+    /// sectors. The system program must be passed after it so the payer can transfer the rent.
+    pub expansion_payer: Option<&'a AccountView>,
 }
 
 impl<'a> PostOrderContext<'a> {
@@ -26,12 +29,22 @@ impl<'a> PostOrderContext<'a> {
     /// Caller guarantees no accounts passed have their data borrowed in any capacity. This is a
     /// more restrictive safety contract than is necessary for soundness but is much simpler.
     pub unsafe fn load(accounts: &'a [AccountView]) -> Result<PostOrderContext<'a>, ProgramError> {
-        let PostOrder {
-            event_authority,
-            user,
-            market_account,
-            dropset_program: _,
-        } = PostOrder::load_accounts(accounts)?;
+        let (
+            PostOrder {
+                event_authority,
+                user,
+                market_account,
+                dropset_program: _,
+            },
+            remaining,
+        ) = PostOrder::load_accounts_with_remaining(accounts)?;
+
+        let expansion_payer = match remaining {
+            [] => None,
+            [payer, _system_program] => Some(payer),
+            [_payer] => return Err(ProgramError::NotEnoughAccountKeys),
+            _ => return Err(ProgramError::InvalidArgument),
+        };
 
         // Safety: Scoped borrow of market account data.
         let market_account = unsafe { MarketAccountView::new(market_account) }?;
@@ -40,6 +53,7 @@ impl<'a> PostOrderContext<'a> {
             event_authority,
             user,
             market_account,
+            expansion_payer,
         })
     }
 }
@@ -0,0 +1,47 @@
+//! See [`SetMarketStatusContext`].
+
+use dropset_interface::instructions::generated_program::SetMarketStatus;
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::validation::market_account_view::MarketAccountView;
+
+/// The account context for the [SetMarketStatus] instruction. It validates the market account
+/// passed in is a valid dropset account.
+///
+/// Note that the authority is checked against the market header in the instruction handler.
+#[derive(Clone)]
+pub struct SetMarketStatusContext<'a> {
+    // The event authority is validated by the inevitable `FlushEvents` self-CPI.
+    pub event_authority: &'a AccountView,
+    pub authority: &'a AccountView,
+    pub market_account: MarketAccountView<'a>,
+}
+
+impl<'a> SetMarketStatusContext<'a> {
+    /// # Safety
+    ///
+    /// Caller guarantees no accounts passed have their data borrowed in any capacity. This is a
+    /// more restrictive safety contract than is necessary for soundness but is much simpler.
+    pub unsafe fn load(
+        accounts: &'a [AccountView],
+    ) -> Result<SetMarketStatusContext<'a>, ProgramError> {
+        let SetMarketStatus {
+            event_authority,
+            authority,
+            market_account,
+            dropset_program: _,
+        } = SetMarketStatus::load_accounts(accounts)?;
+
+        // Safety: Scoped borrow of market account data.
+        let market_account = unsafe { MarketAccountView::new(market_account) }?;
+
+        Ok(Self {
+            event_authority,
+            authority,
+            market_account,
+        })
+    }
+}
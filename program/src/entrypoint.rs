@@ -9,6 +9,7 @@ use dropset_interface::{
 };
 use pinocchio::{
     account::AccountView,
+    cpi::set_return_data,
     no_allocator,
     nostd_panic_handler,
     program_entrypoint,
@@ -71,11 +72,59 @@ pub fn process_instruction(
                 return process_flush_events(accounts, instruction_data)
             }
             DropsetInstruction::BatchReplace => {
-                return process_batch_replace(accounts, instruction_data)
+                process_batch_replace(accounts, instruction_data, event_buffer)
             }
             DropsetInstruction::ExpandMarket => {
                 process_expand_market(accounts, instruction_data, event_buffer)
             }
+            DropsetInstruction::CollectFees => {
+                process_collect_fees(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::UpdateMarketConfig => {
+                process_update_market_config(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::SetMarketStatus => {
+                process_set_market_status(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::CloseMarket => {
+                return process_close_market(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::CancelAllOrders => {
+                process_cancel_all_orders(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::PruneExpiredOrders => {
+                process_prune_expired_orders(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::CancelOrderByClientId => {
+                process_cancel_order_by_client_id(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::AmendOrder => {
+                process_amend_order(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::CloseEmptySeats => {
+                process_close_empty_seats(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::SettleAndCloseSeat => {
+                process_settle_and_close_seat(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::CompactMarket => {
+                process_compact_market(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::SeatMarketOrder => {
+                process_seat_market_order(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::SetSeatDelegate => {
+                process_set_seat_delegate(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::DepositBoth => {
+                process_deposit_both(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::WithdrawBoth => {
+                process_withdraw_both(accounts, instruction_data, event_buffer)
+            }
+            DropsetInstruction::DepositFor => {
+                process_deposit_for(accounts, instruction_data, event_buffer)
+            }
         }
     }?;
 
@@ -85,5 +134,11 @@ pub fn process_instruction(
     } = event_buffer_context;
 
     // Safety: The `market_account` is not currently borrowed in any capacity.
-    unsafe { event_buffer.flush_events(event_authority, market_account) }
+    unsafe { event_buffer.flush_events(event_authority, market_account) }?;
+
+    if let Some(taker_return_data) = &event_buffer.taker_return_data {
+        set_return_data(&taker_return_data.pack());
+    }
+
+    Ok(())
 }
@@ -9,6 +9,7 @@ use dropset_interface::{
     },
     instructions::DropsetInstruction,
     program,
+    return_data::TakerReturnData,
     seeds::event_authority,
 };
 use instruction_macros_traits::Tagged;
@@ -63,6 +64,9 @@ pub struct EventBuffer {
     len: usize,
     /// The instruction tag for the instruction responsible for the `dropset` program's invocation.
     pub instruction_tag: DropsetInstruction,
+    /// The return data set by taker instructions. It's set after the final flush, since the flush
+    /// is itself a CPI.
+    pub taker_return_data: Option<TakerReturnData>,
 }
 
 /// The header data begins after the initial invoking instruction tag byte.
@@ -84,6 +88,7 @@ impl EventBuffer {
             // The length after writing to the first byte + all the header data bytes.
             len: HEADER_SIZE_WITH_TAGS,
             instruction_tag,
+            taker_return_data: None,
         };
 
         // Manually pack the instruction tag for the CPI invocation.
@@ -0,0 +1,243 @@
+//! See [`process_amend_order`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::AmendOrderEventInstructionData,
+    instructions::{
+        AmendOrderInstructionData,
+        ExpiryKind,
+    },
+    state::{
+        asks_dll::AskOrders,
+        bids_dll::BidOrders,
+        market::MarketRefMut,
+        order::{
+            Order,
+            OrdersCollection,
+        },
+        sector::SectorIndex,
+    },
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{
+        clock::Clock,
+        Sysvar,
+    },
+    Address,
+};
+use price::{
+    to_order_info,
+    OrderInfo,
+};
+
+use crate::{
+    context::{
+        amend_order_context::AmendOrderContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::{
+        order_operations::{
+            insert_order,
+            load_mut_order_from_sector_index,
+            load_order_from_sector_index,
+        },
+        seat_operations::{
+            load_mut_seat_with_hint_unchecked,
+            load_seat_user_for_signer,
+            load_seat_with_hint,
+        },
+    },
+};
+
+/// Instruction handler logic for amending the price and/or size of one of a user's resting orders.
+///
+/// If the amended order has the same price and no more base atoms than the order has remaining,
+/// the order is reduced in place and keeps its time priority at its price level. Otherwise, the
+/// order is canceled and reposted at the back of its new price level as a post-only order, keeping
+/// its expiry and client order id. Either way, the difference in collateral is settled against the
+/// user's seat and a single event is emitted.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::AmendOrder`].
+#[inline(never)]
+pub unsafe fn process_amend_order<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let AmendOrderInstructionData {
+        encoded_price,
+        is_bid,
+        user_sector_index_hint,
+        order_info_args,
+    } = AmendOrderInstructionData::unpack_untagged(instruction_data)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { AmendOrderContext::load(accounts) }?;
+
+    let order_info = to_order_info(order_info_args).map_err(DropsetError::from)?;
+
+    let AmendedOrder {
+        order_sector_index,
+        kept_priority,
+        client_order_id,
+    } = {
+        // Safety: The market account is currently not borrowed in any capacity.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        market.header.status()?.check_allows_cancels()?;
+        market
+            .header
+            .check_order_against_config(&order_info.encoded_price, order_info.base_atoms)?;
+
+        // The signer may be the seat's user or its delegate, but the orders always belong to the
+        // user.
+        let user = load_seat_user_for_signer(&market, user_sector_index_hint, ctx.user)?;
+
+        // Safety: The user sector index hint was just verified in-bounds.
+        unsafe {
+            if is_bid {
+                amend_order::<BidOrders>(
+                    &mut market,
+                    &user,
+                    user_sector_index_hint,
+                    encoded_price,
+                    &order_info,
+                )
+            } else {
+                amend_order::<AskOrders>(
+                    &mut market,
+                    &user,
+                    user_sector_index_hint,
+                    encoded_price,
+                    &order_info,
+                )
+            }
+        }?
+    };
+
+    event_buffer.add_to_buffer(
+        AmendOrderEventInstructionData::new(
+            is_bid,
+            user_sector_index_hint,
+            order_sector_index,
+            encoded_price,
+            order_info.encoded_price.as_u32(),
+            order_info.base_atoms,
+            order_info.quote_atoms,
+            kept_priority,
+            client_order_id,
+        ),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
+
+/// The result of amending an order.
+struct AmendedOrder {
+    /// The amended order's sector index.
+    order_sector_index: SectorIndex,
+    /// Whether or not the order was reduced in place and kept its time priority.
+    kept_priority: bool,
+    /// The order's client order id.
+    client_order_id: u64,
+}
+
+/// Amends the user's `Side` order with the passed encoded price to the new order info, then
+/// settles the difference between the old and new order's collateral against the user's seat.
+///
+/// # Safety
+///
+/// Caller guarantees `user_sector_index_hint` is in-bounds of `market.sectors`.
+#[inline(always)]
+unsafe fn amend_order<Side: OrdersCollection>(
+    market: &mut MarketRefMut,
+    user: &Address,
+    user_sector_index_hint: SectorIndex,
+    encoded_price: u32,
+    new_order_info: &OrderInfo,
+) -> Result<AmendedOrder, ProgramError> {
+    // Find the sector index of the user's order at the passed price.
+    let old_sector_index = {
+        // Safety: Caller guarantees the user sector index hint is in-bounds.
+        unsafe { load_seat_with_hint(market, user_sector_index_hint, user) }?;
+        // Safety: The user's seat was just verified at the index hint.
+        unsafe { market.seat_order_sectors::<Side>(user_sector_index_hint) }
+            .iter()
+            .find(|entry| entry.encoded_price.as_array() == encoded_price.to_le_bytes())
+            .map(|entry| SectorIndex::from_le_bytes(entry.sector_index))
+            .ok_or(DropsetError::OrderNotFound)?
+    };
+
+    // Safety: All order sector indices in a user seat are in-bounds.
+    let old_order = unsafe { load_order_from_sector_index(market, old_sector_index) };
+    let old_collateral = old_order.collateral_amount::<Side>();
+    let expiry = old_order.expiry();
+    let client_order_id = old_order.client_order_id();
+    let kept_priority = new_order_info.encoded_price.as_u32() == encoded_price
+        && new_order_info.base_atoms <= old_order.base_remaining();
+
+    let (order_sector_index, new_collateral) = if kept_priority {
+        // Reduce the order in place so it keeps its position in the price level's queue.
+        // Safety: All order sector indices in a user seat are in-bounds.
+        let order = unsafe { load_mut_order_from_sector_index(market, old_sector_index) };
+        order.set_base_remaining(new_order_info.base_atoms);
+        order.set_quote_remaining(new_order_info.quote_atoms);
+        (old_sector_index, order.collateral_amount::<Side>())
+    } else {
+        // Repricing or increasing an order's size posts a new order, so it's subject to the same
+        // market status and expiry checks as a newly posted order.
+        market.header.status()?.check_allows_new_orders()?;
+        let clock = Clock::get()?;
+        if expiry.kind != ExpiryKind::None as u8 {
+            expiry.validate(clock.slot, clock.unix_timestamp)?;
+        }
+
+        // Remove the old order before checking the new one so it can't cross the user's own order.
+        market.orders::<Side>().remove_at(old_sector_index);
+
+        let mut new_order =
+            Order::new_with_expiry(new_order_info.clone(), user_sector_index_hint, expiry);
+        new_order.set_client_order_id(client_order_id);
+        let new_collateral = new_order.collateral_amount::<Side>();
+        Side::post_only_crossing_check(&new_order, market, clock.slot, clock.unix_timestamp)?;
+        let next_index =
+            Side::find_new_order_next_index(market.orders::<Side>().iter(), &new_order);
+        let new_sector_index = insert_order(next_index, &mut market.orders::<Side>(), new_order)?;
+
+        // Safety: The seat hint was already validated as the user's seat. It could only possibly be
+        // out of bounds now if the account data size was just reduced, which it was not.
+        let mut order_sectors =
+            unsafe { market.seat_order_sectors::<Side>(user_sector_index_hint) };
+        order_sectors.find_remove(encoded_price)?;
+        order_sectors.add(
+            &new_order_info.encoded_price.into(),
+            &new_sector_index.to_le_bytes(),
+        )?;
+
+        (new_sector_index, new_collateral)
+    };
+
+    // Return the old order's collateral before taking the new order's, since the new order's
+    // collateral is often covered by the old order's.
+    // Safety: The seat hint was already validated as in-bounds. It could only possibly be out of
+    // bounds now if the account data size was just reduced, which it was not.
+    let user_seat = unsafe { load_mut_seat_with_hint_unchecked(market, user_sector_index_hint) };
+    user_seat.try_increment_collateral_available::<Side>(old_collateral)?;
+    user_seat.try_decrement_collateral_available::<Side>(new_collateral)?;
+
+    Ok(AmendedOrder {
+        order_sector_index,
+        kept_priority,
+        client_order_id,
+    })
+}
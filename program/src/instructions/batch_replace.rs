@@ -1,12 +1,16 @@
 //! See [`process_batch_replace`].
 
 use dropset_interface::{
-    error::{
-        DropsetError,
-        DropsetResult,
+    error::DropsetError,
+    events::{
+        AmendOrderEventInstructionData,
+        BatchReplaceEventInstructionData,
+        CancelOrderEventInstructionData,
+        PostOrderEventInstructionData,
     },
     instructions::{
         BatchReplaceInstructionData,
+        BatchReplaceMode,
         UnvalidatedOrders,
     },
     state::{
@@ -18,55 +22,111 @@ use dropset_interface::{
             OrdersCollection,
         },
         sector::{
-            Sector,
             SectorIndex,
             NIL,
         },
-        user_order_sectors::{
-            PriceToIndexEntry,
-            MAX_ORDERS_USIZE,
-        },
+        user_order_sectors::MAX_ORDERS_USIZE,
     },
 };
+use instruction_macros_traits::Pack;
 use pinocchio::{
     account::AccountView,
+    error::ProgramError,
+    sysvars::{
+        clock::Clock,
+        Sysvar,
+    },
     Address,
     ProgramResult,
 };
+use price::LeEncodedPrice;
 
 use crate::{
-    context::batch_replace_context::BatchReplaceContext,
+    context::{
+        batch_replace_context::BatchReplaceContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
     shared::{
         order_operations::{
+            cancel_all_orders_on_side,
             insert_order,
+            load_mut_order_from_sector_index,
             load_order_from_sector_index,
         },
         seat_operations::{
-            load_mut_seat_with_hint,
             load_mut_seat_with_hint_unchecked,
+            load_seat_user_for_signer,
             load_seat_with_hint,
         },
     },
+    validation::market_account_view::MarketAccountView,
 };
 
+/// The orders a batch replace changed on one side of the book, recorded so their events can be
+/// emitted once the market account data is no longer borrowed.
+struct SideChanges {
+    /// The canceled orders' client order ids. Only the first `num_canceled` are meaningful.
+    canceled_client_order_ids: [u64; MAX_ORDERS_USIZE],
+    num_canceled: u8,
+    /// The sector index of each new order, by its index in the new orders.
+    new_order_sector_indices: [SectorIndex; MAX_ORDERS_USIZE],
+    /// Whether or not each new order, by its index in the new orders, reduced one of the user's
+    /// orders in place instead of being posted.
+    kept_new: [bool; MAX_ORDERS_USIZE],
+    num_posted: u8,
+}
+
+impl SideChanges {
+    #[inline(always)]
+    const fn new() -> Self {
+        Self {
+            canceled_client_order_ids: [0; MAX_ORDERS_USIZE],
+            num_canceled: 0,
+            new_order_sector_indices: [NIL; MAX_ORDERS_USIZE],
+            kept_new: [false; MAX_ORDERS_USIZE],
+            num_posted: 0,
+        }
+    }
+}
+
 /// Handler logic for batching multiple cancel + place order instructions in a single atomic
 /// instruction.
 ///
+/// In [`BatchReplaceMode::Replace`] mode, all of the user's orders are canceled before the new
+/// orders are posted. In [`BatchReplaceMode::Incremental`] mode, only the user's orders that differ
+/// from the new orders are changed, so unchanged orders keep their time priority.
+///
+/// Each canceled order emits a `CancelOrderEvent`, each posted order a `PostOrderEvent` and each
+/// order reduced in place an `AmendOrderEvent`, so every order can be matched to its client order
+/// id, followed by a single `BatchReplaceEvent` summarizing the batch.
+///
 /// # Safety
 ///
 /// Since the accounts borrowed depend on the inner batch instructions, the most straightforward
 /// safety contract is simply ensuring that **no Solana account data is currently borrowed** prior
 /// to calling this instruction.
 #[inline(never)]
-pub unsafe fn process_batch_replace(
-    accounts: &[AccountView],
+pub unsafe fn process_batch_replace<'a>(
+    accounts: &'a [AccountView],
     instruction_data: &[u8],
-) -> ProgramResult {
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
     let BatchReplaceInstructionData {
         user_sector_index_hint,
-        new_bids,
-        new_asks,
+        mode,
     } = BatchReplaceInstructionData::unpack_untagged(instruction_data)?;
+    let mode = BatchReplaceMode::try_from(mode)?;
+
+    // The new bids and asks follow the fixed size instruction args as length-prefixed lists.
+    let order_lists = instruction_data
+        .get(BatchReplaceInstructionData::LEN..)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let (new_bids, order_lists) = UnvalidatedOrders::unpack_length_prefixed(order_lists)?;
+    let (new_asks, remaining) = UnvalidatedOrders::unpack_length_prefixed(order_lists)?;
+    if !remaining.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
 
     // Safety: No account data in `accounts` is currently borrowed.
     let mut ctx = unsafe { BatchReplaceContext::load(accounts) }?;
@@ -74,85 +134,190 @@ pub unsafe fn process_batch_replace(
     // Safety: Market account data isn't currently borrowed in any capacity.
     let mut market: MarketRefMut = unsafe { ctx.market_account.load_unchecked_mut() };
 
-    Sector::check_in_bounds(market.sectors, user_sector_index_hint)?;
+    // Canceling is allowed in more market statuses than posting, so a batch that only cancels is
+    // checked separately from one that also posts new orders below.
+    let status = market.header.status()?;
+    status.check_allows_cancels()?;
+
+    // The signer may be the seat's user or its delegate, but the orders always belong to the user.
+    let user = load_seat_user_for_signer(&market, user_sector_index_hint, ctx.user)?;
+
+    // The new orders are checked against the opposite side of the book, where expired orders
+    // don't count as crossing.
+    let clock = Clock::get()?;
 
     // Safety: The user sector index hint was verified as in-bounds.
-    unsafe {
-        remove_orders_from_market_and_update_seat_balance::<BidOrders>(
-            &mut market,
-            ctx.user.address(),
-            user_sector_index_hint,
-        )?;
+    let (bids, asks) = unsafe {
+        match mode {
+            BatchReplaceMode::Replace => {
+                let mut bids = SideChanges::new();
+                let mut asks = SideChanges::new();
+                (bids.canceled_client_order_ids, bids.num_canceled) =
+                    cancel_all_orders_on_side::<BidOrders>(
+                        &mut market,
+                        &user,
+                        user_sector_index_hint,
+                    )?;
+                (asks.canceled_client_order_ids, asks.num_canceled) =
+                    cancel_all_orders_on_side::<AskOrders>(
+                        &mut market,
+                        &user,
+                        user_sector_index_hint,
+                    )?;
 
-        remove_orders_from_market_and_update_seat_balance::<AskOrders>(
-            &mut market,
-            ctx.user.address(),
-            user_sector_index_hint,
-        )?;
+                bids.num_posted = add_new_orders_and_update_seat_balance::<BidOrders>(
+                    &mut market,
+                    user_sector_index_hint,
+                    new_bids,
+                    &mut bids.new_order_sector_indices,
+                    &clock,
+                )?;
+                asks.num_posted = add_new_orders_and_update_seat_balance::<AskOrders>(
+                    &mut market,
+                    user_sector_index_hint,
+                    new_asks,
+                    &mut asks.new_order_sector_indices,
+                    &clock,
+                )?;
 
-        add_new_orders_and_update_seat_balance::<BidOrders>(
-            &mut market,
-            user_sector_index_hint,
-            new_bids,
-        )?;
+                (bids, asks)
+            }
+            BatchReplaceMode::Incremental => {
+                let bids = replace_orders_incrementally::<BidOrders>(
+                    &mut market,
+                    &user,
+                    user_sector_index_hint,
+                    new_bids,
+                    &clock,
+                )?;
 
-        add_new_orders_and_update_seat_balance::<AskOrders>(
-            &mut market,
+                let asks = replace_orders_incrementally::<AskOrders>(
+                    &mut market,
+                    &user,
+                    user_sector_index_hint,
+                    new_asks,
+                    &clock,
+                )?;
+
+                (bids, asks)
+            }
+        }
+    };
+
+    if bids.num_posted != 0 || asks.num_posted != 0 {
+        status.check_allows_new_orders()?;
+    }
+
+    // The market account data must not be used past this point, since adding to the event buffer
+    // may flush it.
+    for (is_bid, changes, new_orders) in [(true, &bids, new_bids), (false, &asks, new_asks)] {
+        emit_side_events(
+            event_buffer,
+            ctx.event_authority,
+            &ctx.market_account,
+            is_bid,
             user_sector_index_hint,
-            new_asks,
+            changes,
+            new_orders,
         )?;
     }
 
-    Ok(())
+    event_buffer.add_to_buffer(
+        BatchReplaceEventInstructionData::new(
+            user_sector_index_hint,
+            bids.num_canceled,
+            asks.num_canceled,
+            bids.num_posted,
+            asks.num_posted,
+        ),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
 }
 
-/// Removes a user's orders from the market orders collection and update the seat balance to reflect
-/// the collateral returned from closing those orders.
-///
-/// Note this does *not* remove the entries from the user seat's price -> order sectors mapping.
-///
-/// # Safety
+/// Emits the events for the orders a batch replace changed on one side of the book.
 ///
-/// Caller guarantees the user seat index passed is a non-NIL, valid, and in-bounds sector index.
+/// The new orders were all validated while processing the batch, so they're only converted again
+/// here to get each order's base and quote atoms.
 #[inline(always)]
-unsafe fn remove_orders_from_market_and_update_seat_balance<Side: OrdersCollection>(
-    market: &mut MarketRefMut,
-    user_address: &Address,
-    valid_user_seat_index: SectorIndex,
-) -> DropsetResult {
-    // Find and verify the user's seat with the given index hint.
-    // Safety: The index hint was just verified as in-bounds.
-    let user_seat = load_seat_with_hint(market, valid_user_seat_index, user_address)?;
-    let order_sectors = user_seat.user_order_sectors.order_sectors::<Side>();
-
-    let mut collateral_returned: u64 = 0;
-
-    for idx in order_sectors.to_sector_indices() {
-        if idx != NIL {
-            // Safety: Caller upholds the safety contract.
-            let collateral_remaining =
-                unsafe { load_order_from_sector_index(market, idx).collateral_amount::<Side>() };
-            collateral_returned = collateral_returned
-                .checked_add(collateral_remaining)
-                .ok_or(DropsetError::ArithmeticOverflow)?;
-            market.orders::<Side>().remove_at(idx);
-        }
+fn emit_side_events<'a>(
+    event_buffer: &mut EventBuffer,
+    event_authority: &'a AccountView,
+    market_account: &MarketAccountView<'a>,
+    is_bid: bool,
+    user_sector_index_hint: SectorIndex,
+    changes: &SideChanges,
+    new_orders: UnvalidatedOrders,
+) -> ProgramResult {
+    for client_order_id in &changes.canceled_client_order_ids[..changes.num_canceled as usize] {
+        event_buffer.add_to_buffer(
+            CancelOrderEventInstructionData::new(is_bid, user_sector_index_hint, *client_order_id),
+            event_authority,
+            market_account.clone(),
+        )?;
     }
 
+    for ((order, order_sector_index), kept) in new_orders
+        .into_valid_orders_iter()
+        .zip(changes.new_order_sector_indices)
+        .zip(changes.kept_new)
     {
-        let mut_user_seat = load_mut_seat_with_hint(market, valid_user_seat_index, user_address)?;
-        mut_user_seat.try_increment_collateral_available::<Side>(collateral_returned)?;
+        let (order_info, client_order_id) = order?;
+        if kept {
+            let encoded_price = order_info.encoded_price.as_u32();
+            event_buffer.add_to_buffer(
+                AmendOrderEventInstructionData::new(
+                    is_bid,
+                    user_sector_index_hint,
+                    order_sector_index,
+                    encoded_price,
+                    encoded_price,
+                    order_info.base_atoms,
+                    order_info.quote_atoms,
+                    true,
+                    client_order_id,
+                ),
+                event_authority,
+                market_account.clone(),
+            )?;
+        } else {
+            event_buffer.add_to_buffer(
+                PostOrderEventInstructionData::new(
+                    is_bid,
+                    user_sector_index_hint,
+                    order_sector_index,
+                    order_info.base_atoms,
+                    order_info.quote_atoms,
+                    0,
+                    0,
+                    0,
+                    0,
+                    client_order_id,
+                ),
+                event_authority,
+                market_account.clone(),
+            )?;
+        }
     }
 
     Ok(())
 }
 
-/// First adds the passed orders to the appropriate market orders collection and user seat's price
-/// -> order sectors mapping, skipping unnecessary checks and freeing unused entries in the order
-/// sectors mapping where appropriate.
+/// First clears the user seat's price -> order sectors mapping, then adds the passed orders to the
+/// appropriate market orders collection and the mapping.
+///
+/// The user's orders must already be removed from the market orders collection.
 ///
 /// Then updates the user's seat balance to reflect the collateral necessary to post those orders.
 ///
+/// Records each posted order's sector index in `posted_sector_indices` by its index in `orders`,
+/// and returns the number of orders posted.
+///
 /// # Safety
 ///
 /// Caller guarantees the user seat index passed is a non-NIL, valid, and in-bounds sector index.
@@ -161,7 +326,9 @@ unsafe fn add_new_orders_and_update_seat_balance<Side: OrdersCollection>(
     market: &mut MarketRefMut,
     valid_user_seat_index: SectorIndex,
     orders: UnvalidatedOrders,
-) -> DropsetResult {
+    posted_sector_indices: &mut [SectorIndex; MAX_ORDERS_USIZE],
+    clock: &Clock,
+) -> Result<u8, DropsetError> {
     let mut prev_price = Side::HIGHEST_PRIORITY_PRICE;
     let mut first_order = None;
 
@@ -170,9 +337,26 @@ unsafe fn add_new_orders_and_update_seat_balance<Side: OrdersCollection>(
 
     let mut collateral_in_posted_orders: u64 = 0;
 
+    let max_orders_per_user = market.header.max_orders_per_user() as usize;
+
+    // Free the seat's mapped entries, along with its overflow sector, so the new orders can use
+    // them.
+    // Safety: Caller guarantees the seat index is valid.
+    unsafe { market.seat_order_sectors::<Side>(valid_user_seat_index) }.clear();
+
     let mut i = 0;
-    for order_info in orders.into_valid_order_infos_iter() {
-        let order = Order::new(order_info, valid_user_seat_index);
+    for order in orders.into_valid_orders_iter() {
+        // The market's max orders per user is never greater than `MAX_ORDERS`.
+        if i >= max_orders_per_user {
+            return Err(DropsetError::UserHasMaxOrders);
+        }
+        let (order_info, client_order_id) = order?;
+        market
+            .header
+            .check_order_against_config(&order_info.encoded_price, order_info.base_atoms)?;
+
+        let mut order = Order::new(order_info, valid_user_seat_index);
+        order.set_client_order_id(client_order_id);
         let order_price = order.encoded_price();
         if i == 0 {
             // Clone the first order so it can be validated as a post-only order later.
@@ -202,6 +386,9 @@ unsafe fn add_new_orders_and_update_seat_balance<Side: OrdersCollection>(
         let next_index =
             Side::find_new_order_next_index(unsafe { list.iter_from(iter_sector_index) }, &order);
         let insertion_index = insert_order(next_index, list, order)?;
+        // Safety: `i` was checked against the market's max orders per user above, which is never
+        // greater than `MAX_ORDERS_USIZE`.
+        *posted_sector_indices.get_unchecked_mut(i) = insertion_index;
 
         // Since orders are sorted in descending price priority, it's much more efficient to start
         // each search from the current index rather than from the head.
@@ -209,43 +396,18 @@ unsafe fn add_new_orders_and_update_seat_balance<Side: OrdersCollection>(
         // the newly inserted index.
         iter_sector_index = insertion_index;
 
-        // Add the order to the user's order sectors mapping with an unchecked add operation where
-        // the order isn't checked for duplication within the order sectors mapping.
-        {
-            // Safety: The seat hint was already validated as in-bounds. It could only possibly be
-            // out of bounds now if the account data size was just reduced, which it was
-            // not.
-            let user_seat = load_mut_seat_with_hint_unchecked(market, valid_user_seat_index);
-            // Safety: The duplication check isn't necessary below because the orders are guaranteed
-            // to be strictly increasing or decreasing (through strictly decreasing price priority).
-            // Therefore, there cannot be price duplicates and it's safe to directly mutate each
-            // entry without going through the typical `.add(...)` API.
-            unsafe {
-                let entry_at_index_i = user_seat
-                    .user_order_sectors
-                    .order_sectors_mut::<Side>()
-                    .get_mut_entry_at(i);
-
-                *entry_at_index_i = PriceToIndexEntry {
-                    encoded_price: order_price.into(),
-                    sector_index: insertion_index.to_le_bytes(),
-                };
-            };
-        }
+        // Add the order to the user's order sectors mapping. The orders are strictly sorted by
+        // price priority, so this never fails on a duplicate price.
+        // Safety: The seat hint was already validated as the user's seat. It could only possibly be
+        // out of bounds now if the account data size was just reduced, which it was not.
+        unsafe { market.seat_order_sectors::<Side>(valid_user_seat_index) }
+            .add(&order_price.into(), &insertion_index.to_le_bytes())?;
 
         i += 1;
     }
 
-    // Free the remaining price to order index entries in the user's seat.
+    // Safety: The seat hint was already validated as in-bounds.
     let user_seat = load_mut_seat_with_hint_unchecked(market, valid_user_seat_index);
-    // Safety: `i` is the 0-based enumerated index over the valid order infos iterator. The number
-    // of valid order infos is always <= `MAX_ORDERS_USIZE`, which means that `i`, after the
-    // last increment is exactly the count of valid order infos. Thus `i <= MAX_ORDERS_USIZE`
-    // and is exactly the index to begin removing mapped entries from.
-    user_seat
-        .user_order_sectors
-        .order_sectors_mut::<Side>()
-        .remove_range(i..MAX_ORDERS_USIZE);
 
     // Since these balance updates are reduced to a single operation, it's possible overflow or
     // underflow occurs where it normally wouldn't if the seat were updated for each order.
@@ -255,8 +417,189 @@ unsafe fn add_new_orders_and_update_seat_balance<Side: OrdersCollection>(
     user_seat.try_decrement_collateral_available::<Side>(collateral_in_posted_orders)?;
 
     if let Some(first) = first_order {
-        Side::post_only_crossing_check(&first, market)?;
+        Side::post_only_crossing_check(&first, market, clock.slot, clock.unix_timestamp)?;
     }
 
-    Ok(())
+    // `i` is <= `MAX_ORDERS_USIZE`, which always fits in a `u8`.
+    Ok(i as u8)
+}
+
+/// Replaces the user's `Side` orders with the passed orders, only changing the orders that differ:
+///
+/// 1. Validates the new orders and reduces the user's orders with the same price as a new order and
+///    at least as many base atoms remaining in place, keeping their time priority. Orders with the
+///    same price and size are left as is, apart from taking the new order's client order id.
+/// 2. Cancels the rest of the user's orders.
+/// 3. Posts the new orders that didn't match one of the user's orders in step 1.
+///
+/// Then updates the user's seat balance to reflect the collateral returned and posted.
+///
+/// Returns the orders canceled, posted and reduced in place. Orders reduced in place count as
+/// neither canceled nor posted.
+///
+/// # Safety
+///
+/// Caller guarantees the user seat index passed is a non-NIL, valid, and in-bounds sector index.
+#[inline(always)]
+unsafe fn replace_orders_incrementally<Side: OrdersCollection>(
+    market: &mut MarketRefMut,
+    user_address: &Address,
+    valid_user_seat_index: SectorIndex,
+    orders: UnvalidatedOrders,
+    clock: &Clock,
+) -> Result<SideChanges, DropsetError> {
+    // Find and verify the user's seat with the given index hint, then copy its current orders so
+    // the market can be mutated while iterating over them.
+    load_seat_with_hint(market, valid_user_seat_index, user_address)?;
+    // Safety: The user's seat was just verified at the seat index.
+    let current_orders =
+        unsafe { market.seat_order_sectors::<Side>(valid_user_seat_index) }.to_entries();
+
+    let max_orders_per_user = market.header.max_orders_per_user() as usize;
+    let mut prev_price = Side::HIGHEST_PRIORITY_PRICE;
+
+    // Whether or not each current order, by its index in `current_orders`, is kept.
+    let mut kept_current = [false; MAX_ORDERS_USIZE];
+    // The orders canceled, posted and reduced in place, recorded for their events.
+    let mut changes = SideChanges::new();
+
+    let mut collateral_returned: u64 = 0;
+    let mut collateral_in_posted_orders: u64 = 0;
+
+    // 1. Validate the new orders and reduce the matching current orders in place.
+    for (i, order) in orders.into_valid_orders_iter().enumerate() {
+        // The market's max orders per user is never greater than `MAX_ORDERS`, so this also
+        // guarantees `i < MAX_ORDERS_USIZE` below.
+        if i >= max_orders_per_user {
+            return Err(DropsetError::UserHasMaxOrders);
+        }
+        let (order_info, client_order_id) = order?;
+        market
+            .header
+            .check_order_against_config(&order_info.encoded_price, order_info.base_atoms)?;
+
+        // Orders should be sorted in strictly descending price priority. There's no `prev_price`
+        // to meaningfully compare to for the first order, so skip that check.
+        if i != 0 && !Side::has_higher_price_priority(&prev_price, &order_info.encoded_price) {
+            return Err(DropsetError::OrdersNotSorted);
+        }
+        prev_price = order_info.encoded_price;
+
+        let le_encoded_price: LeEncodedPrice = order_info.encoded_price.into();
+        let Some((current_index, sector_index)) = current_orders
+            .iter()
+            .enumerate()
+            .find(|(_, entry)| {
+                !entry.is_free() && entry.encoded_price.as_slice() == le_encoded_price.as_slice()
+            })
+            .map(|(j, entry)| (j, SectorIndex::from_le_bytes(entry.sector_index)))
+        else {
+            continue;
+        };
+
+        // Safety: All order sector indices in a user seat are in-bounds.
+        let order = load_mut_order_from_sector_index(market, sector_index);
+        // Increasing an order's size would unfairly keep its time priority, so it's canceled and
+        // reposted instead.
+        if order_info.base_atoms > order.base_remaining() {
+            continue;
+        }
+
+        collateral_returned = collateral_returned
+            .checked_add(order.collateral_amount::<Side>())
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+        order.set_base_remaining(order_info.base_atoms);
+        order.set_quote_remaining(order_info.quote_atoms);
+        order.set_client_order_id(client_order_id);
+        collateral_in_posted_orders = collateral_in_posted_orders
+            .checked_add(order.collateral_amount::<Side>())
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+
+        // Safety: `current_index` is an index into `current_orders`, and `i < MAX_ORDERS_USIZE`.
+        *kept_current.get_unchecked_mut(current_index) = true;
+        *changes.kept_new.get_unchecked_mut(i) = true;
+        *changes.new_order_sector_indices.get_unchecked_mut(i) = sector_index;
+    }
+
+    // 2. Cancel the current orders that weren't kept.
+    for (entry, kept) in current_orders.iter().zip(kept_current) {
+        if entry.is_free() || kept {
+            continue;
+        }
+        let sector_index = SectorIndex::from_le_bytes(entry.sector_index);
+        // Safety: All order sector indices in a user seat are in-bounds.
+        let order = load_order_from_sector_index(market, sector_index);
+        collateral_returned = collateral_returned
+            .checked_add(order.collateral_amount::<Side>())
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+        // Safety: At most `MAX_ORDERS_USIZE` current orders are canceled.
+        *changes
+            .canceled_client_order_ids
+            .get_unchecked_mut(changes.num_canceled as usize) = order.client_order_id();
+        market.orders::<Side>().remove_at(sector_index);
+
+        // Safety: The seat hint was already validated as the user's seat. It could only possibly
+        // be out of bounds now if the account data size was just reduced, which it was not.
+        unsafe { market.seat_order_sectors::<Side>(valid_user_seat_index) }
+            .find_remove(u32::from_le_bytes(entry.encoded_price.as_array()))?;
+        changes.num_canceled += 1;
+    }
+
+    // 3. Post the new orders that weren't matched to a current order.
+    let mut first_posted = None;
+    // Initialize hint to start from the head of the list
+    let mut iter_sector_index = Side::head(market.header);
+    for (i, (order, kept)) in orders
+        .into_valid_orders_iter()
+        .zip(changes.kept_new)
+        .enumerate()
+    {
+        if kept {
+            continue;
+        }
+        // Every order was already validated above.
+        let (order_info, client_order_id) = order?;
+        let mut order = Order::new(order_info, valid_user_seat_index);
+        order.set_client_order_id(client_order_id);
+        collateral_in_posted_orders = collateral_in_posted_orders
+            .checked_add(order.collateral_amount::<Side>())
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+        if first_posted.is_none() {
+            // The first order posted has the highest price priority of the orders posted, so it's
+            // the only one that needs to be validated as a post-only order later.
+            first_posted = Some(order.clone());
+        }
+        let le_encoded_price = *order.le_encoded_price();
+
+        let list = &mut market.orders::<Side>();
+        // Since the new orders are sorted in descending price priority, each search can continue
+        // from the previously inserted order rather than the head of the list.
+        // Safety: `iter_sector_index` is either the head of the list (initially) or the sector
+        // index of the previously inserted order, which is a valid sector index in the list.
+        let next_index =
+            Side::find_new_order_next_index(unsafe { list.iter_from(iter_sector_index) }, &order);
+        let insertion_index = insert_order(next_index, list, order)?;
+        iter_sector_index = insertion_index;
+        // Safety: Every new order was checked against the market's max orders per user above, so
+        // `i < MAX_ORDERS_USIZE`.
+        *changes.new_order_sector_indices.get_unchecked_mut(i) = insertion_index;
+
+        // Safety: The seat hint was already validated as the user's seat. It could only possibly
+        // be out of bounds now if the account data size was just reduced, which it was not.
+        unsafe { market.seat_order_sectors::<Side>(valid_user_seat_index) }
+            .add(&le_encoded_price, &insertion_index.to_le_bytes())?;
+        changes.num_posted += 1;
+    }
+
+    // Add the returned collateral first since underflow is much more likely than overflow. See
+    // `add_new_orders_and_update_seat_balance` for more details.
+    let user_seat = load_mut_seat_with_hint_unchecked(market, valid_user_seat_index);
+    user_seat.try_increment_collateral_available::<Side>(collateral_returned)?;
+    user_seat.try_decrement_collateral_available::<Side>(collateral_in_posted_orders)?;
+
+    if let Some(first) = first_posted {
+        Side::post_only_crossing_check(&first, market, clock.slot, clock.unix_timestamp)?;
+    }
+
+    Ok(changes)
 }
@@ -0,0 +1,100 @@
+//! See [`process_cancel_all_orders`].
+
+use dropset_interface::{
+    events::CancelOrderEventInstructionData,
+    instructions::{
+        CancelAllOrdersInstructionData,
+        SideFilter,
+    },
+    state::{
+        asks_dll::AskOrders,
+        bids_dll::BidOrders,
+        user_order_sectors::MAX_ORDERS_USIZE,
+    },
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        cancel_all_orders_context::CancelAllOrdersContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::{
+        order_operations::cancel_all_orders_on_side,
+        seat_operations::load_seat_user_for_signer,
+    },
+};
+
+/// Instruction handler logic for cancelling all of a user's orders on one or both sides of the
+/// market's order book and returning their collateral to the user's seat.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::CancelAllOrders`].
+#[inline(never)]
+pub unsafe fn process_cancel_all_orders<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let CancelAllOrdersInstructionData {
+        user_sector_index_hint,
+        side_filter,
+    } = CancelAllOrdersInstructionData::unpack_untagged(instruction_data)?;
+    let side_filter = SideFilter::try_from(side_filter)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { CancelAllOrdersContext::load(accounts) }?;
+
+    let ((bid_client_order_ids, bids_canceled), (ask_client_order_ids, asks_canceled)) = {
+        // Safety: Scoped mutable borrow of the market account data to cancel the user's orders.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        market.header.status()?.check_allows_cancels()?;
+
+        // The signer may be the seat's user or its delegate, but the orders always belong to the
+        // user.
+        let user = load_seat_user_for_signer(&market, user_sector_index_hint, ctx.user)?;
+
+        // Safety: The user sector index hint was just verified as in-bounds.
+        unsafe {
+            let bids_canceled = if side_filter.includes_bids() {
+                cancel_all_orders_on_side::<BidOrders>(&mut market, &user, user_sector_index_hint)?
+            } else {
+                ([0; MAX_ORDERS_USIZE], 0)
+            };
+            let asks_canceled = if side_filter.includes_asks() {
+                cancel_all_orders_on_side::<AskOrders>(&mut market, &user, user_sector_index_hint)?
+            } else {
+                ([0; MAX_ORDERS_USIZE], 0)
+            };
+            (bids_canceled, asks_canceled)
+        }
+    };
+
+    // Emit the events after the market data borrow above is dropped, since adding to the event
+    // buffer may flush it.
+    for client_order_id in &bid_client_order_ids[..bids_canceled as usize] {
+        event_buffer.add_to_buffer(
+            CancelOrderEventInstructionData::new(true, user_sector_index_hint, *client_order_id),
+            ctx.event_authority,
+            ctx.market_account.clone(),
+        )?;
+    }
+    for client_order_id in &ask_client_order_ids[..asks_canceled as usize] {
+        event_buffer.add_to_buffer(
+            CancelOrderEventInstructionData::new(false, user_sector_index_hint, *client_order_id),
+            ctx.event_authority,
+            ctx.market_account.clone(),
+        )?;
+    }
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -1,17 +1,23 @@
 //! See [`process_cancel_order`].
 
-#[cfg(feature = "debug")]
-use dropset_interface::events::CancelOrderEventInstructionData;
 use dropset_interface::{
+    error::DropsetError,
+    events::CancelOrderEventInstructionData,
     instructions::CancelOrderInstructionData,
-    state::sector::{
-        Sector,
-        SectorIndex,
+    state::{
+        asks_dll::AskOrders,
+        bids_dll::BidOrders,
+        market::MarketRefMut,
+        sector::{
+            Sector,
+            SectorIndex,
+        },
     },
 };
 use pinocchio::{
     account::AccountView,
     error::ProgramError,
+    Address,
 };
 
 use crate::{
@@ -23,8 +29,9 @@ use crate::{
     shared::{
         order_operations::load_order_from_sector_index,
         seat_operations::{
-            load_mut_seat_with_hint,
             load_mut_seat_with_hint_unchecked,
+            load_seat_user_for_signer,
+            load_seat_with_hint,
         },
     },
 };
@@ -39,7 +46,7 @@ use crate::{
 pub unsafe fn process_cancel_order<'a>(
     accounts: &'a [AccountView],
     instruction_data: &[u8],
-    _event_buffer: &mut EventBuffer,
+    event_buffer: &mut EventBuffer,
 ) -> Result<EventBufferContext<'a>, ProgramError> {
     let CancelOrderInstructionData {
         encoded_price,
@@ -50,31 +57,72 @@ pub unsafe fn process_cancel_order<'a>(
     // Safety: No account data in `accounts` is currently borrowed.
     let mut ctx = unsafe { CancelOrderContext::load(accounts) }?;
 
-    // Safety: The market account is currently not borrowed in any capacity.
-    let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+    let client_order_id = {
+        // Safety: The market account is currently not borrowed in any capacity.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        market.header.status()?.check_allows_cancels()?;
+
+        // The signer may be the seat's user or its delegate, but the orders always belong to the
+        // user.
+        let user = load_seat_user_for_signer(&market, user_sector_index_hint, ctx.user)?;
+        // Safety: The user sector index hint was just verified in-bounds.
+        unsafe {
+            cancel_order_at_price(
+                &mut market,
+                &user,
+                user_sector_index_hint,
+                encoded_price,
+                is_bid,
+            )
+        }?
+    };
+
+    event_buffer.add_to_buffer(
+        CancelOrderEventInstructionData::new(is_bid, user_sector_index_hint, client_order_id),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
 
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
+
+/// Cancels the user's order with the passed encoded price: removes it from the user seat's price
+/// -> order sectors mapping and the market's orders collection and returns its remaining
+/// collateral to the user's seat.
+///
+/// Returns the canceled order's client order id.
+///
+/// # Safety
+///
+/// Caller guarantees `user_sector_index_hint` is in-bounds of `market.sectors`.
+#[inline(always)]
+pub unsafe fn cancel_order_at_price(
+    market: &mut MarketRefMut,
+    user: &Address,
+    user_sector_index_hint: SectorIndex,
+    encoded_price: u32,
+    is_bid: bool,
+) -> Result<u64, DropsetError> {
     // Remove the order from the user seat's order sectors mapping.
     let order_sector_index = {
-        Sector::check_in_bounds(market.sectors, user_sector_index_hint)?;
-        // Safety: The user sector index hint was just verified in-bounds.
-        let user_seat = unsafe {
-            load_mut_seat_with_hint(&mut market, user_sector_index_hint, ctx.user.address())
+        // Safety: Caller guarantees the user sector index hint is in-bounds.
+        unsafe { load_seat_with_hint(market, user_sector_index_hint, user) }?;
+        // Safety: The user's seat was just verified at the index hint.
+        let order_sector_index = unsafe {
+            if is_bid {
+                market
+                    .seat_order_sectors::<BidOrders>(user_sector_index_hint)
+                    .find_remove(encoded_price)
+            } else {
+                market
+                    .seat_order_sectors::<AskOrders>(user_sector_index_hint)
+                    .find_remove(encoded_price)
+            }
         }?;
-        if is_bid {
-            SectorIndex::from_le_bytes(
-                user_seat
-                    .user_order_sectors
-                    .bids
-                    .find_remove(encoded_price)?,
-            )
-        } else {
-            SectorIndex::from_le_bytes(
-                user_seat
-                    .user_order_sectors
-                    .asks
-                    .find_remove(encoded_price)?,
-            )
-        }
+        SectorIndex::from_le_bytes(order_sector_index)
     };
 
     // The safety comment below explains why this isn't explicitly necessary.
@@ -85,8 +133,9 @@ pub unsafe fn process_cancel_order<'a>(
         // Safety: The order sector index returned from the `remove` method still points to a
         // sector with a valid order. All order sector indices in a user seat are thus in-bounds and
         // don't need to be explicitly verified as in-bounds.
-        load_order_from_sector_index(&market, order_sector_index)
+        load_order_from_sector_index(market, order_sector_index)
     };
+    let client_order_id = order.client_order_id();
 
     // Increment the user's collateral in their market seat by the amount remaining in the order.
     if is_bid {
@@ -94,14 +143,14 @@ pub unsafe fn process_cancel_order<'a>(
         let order_size_remaining = order.quote_remaining();
         // Safety: The seat hint was already validated as in-bounds. It could only possibly be out
         // of bounds now if the account data size was just reduced, which it was not.
-        let user_seat = load_mut_seat_with_hint_unchecked(&mut market, user_sector_index_hint);
+        let user_seat = load_mut_seat_with_hint_unchecked(market, user_sector_index_hint);
         user_seat.try_increment_quote_available(order_size_remaining)?;
     } else {
         // If the user placed an ask, they provided base as collateral.
         let order_size_remaining = order.base_remaining();
         // Safety: The seat hint was already validated as in-bounds. It could only possibly be out
         // of bounds now if the account data size was just reduced, which it was not.
-        let user_seat = load_mut_seat_with_hint_unchecked(&mut market, user_sector_index_hint);
+        let user_seat = load_mut_seat_with_hint_unchecked(market, user_sector_index_hint);
         user_seat.try_increment_base_available(order_size_remaining)?;
     }
 
@@ -115,15 +164,5 @@ pub unsafe fn process_cancel_order<'a>(
         }
     }
 
-    #[cfg(feature = "debug")]
-    _event_buffer.add_to_buffer(
-        CancelOrderEventInstructionData::new(is_bid, user_sector_index_hint),
-        ctx.event_authority,
-        ctx.market_account.clone(),
-    )?;
-
-    Ok(EventBufferContext {
-        event_authority: ctx.event_authority,
-        market_account: ctx.market_account,
-    })
+    Ok(client_order_id)
 }
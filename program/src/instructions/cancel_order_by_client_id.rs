@@ -0,0 +1,111 @@
+//! See [`process_cancel_order_by_client_id`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::CancelOrderEventInstructionData,
+    instructions::CancelOrderByClientIdInstructionData,
+    state::sector::SectorIndex,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        cancel_order_by_client_id_context::CancelOrderByClientIdContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    instructions::cancel_order::cancel_order_at_price,
+    shared::{
+        order_operations::load_order_from_sector_index,
+        seat_operations::{
+            load_seat_user_for_signer,
+            load_seat_with_hint,
+        },
+    },
+};
+
+/// Instruction handler logic for cancelling a user's bid or ask order on the market's order book
+/// by the client order id it was posted with.
+///
+/// Client order ids aren't required to be unique, so if multiple of the user's orders on the same
+/// side share the client order id, only the first one found in the user's seat is canceled.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::CancelOrderByClientId`].
+#[inline(never)]
+pub unsafe fn process_cancel_order_by_client_id<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let CancelOrderByClientIdInstructionData {
+        client_order_id,
+        is_bid,
+        user_sector_index_hint,
+    } = CancelOrderByClientIdInstructionData::unpack_untagged(instruction_data)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { CancelOrderByClientIdContext::load(accounts) }?;
+
+    {
+        // Safety: The market account is currently not borrowed in any capacity.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        market.header.status()?.check_allows_cancels()?;
+
+        // The signer may be the seat's user or its delegate, but the orders always belong to the
+        // user.
+        let user = load_seat_user_for_signer(&market, user_sector_index_hint, ctx.user)?;
+
+        // Find the price of the user's order with the matching client order id.
+        let encoded_price = {
+            // Safety: The user sector index hint was just verified in-bounds.
+            let user_seat = unsafe { load_seat_with_hint(&market, user_sector_index_hint, &user) }?;
+            let order_sectors = if is_bid {
+                &user_seat.user_order_sectors.bids
+            } else {
+                &user_seat.user_order_sectors.asks
+            };
+            // Safety: The seat's order sectors belong to this market.
+            unsafe { order_sectors.iter(market.sectors) }
+                .find_map(|entry| {
+                    // Safety: All order sector indices in a user seat are in-bounds.
+                    let order = unsafe {
+                        load_order_from_sector_index(
+                            &market,
+                            SectorIndex::from_le_bytes(entry.sector_index),
+                        )
+                    };
+                    (order.client_order_id() == client_order_id)
+                        .then(|| order.encoded_price().as_u32())
+                })
+                .ok_or(DropsetError::OrderNotFound)?
+        };
+
+        // Safety: The user sector index hint was verified in-bounds above.
+        unsafe {
+            cancel_order_at_price(
+                &mut market,
+                &user,
+                user_sector_index_hint,
+                encoded_price,
+                is_bid,
+            )
+        }?;
+    }
+
+    event_buffer.add_to_buffer(
+        CancelOrderEventInstructionData::new(is_bid, user_sector_index_hint, client_order_id),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -0,0 +1,96 @@
+//! See [`process_close_empty_seats`].
+
+use dropset_interface::{
+    events::CloseSeatEventInstructionData,
+    instructions::CloseEmptySeatsInstructionData,
+    state::{
+        market_seat::MarketSeat,
+        sector::{
+            Sector,
+            NIL,
+        },
+    },
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        close_empty_seats_context::CloseEmptySeatsContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+};
+
+/// Instruction handler logic for permissionlessly closing empty market seats, i.e., seats with no
+/// base or quote available and no open orders, and returning their sectors to the free stack.
+///
+/// Seats with a delegate are skipped, since the owner configured them for continued use and
+/// closing them would silently remove the delegate.
+///
+/// At most `max_seats` seats are closed, walking the seats list from the head.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::CloseEmptySeats`].
+#[inline(never)]
+pub unsafe fn process_close_empty_seats<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let CloseEmptySeatsInstructionData { max_seats } =
+        CloseEmptySeatsInstructionData::unpack_untagged(instruction_data)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { CloseEmptySeatsContext::load(accounts) }?;
+
+    let mut num_closed: u16 = 0;
+
+    // Safety: Scoped borrow of the market account data to read the head of the seats list.
+    let mut sector_index = unsafe { ctx.market_account.load_unchecked() }
+        .header
+        .seats_dll_head();
+
+    while sector_index != NIL && num_closed < max_seats {
+        let (next_index, closed) = {
+            // Safety: Scoped mutable borrow of the market account data.
+            let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+            // Safety: `sector_index` is a non-NIL index from the seats list.
+            let sector = unsafe { Sector::from_sector_index(market.sectors, sector_index) };
+            // Read the next index before the sector is removed from the list.
+            let next_index = sector.next();
+            let seat = sector.load_payload::<MarketSeat>();
+            let closed = seat.is_empty() && seat.delegate().is_none();
+
+            if closed {
+                // Remove the seat, push it to the free stack, and zero it out.
+                // Safety: `sector_index` is a valid sector index in the seats list.
+                market.seats().remove_at(sector_index);
+            }
+
+            (next_index, closed)
+        };
+
+        // Emit the event after the market data borrow above is dropped, since adding to the event
+        // buffer may flush it.
+        if closed {
+            event_buffer.add_to_buffer(
+                CloseSeatEventInstructionData::new(sector_index),
+                ctx.event_authority,
+                ctx.market_account.clone(),
+            )?;
+            num_closed += 1;
+        }
+
+        sector_index = next_index;
+    }
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
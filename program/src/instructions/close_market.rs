@@ -0,0 +1,147 @@
+//! See [`process_close_market`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::CloseMarketEventInstructionData,
+    state::market_status::MarketStatus,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{
+    context::close_market_context::CloseMarketContext,
+    events::EventBuffer,
+    shared::token_utils::market_transfers::{
+        close_market_token_account,
+        harvest_withheld_market_fees,
+        withdraw_non_zero_from_market,
+    },
+    validation::{
+        market_account_view::MarketAccountView,
+        mint_account_view::MintAccountView,
+        token_account_view::TokenAccountView,
+    },
+};
+
+/// Instruction handler logic for permanently closing an empty, [`MarketStatus::Closed`] market and
+/// returning the rent from the market account and its token accounts to the market authority.
+///
+/// An empty market owes nothing to any seat or fee recipient, so any tokens still held in the
+/// market's token accounts (e.g. transferred in directly rather than deposited) are swept to the
+/// authority's token accounts first, since the token program won't close a non-zero balance. For
+/// the same reason, any `spl_token_2022` transfer fees withheld in the market's token accounts are
+/// harvested to their mints.
+///
+/// Since the market account no longer exists afterwards, the event buffer is flushed before the
+/// accounts are closed instead of by the entrypoint.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::CloseMarket`].
+#[inline(never)]
+pub unsafe fn process_close_market(
+    accounts: &[AccountView],
+    _instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> ProgramResult {
+    // Safety: No account data in `accounts` is currently borrowed.
+    let ctx = unsafe { CloseMarketContext::load(accounts) }?;
+
+    {
+        // Safety: Scoped borrow of the market account data to check the market can be closed.
+        let market = unsafe { ctx.market_account.load_unchecked() };
+        if !ctx.authority.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if market.header.authority != *ctx.authority.address() {
+            return Err(DropsetError::IncorrectMarketAuthority.into());
+        }
+        if market.header.status()? != MarketStatus::Closed {
+            return Err(DropsetError::MarketStatusForbidsInstruction.into());
+        }
+        market.header.check_is_empty()?;
+    }
+
+    let market_lamports = ctx.market_account.account().lamports();
+    let lamports_reclaimed = market_lamports
+        .checked_add(ctx.base_market_ata.account.lamports())
+        .and_then(|sum| sum.checked_add(ctx.quote_market_ata.account.lamports()))
+        .ok_or(DropsetError::ArithmeticOverflow)?;
+
+    event_buffer.add_to_buffer(
+        CloseMarketEventInstructionData::new(lamports_reclaimed),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    // Safety: No account data is currently borrowed.
+    unsafe {
+        event_buffer.flush_events(ctx.event_authority, ctx.market_account.clone())?;
+
+        sweep_market_token_account(
+            &ctx.base_authority_ata,
+            &ctx.base_market_ata,
+            &ctx.market_account,
+            &ctx.base_mint,
+        )?;
+        sweep_market_token_account(
+            &ctx.quote_authority_ata,
+            &ctx.quote_market_ata,
+            &ctx.market_account,
+            &ctx.quote_mint,
+        )?;
+
+        harvest_withheld_market_fees(&ctx.base_market_ata, &ctx.base_mint)?;
+        harvest_withheld_market_fees(&ctx.quote_market_ata, &ctx.quote_mint)?;
+
+        close_market_token_account(
+            &ctx.base_market_ata,
+            ctx.authority,
+            &ctx.market_account,
+            &ctx.base_mint,
+        )?;
+        close_market_token_account(
+            &ctx.quote_market_ata,
+            ctx.authority,
+            &ctx.market_account,
+            &ctx.quote_mint,
+        )?;
+    }
+
+    // Move the market account's lamports to the authority and close it.
+    let authority_lamports = ctx
+        .authority
+        .lamports()
+        .checked_add(market_lamports)
+        .ok_or(DropsetError::ArithmeticOverflow)?;
+    ctx.authority.set_lamports(authority_lamports);
+    ctx.market_account.account().close()
+}
+
+/// Transfers the market token account's entire remaining balance to the authority's token account.
+///
+/// # Safety
+///
+/// Caller guarantees no account data of the accounts passed is currently borrowed.
+#[inline(always)]
+unsafe fn sweep_market_token_account<'a>(
+    authority_ata: &TokenAccountView<'a>,
+    market_ata: &TokenAccountView<'a>,
+    market_account: &MarketAccountView<'a>,
+    mint: &MintAccountView<'a>,
+) -> ProgramResult {
+    // Safety: Scoped immutable borrow of the market token account data to get its balance.
+    let leftover = unsafe { market_ata.get_balance() }?;
+    if leftover == 0 {
+        return Ok(());
+    }
+
+    // Safety: No account data is currently borrowed.
+    unsafe {
+        withdraw_non_zero_from_market(authority_ata, market_ata, market_account, mint, leftover)
+    }
+}
@@ -1,6 +1,7 @@
 //! See [`process_close_seat`].
 
 use dropset_interface::{
+    error::DropsetError,
     events::CloseSeatEventInstructionData,
     instructions::CloseSeatInstructionData,
     state::sector::Sector,
@@ -21,7 +22,8 @@ use crate::{
     shared::seat_operations::load_seat_with_hint,
 };
 
-/// Instruction handler logic for closing an existing market seat and reclaiming associated funds.
+/// Instruction handler logic for closing an existing market seat with no open orders and reclaiming
+/// associated funds.
 ///
 /// # Safety
 ///
@@ -39,20 +41,32 @@ pub unsafe fn process_close_seat<'a>(
     // Safety: No account data in `accounts` is currently borrowed.
     let mut ctx = unsafe { CloseSeatContext::load(accounts) }?;
 
-    // Remove the seat after copying the market bump and the seat's base and quote available.
-    let (market_bump, base_available, quote_available) = unsafe {
+    // Remove the seat after copying the market signer seeds and the seat's base and quote
+    // available.
+    let (market_id, market_bump, base_available, quote_available) = unsafe {
         // Safety: Scoped mutable borrow of market account data.
         let mut market = ctx.market_account.load_unchecked_mut();
 
         // --- read market data ---
-        // Copy the market bump and the seat's base and quote amounts available to the user.
+        // Copy the market ID, bump and the seat's base and quote amounts available to the user.
+        let market_id = market.header.market_id();
         let market_bump = market.header.market_bump;
         Sector::check_in_bounds(market.sectors, sector_index_hint)?;
         // Safety: The index hint was just verified as in-bounds.
         let seat = load_seat_with_hint(&market, sector_index_hint, ctx.user.address())?;
+        // The collateral locked in open orders isn't included in the seat's balances, so closing a
+        // seat with open orders would strand that collateral.
+        if seat.has_open_orders() {
+            return Err(DropsetError::SeatHasOpenOrders.into());
+        }
         // NOTE: The base/quote available and deposited do not need to be zeroed here because
         // they're zeroed out in the `push_free_sector` call in the `remove_at` method below.
-        let copied_values = (market_bump, seat.base_available(), seat.quote_available());
+        let copied_values = (
+            market_id,
+            market_bump,
+            seat.base_available(),
+            seat.quote_available(),
+        );
 
         // --- write market data ---
         // Remove the seat, push it to the free stack, and zero it out.
@@ -76,6 +90,7 @@ pub unsafe fn process_close_seat<'a>(
             .invoke_signed(&[market_signer!(
                 ctx.base_mint.account.address(),
                 ctx.quote_mint.account.address(),
+                market_id,
                 market_bump
             )])?;
         } else {
@@ -93,6 +108,7 @@ pub unsafe fn process_close_seat<'a>(
             .invoke_signed(&[market_signer!(
                 ctx.base_mint.account.address(),
                 ctx.quote_mint.account.address(),
+                market_id,
                 market_bump
             )])?;
         }
@@ -110,6 +126,7 @@ pub unsafe fn process_close_seat<'a>(
             .invoke_signed(&[market_signer!(
                 ctx.base_mint.account.address(),
                 ctx.quote_mint.account.address(),
+                market_id,
                 market_bump
             )])?;
         } else {
@@ -127,6 +144,7 @@ pub unsafe fn process_close_seat<'a>(
             .invoke_signed(&[market_signer!(
                 ctx.base_mint.account.address(),
                 ctx.quote_mint.account.address(),
+                market_id,
                 market_bump
             )])?;
         }
@@ -0,0 +1,86 @@
+//! See [`process_collect_fees`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::CollectFeesEventInstructionData,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        collect_fees_context::CollectFeesContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::token_utils::market_transfers::withdraw_non_zero_from_market,
+};
+
+/// Instruction handler logic for sweeping a market's accrued fees to its fee recipient.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::CollectFees`].
+#[inline(never)]
+pub unsafe fn process_collect_fees<'a>(
+    accounts: &'a [AccountView],
+    _instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { CollectFeesContext::load(accounts) }?;
+
+    if !ctx.fee_recipient.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Take the accrued fees out of the header, resetting them to zero.
+    let (base_amount, quote_amount) = {
+        // Safety: Scoped mutable borrow of the market account data to take the accrued fees.
+        let market = unsafe { ctx.market_account.load_unchecked_mut() };
+        if market.header.fee_recipient != *ctx.fee_recipient.address() {
+            return Err(DropsetError::IncorrectFeeRecipient.into());
+        }
+        market.header.take_fees_accrued()
+    };
+
+    if base_amount == 0 && quote_amount == 0 {
+        return Err(DropsetError::AmountCannotBeZero.into());
+    }
+
+    // Safety: No account data is currently borrowed.
+    unsafe {
+        if base_amount != 0 {
+            withdraw_non_zero_from_market(
+                &ctx.base_recipient_ata,
+                &ctx.base_market_ata,
+                &ctx.market_account,
+                &ctx.base_mint,
+                base_amount,
+            )?;
+        }
+        if quote_amount != 0 {
+            withdraw_non_zero_from_market(
+                &ctx.quote_recipient_ata,
+                &ctx.quote_market_ata,
+                &ctx.market_account,
+                &ctx.quote_mint,
+                quote_amount,
+            )?;
+        }
+    }
+
+    event_buffer.add_to_buffer(
+        CollectFeesEventInstructionData::new(base_amount, quote_amount),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -0,0 +1,70 @@
+//! See [`process_compact_market`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::CompactMarketEventInstructionData,
+    instructions::CompactMarketInstructionData,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        compact_market_context::CompactMarketContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+};
+
+/// Instruction handler logic for the market authority shrinking the market account down to its live
+/// sectors plus `num_free_sectors` free sectors and reclaiming the excess rent.
+///
+/// No event is emitted per relocated seat or order, so clients must look up seat index hints and
+/// order sector indices again after a compaction instead of relying on the ones they cached.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::CompactMarket`].
+#[inline(never)]
+pub unsafe fn process_compact_market<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let CompactMarketInstructionData { num_free_sectors } =
+        CompactMarketInstructionData::unpack_untagged(instruction_data)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { CompactMarketContext::load(accounts) }?;
+
+    if !ctx.authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Safety: Scoped borrow of the market account data to check the authority.
+    if unsafe { ctx.market_account.load_unchecked() }
+        .header
+        .authority
+        != *ctx.authority.address()
+    {
+        return Err(DropsetError::IncorrectMarketAuthority.into());
+    }
+
+    // Safety: Scoped writes to the market account and authority to compact the market account.
+    let (num_sectors_removed, lamports_refunded) =
+        unsafe { ctx.market_account.compact(ctx.authority, num_free_sectors) }?;
+
+    event_buffer.add_to_buffer(
+        CompactMarketEventInstructionData::new(num_sectors_removed, lamports_refunded),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
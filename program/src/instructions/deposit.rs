@@ -40,7 +40,8 @@ use crate::{
 ///   - If invalid return early, otherwise update the seat with the amount deposited.
 ///
 /// 2) The user didn't provide a non-NIL sector index hint: register a new seat.
-///   - Check if the account needs extra storage and resize it if so.
+///   - Check if the account needs extra storage and resize it if so, funded by the optional payer
+///     account or the user.
 ///   - Then register the user's new seat at the proper index with the amount deposited data.
 ///   - If the user already exists, return an error instead of inserting.
 ///
@@ -62,6 +63,12 @@ pub unsafe fn process_deposit<'a>(
     // Safety: No account data in `accounts` is currently borrowed.
     let mut ctx = unsafe { DepositContext::load(accounts) }?;
 
+    // Safety: Scoped borrow of the market account data to check its status.
+    unsafe { ctx.market_account.load_unchecked() }
+        .header
+        .status()?
+        .check_allows_deposits()?;
+
     // Safety: No account data is currently borrowed.
     let amount_deposited = unsafe {
         deposit_non_zero_to_market(&ctx.user_ata, &ctx.market_ata, ctx.user, &ctx.mint, amount)
@@ -93,7 +100,7 @@ pub unsafe fn process_deposit<'a>(
 
         if needs_resize {
             // Safety: Scoped mutable borrow to resize the market account and add a new sector.
-            unsafe { ctx.market_account.resize(ctx.user, 1) }?;
+            unsafe { ctx.market_account.resize(ctx.expansion_payer, 1) }?;
         }
 
         // Safety: Scoped mutable borrow of market account data to insert the new seat.
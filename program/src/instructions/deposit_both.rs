@@ -0,0 +1,152 @@
+//! See [`process_deposit_both`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::DepositEventInstructionData,
+    instructions::DepositBothInstructionData,
+    state::{
+        market_seat::MarketSeat,
+        sector::{
+            Sector,
+            NIL,
+        },
+    },
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        deposit_both_context::DepositBothContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::{
+        seat_operations::{
+            load_mut_seat_with_hint,
+            try_insert_market_seat,
+        },
+        token_utils::market_transfers::deposit_non_zero_to_market,
+    },
+};
+
+/// Instruction handler logic for depositing base and quote into a market seat in one instruction.
+///
+/// A zero amount skips the transfer for that token, but at least one amount must be non-zero. The
+/// seat is updated or registered the same way as in [`crate::instructions::process_deposit`], and
+/// a deposit event is emitted for each token deposited.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::DepositBoth`].
+#[inline(never)]
+pub unsafe fn process_deposit_both<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let DepositBothInstructionData {
+        base_amount,
+        quote_amount,
+        sector_index_hint,
+    } = DepositBothInstructionData::unpack_untagged(instruction_data)?;
+
+    if base_amount == 0 && quote_amount == 0 {
+        return Err(DropsetError::AmountCannotBeZero.into());
+    }
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { DepositBothContext::load(accounts) }?;
+
+    // Safety: Scoped borrow of the market account data to check its status.
+    unsafe { ctx.market_account.load_unchecked() }
+        .header
+        .status()?
+        .check_allows_deposits()?;
+
+    // Safety: No account data is currently borrowed.
+    let base_deposited = match base_amount {
+        0 => 0,
+        amount => unsafe {
+            deposit_non_zero_to_market(
+                &ctx.base_user_ata,
+                &ctx.base_market_ata,
+                ctx.user,
+                &ctx.base_mint,
+                amount,
+            )
+        }?,
+    };
+
+    // Safety: No account data is currently borrowed.
+    let quote_deposited = match quote_amount {
+        0 => 0,
+        amount => unsafe {
+            deposit_non_zero_to_market(
+                &ctx.quote_user_ata,
+                &ctx.quote_market_ata,
+                ctx.user,
+                &ctx.quote_mint,
+                amount,
+            )
+        }?,
+    };
+
+    // 1) Update an existing seat.
+    let sector_index = if sector_index_hint != NIL {
+        // Safety: Scoped mutable borrow of the market account to mutate the user's seat.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        Sector::check_in_bounds(market.sectors, sector_index_hint)?;
+        // Safety: The index hint was just verified as in-bounds.
+        let seat =
+            unsafe { load_mut_seat_with_hint(&mut market, sector_index_hint, ctx.user.address()) }?;
+
+        seat.try_increment_base_available(base_deposited)?;
+        seat.try_increment_quote_available(quote_deposited)?;
+
+        sector_index_hint
+    } else {
+        // 2) Register a new seat.
+        // Safety: Scoped immutable borrow of the market account, checks the number of free sectors.
+        let needs_resize = unsafe { ctx.market_account.load_unchecked() }
+            .header
+            .num_free_sectors()
+            == 0;
+
+        if needs_resize {
+            // Safety: Scoped mutable borrow to resize the market account and add a new sector.
+            unsafe { ctx.market_account.resize(ctx.expansion_payer, 1) }?;
+        }
+
+        // Safety: Scoped mutable borrow of market account data to insert the new seat.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        let seat = MarketSeat::new(*ctx.user.address(), base_deposited, quote_deposited);
+
+        // Attempts to insert the user into the linked list. If the user already exists, this fails.
+        try_insert_market_seat(&mut market.seats(), seat)?
+    };
+
+    if base_deposited != 0 {
+        event_buffer.add_to_buffer(
+            DepositEventInstructionData::new(base_deposited, true, sector_index),
+            ctx.event_authority,
+            ctx.market_account.clone(),
+        )?;
+    }
+
+    if quote_deposited != 0 {
+        event_buffer.add_to_buffer(
+            DepositEventInstructionData::new(quote_deposited, false, sector_index),
+            ctx.event_authority,
+            ctx.market_account.clone(),
+        )?;
+    }
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -0,0 +1,135 @@
+//! See [`process_deposit_for`].
+
+use dropset_interface::{
+    events::DepositForEventInstructionData,
+    instructions::DepositForInstructionData,
+    state::{
+        market_seat::MarketSeat,
+        sector::{
+            Sector,
+            NIL,
+        },
+    },
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        deposit_for_context::DepositForContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::{
+        seat_operations::{
+            load_mut_seat_with_hint,
+            try_insert_market_seat,
+        },
+        token_utils::market_transfers::deposit_non_zero_to_market,
+    },
+};
+
+/// Instruction handler logic for depositing funds from a funder into a beneficiary's market seat.
+///
+/// This follows the same two paths as [`crate::instructions::process_deposit`], except the seat
+/// is looked up or registered for the beneficiary instead of the signer, and the funder pays for
+/// the market expansion if registering the seat requires one.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::DepositFor`].
+#[inline(never)]
+pub unsafe fn process_deposit_for<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let DepositForInstructionData {
+        amount,
+        sector_index_hint,
+        beneficiary,
+    } = DepositForInstructionData::unpack_untagged(instruction_data)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { DepositForContext::load(accounts) }?;
+
+    // Safety: Scoped borrow of the market account data to check its status.
+    unsafe { ctx.market_account.load_unchecked() }
+        .header
+        .status()?
+        .check_allows_deposits()?;
+
+    // Safety: No account data is currently borrowed.
+    let amount_deposited = unsafe {
+        deposit_non_zero_to_market(
+            &ctx.funder_ata,
+            &ctx.market_ata,
+            ctx.funder,
+            &ctx.mint,
+            amount,
+        )
+    }?;
+
+    // 1) Update the beneficiary's existing seat.
+    let sector_index = if sector_index_hint != NIL {
+        // Safety: Scoped mutable borrow of the market account to mutate the beneficiary's seat.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        Sector::check_in_bounds(market.sectors, sector_index_hint)?;
+        // Safety: The index hint was just verified as in-bounds.
+        let seat =
+            unsafe { load_mut_seat_with_hint(&mut market, sector_index_hint, &beneficiary) }?;
+
+        if ctx.mint.is_base_mint {
+            seat.try_increment_base_available(amount_deposited)?;
+        } else {
+            seat.try_increment_quote_available(amount_deposited)?;
+        }
+
+        sector_index_hint
+    } else {
+        // 2) Register a new seat for the beneficiary.
+        // Safety: Scoped immutable borrow of the market account, checks the number of free sectors.
+        let needs_resize = unsafe { ctx.market_account.load_unchecked() }
+            .header
+            .num_free_sectors()
+            == 0;
+
+        if needs_resize {
+            // Safety: Scoped mutable borrow to resize the market account and add a new sector.
+            unsafe { ctx.market_account.resize(ctx.funder, 1) }?;
+        }
+
+        // Safety: Scoped mutable borrow of market account data to insert the new seat.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+
+        let seat = if ctx.mint.is_base_mint {
+            MarketSeat::new(beneficiary, amount_deposited, 0)
+        } else {
+            MarketSeat::new(beneficiary, 0, amount_deposited)
+        };
+
+        // Attempts to insert the beneficiary into the linked list. If they already have a seat,
+        // this fails.
+        try_insert_market_seat(&mut market.seats(), seat)?
+    };
+
+    event_buffer.add_to_buffer(
+        DepositForEventInstructionData::new(
+            amount_deposited,
+            ctx.mint.is_base_mint,
+            sector_index,
+            *ctx.funder.address(),
+            beneficiary,
+        ),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -5,10 +5,17 @@ use dropset_interface::{
         DropsetError,
         DropsetResult,
     },
+    events::{
+        ExpireOrderEventInstructionData,
+        FillEventInstructionData,
+        SelfTradeEventInstructionData,
+    },
+    instructions::SelfTradeBehavior,
     state::{
         asks_dll::AskOrders,
         bids_dll::BidOrders,
         linked_list::LinkedListHeaderOperations,
+        market::MarketRefMut,
         market_seat::MarketSeat,
         sector::{
             Sector,
@@ -17,17 +24,31 @@ use dropset_interface::{
         },
     },
 };
-use pinocchio::hint;
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    hint,
+    sysvars::{
+        clock::Clock,
+        Sysvar,
+    },
+    Address,
+};
 #[cfg(debug_assertions)]
 use price::EncodedPrice;
 
 use crate::{
-    context::market_order_context::MarketOrderContext,
+    events::EventBuffer,
     instructions::market_order::mul_div_checked,
-    shared::order_operations::{
-        load_mut_order_from_sector_index,
-        load_order_from_sector_index,
+    shared::{
+        fees::maker_rebate,
+        order_operations::{
+            load_mut_order_from_sector_index,
+            load_order_from_sector_index,
+            remove_order_and_refund_maker,
+        },
     },
+    validation::market_account_view::MarketAccountView,
 };
 
 struct OrderSnapshot {
@@ -36,6 +57,8 @@ struct OrderSnapshot {
     encoded_price: u32,
     maker_seat_sector: SectorIndex,
     order_sector: SectorIndex,
+    is_expired: bool,
+    is_self_trade: bool,
 }
 
 impl OrderSnapshot {
@@ -61,6 +84,12 @@ impl OrderSnapshot {
 pub struct AmountsFilled {
     pub base: u64,
     pub quote: u64,
+    /// The total maker rebates credited to maker seats, denominated in the asset the taker
+    /// receives; i.e., base for a market buy and quote for a market sell.
+    pub maker_rebates: u64,
+    /// The amount the taker order was decremented by without filling due to
+    /// [`SelfTradeBehavior::DecrementBoth`], denominated in the same asset as the order size.
+    pub self_trade_decremented: u64,
 }
 
 /// `IS_BUY` determines whether or not it's a market buy or a market sell.
@@ -70,6 +99,22 @@ pub struct AmountsFilled {
 ///
 /// `IS_BUY == true && BASE_DENOM == false && amount == 1000`
 ///
+/// `limit_price` is the worst encoded price the taker is willing to fill at. Filling stops as soon
+/// as the top of book is worse than the limit price, even if the order size hasn't been exhausted.
+///
+/// `maker_rebate_bps` is the maker rebate credited to each filled maker's seat, paid in the asset
+/// the taker receives. The caller is responsible for charging the taker fee that funds it.
+///
+/// A [`FillEventInstructionData`] is added to the event buffer for every maker order matched.
+///
+/// Expired maker orders reached while filling are never matched. They're removed from the book,
+/// their collateral is returned to the maker's seat and an [`ExpireOrderEventInstructionData`] is
+/// added to the event buffer instead.
+///
+/// `self_trade_behavior` determines what happens when the top of book belongs to `taker`. Unless
+/// the taker aborts, a [`SelfTradeEventInstructionData`] is added to the event buffer instead of a
+/// fill event, since nothing is transferred between the two sides.
+///
 /// This function returns the amounts filled denominated in both base and quote. The ratio of these
 /// two values is effectively the average fill price.
 ///
@@ -77,25 +122,102 @@ pub struct AmountsFilled {
 ///
 /// The market account data must not be currently borrowed.
 #[inline(always)]
-pub unsafe fn fill_market_order<const IS_BUY: bool, const BASE_DENOM: bool>(
-    ctx: &'_ mut MarketOrderContext<'_>,
+pub unsafe fn fill_market_order<'a, const IS_BUY: bool, const BASE_DENOM: bool>(
+    market_account: &'_ mut MarketAccountView<'a>,
+    event_authority: &'a AccountView,
+    event_buffer: &mut EventBuffer,
     order_size: u64,
-) -> Result<AmountsFilled, DropsetError> {
+    limit_price: u32,
+    maker_rebate_bps: u16,
+    taker: &Address,
+    self_trade_behavior: SelfTradeBehavior,
+) -> Result<AmountsFilled, ProgramError> {
     // All amounts in this function are in atoms.
     let mut constraint_asset_remaining = order_size;
     let mut counter_asset_filled: u64 = 0;
+    let mut maker_rebates: u64 = 0;
+    let mut self_trade_decremented: u64 = 0;
+
+    let clock = Clock::get()?;
 
     // Iterate over each order on the book, filling each posted order in whole as long as the
     // market order has any remaining size.
     // That is, as long as the amount not filled yet exceeds the amount in the next posted order,
     // simply close the order and decrement the remaining amount by the amount used to fill the
     // order. This skips muldiv operations until the very last partial fill.
-    while let Some(top_order) = top_of_book_snapshot::<IS_BUY>(ctx) {
+    while let Some(top_order) = top_of_book_snapshot::<IS_BUY>(market_account, &clock, taker) {
         // If there's nothing left to fill, break from the loop. The last order filled cleanly with
         // no remainder so there's no partial order to fill.
         if hint::unlikely(constraint_asset_remaining == 0) {
             break;
+        // If the top of book is past the taker's limit price, stop filling. Asks are sorted
+        // ascending and bids descending, so every order after this one is past the limit too.
+        } else if exceeds_limit_price::<IS_BUY>(top_order.encoded_price, limit_price) {
+            break;
+        // If the top of book has expired, remove it without filling it and move on to the next
+        // order.
+        } else if hint::unlikely(top_order.is_expired) {
+            // Safety: The market account data isn't currently borrowed and the top order's sector
+            // index points to a valid order on the side of the book being filled.
+            unsafe {
+                let mut market = market_account.load_unchecked_mut();
+                if IS_BUY {
+                    remove_order_and_refund_maker::<AskOrders>(&mut market, top_order.order_sector)
+                } else {
+                    remove_order_and_refund_maker::<BidOrders>(&mut market, top_order.order_sector)
+                }
+            }?;
+
+            event_buffer.add_to_buffer(
+                ExpireOrderEventInstructionData::new(
+                    !IS_BUY,
+                    top_order.maker_seat_sector,
+                    top_order.order_sector,
+                    top_order.encoded_price,
+                    top_order.base_remaining,
+                    top_order.quote_remaining,
+                ),
+                event_authority,
+                market_account.clone(),
+            )?;
+        // If the top of book is the taker's own order and the taker opted to abort, fail the
+        // whole instruction.
+        } else if hint::unlikely(
+            top_order.is_self_trade && self_trade_behavior == SelfTradeBehavior::Abort,
+        ) {
+            return Err(DropsetError::SelfTradeAborted.into());
+        // If the taker opted to cancel their own resting order, remove it without filling it and
+        // move on to the next order.
+        } else if hint::unlikely(
+            top_order.is_self_trade && self_trade_behavior == SelfTradeBehavior::CancelResting,
+        ) {
+            // Safety: The market account data isn't currently borrowed and the top order's sector
+            // index points to a valid order on the side of the book being filled.
+            unsafe {
+                let mut market = market_account.load_unchecked_mut();
+                if IS_BUY {
+                    remove_order_and_refund_maker::<AskOrders>(&mut market, top_order.order_sector)
+                } else {
+                    remove_order_and_refund_maker::<BidOrders>(&mut market, top_order.order_sector)
+                }
+            }?;
+
+            event_buffer.add_to_buffer(
+                SelfTradeEventInstructionData::new(
+                    SelfTradeBehavior::CancelResting as u8,
+                    top_order.maker_seat_sector,
+                    top_order.order_sector,
+                    top_order.encoded_price,
+                    top_order.base_remaining,
+                    top_order.quote_remaining,
+                    true,
+                ),
+                event_authority,
+                market_account.clone(),
+            )?;
         } else {
+            // Any self trade that reaches this point decrements both sides instead of filling.
+            let is_self_trade = top_order.is_self_trade;
             // Safety:
             // 1. Market account data isn't currently borrowed per this function's safety contract.
             // 2. The head/top of book order sector index is valid.
@@ -107,27 +229,67 @@ pub unsafe fn fill_market_order<const IS_BUY: bool, const BASE_DENOM: bool>(
                 // can be completely filled and thus removed from the books.
                 if top_order.get_constrained_remaining::<BASE_DENOM>() <= constraint_asset_remaining
                 {
+                    if is_self_trade {
+                        self_trade_decremented = self_trade_decremented
+                            .checked_add(top_order.get_constrained_remaining::<BASE_DENOM>())
+                            .ok_or(DropsetError::ArithmeticOverflow)?;
+                    }
+
                     // Safety: The order's constrained amount remaining is <= the constraint asset
                     // remaining.
                     full_fill::<IS_BUY, BASE_DENOM>(
-                        ctx,
+                        market_account,
                         &mut constraint_asset_remaining,
                         &mut counter_asset_filled,
+                        &mut maker_rebates,
+                        maker_rebate_bps,
                         &top_order,
+                        is_self_trade,
                     )?;
 
                     // Safety: The market account data isn't currently borrowed and the top order's
                     // maker seat sector index still points to a valid seat in memory.
                     #[cfg(debug_assertions)]
-                    ensure_order_has_been_removed::<IS_BUY>(ctx, &top_order);
+                    ensure_order_has_been_removed::<IS_BUY>(market_account, &top_order);
+
+                    add_match_event(
+                        event_buffer,
+                        event_authority,
+                        market_account,
+                        &top_order,
+                        top_order.base_remaining,
+                        top_order.quote_remaining,
+                        true,
+                        is_self_trade,
+                    )?;
                 } else {
                     // Otherwise, it's a partial fill. That is, the maker order *cannot* be
                     // completely filled and must be mutated to reflect the new amounts remaining.
-                    partial_fill::<IS_BUY, BASE_DENOM>(
-                        ctx,
+                    if is_self_trade {
+                        self_trade_decremented = self_trade_decremented
+                            .checked_add(constraint_asset_remaining)
+                            .ok_or(DropsetError::ArithmeticOverflow)?;
+                    }
+
+                    let (base_filled, quote_filled) = partial_fill::<IS_BUY, BASE_DENOM>(
+                        market_account,
                         &mut constraint_asset_remaining,
                         &mut counter_asset_filled,
+                        &mut maker_rebates,
+                        maker_rebate_bps,
+                        &top_order,
+                        is_self_trade,
+                    )?;
+
+                    add_match_event(
+                        event_buffer,
+                        event_authority,
+                        market_account,
                         &top_order,
+                        base_filled,
+                        quote_filled,
+                        false,
+                        is_self_trade,
                     )?;
 
                     // The taker order amount should be completely filled now.
@@ -138,26 +300,94 @@ pub unsafe fn fill_market_order<const IS_BUY: bool, const BASE_DENOM: bool>(
         }
     }
 
-    // Safety: The constraint asset remaining never increments, so it's always <= the order size.
-    let constrained_asset_filled = order_size.unchecked_sub(constraint_asset_remaining);
+    // Safety: The constraint asset remaining and the amount decremented by self trades never
+    // increment past the order size in total, so their sum is always <= the order size.
+    let constrained_asset_filled = order_size
+        .unchecked_sub(constraint_asset_remaining)
+        .unchecked_sub(self_trade_decremented);
 
     if BASE_DENOM {
         Ok(AmountsFilled {
             base: constrained_asset_filled,
             quote: counter_asset_filled,
+            maker_rebates,
+            self_trade_decremented,
         })
     } else {
         Ok(AmountsFilled {
             base: counter_asset_filled,
             quote: constrained_asset_filled,
+            maker_rebates,
+            self_trade_decremented,
         })
     }
 }
 
+/// Adds a [`FillEventInstructionData`] for a match between two different users, or a
+/// [`SelfTradeEventInstructionData`] if both sides belong to the taker and were only decremented.
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+fn add_match_event<'a>(
+    event_buffer: &mut EventBuffer,
+    event_authority: &'a AccountView,
+    market_account: &'_ MarketAccountView<'a>,
+    top_order: &OrderSnapshot,
+    base_matched: u64,
+    quote_matched: u64,
+    order_removed: bool,
+    is_self_trade: bool,
+) -> Result<(), ProgramError> {
+    if hint::unlikely(is_self_trade) {
+        event_buffer.add_to_buffer(
+            SelfTradeEventInstructionData::new(
+                SelfTradeBehavior::DecrementBoth as u8,
+                top_order.maker_seat_sector,
+                top_order.order_sector,
+                top_order.encoded_price,
+                base_matched,
+                quote_matched,
+                order_removed,
+            ),
+            event_authority,
+            market_account.clone(),
+        )
+    } else {
+        event_buffer.add_to_buffer(
+            FillEventInstructionData::new(
+                top_order.maker_seat_sector,
+                top_order.order_sector,
+                top_order.encoded_price,
+                base_matched,
+                quote_matched,
+                order_removed,
+            ),
+            event_authority,
+            market_account.clone(),
+        )
+    }
+}
+
+/// Whether or not a maker order's encoded price is worse than the taker's limit price.
+///
+/// A market buy fills asks, so any ask priced above the limit is too expensive. A market sell fills
+/// bids, so any bid priced below the limit is too cheap.
 #[inline(always)]
-fn top_of_book_snapshot<const IS_BUY: bool>(ctx: &'_ MarketOrderContext) -> Option<OrderSnapshot> {
+const fn exceeds_limit_price<const IS_BUY: bool>(maker_price: u32, limit_price: u32) -> bool {
+    if IS_BUY {
+        maker_price > limit_price
+    } else {
+        maker_price < limit_price
+    }
+}
+
+#[inline(always)]
+fn top_of_book_snapshot<const IS_BUY: bool>(
+    market_account: &'_ MarketAccountView,
+    clock: &Clock,
+    taker: &Address,
+) -> Option<OrderSnapshot> {
     // Safety: Scoped borrow of the market account data to check the top of book.
-    let market = unsafe { ctx.market_account.load_unchecked() };
+    let market = unsafe { market_account.load_unchecked() };
 
     let head_index = if IS_BUY {
         AskOrders::head(market.header)
@@ -170,12 +400,17 @@ fn top_of_book_snapshot<const IS_BUY: bool>(ctx: &'_ MarketOrderContext) -> Opti
     } else {
         // Safety: The head index is a non-NIL sector index pointing to a valid order sector.
         let order = unsafe { load_order_from_sector_index(&market, head_index) };
+        // Safety: The maker seat sector index came from a valid order, so it's in-bounds.
+        let maker_seat = unsafe { Sector::from_sector_index(market.sectors, order.user_seat()) }
+            .load_payload::<MarketSeat>();
         Some(OrderSnapshot {
             base_remaining: order.base_remaining(),
             quote_remaining: order.quote_remaining(),
             encoded_price: order.encoded_price().as_u32(),
             maker_seat_sector: order.user_seat(),
             order_sector: head_index,
+            is_expired: order.is_expired(clock.slot, clock.unix_timestamp),
+            is_self_trade: maker_seat.user == *taker,
         })
     }
 }
@@ -186,6 +421,9 @@ fn top_of_book_snapshot<const IS_BUY: bool>(ctx: &'_ MarketOrderContext) -> Opti
 ///    order map.
 /// 3. Update the constraint asset remaining and the counter asset filled.
 ///
+/// If `is_self_trade` is true, the order belongs to the taker and is only decremented: its
+/// collateral is returned to the maker's seat and the counter asset filled isn't updated.
+///
 /// # Safety
 ///
 /// The market account data must not be currently borrowed and the top order sector index and the
@@ -194,19 +432,22 @@ fn top_of_book_snapshot<const IS_BUY: bool>(ctx: &'_ MarketOrderContext) -> Opti
 /// The constraint asset remaining must be <= the top order's constraint asset remaining.
 #[inline(always)]
 unsafe fn full_fill<const IS_BUY: bool, const BASE_DENOM: bool>(
-    ctx: &'_ mut MarketOrderContext<'_>,
+    market_account: &'_ mut MarketAccountView<'_>,
     constraint_asset_remaining: &mut u64,
     counter_asset_filled: &mut u64,
+    maker_rebates: &mut u64,
+    maker_rebate_bps: u16,
     top_order: &OrderSnapshot,
+    is_self_trade: bool,
 ) -> DropsetResult {
     // 1. Close/remove the order from the orders collection.
     if IS_BUY {
-        ctx.market_account
+        market_account
             .load_unchecked_mut()
             .asks()
             .remove_at(top_order.order_sector);
     } else {
-        ctx.market_account
+        market_account
             .load_unchecked_mut()
             .bids()
             .remove_at(top_order.order_sector);
@@ -215,17 +456,33 @@ unsafe fn full_fill<const IS_BUY: bool, const BASE_DENOM: bool>(
     // 2. Update the filled maker seat's balance and remove the order from their price to order
     // sector map.
     // Safety: The safety contract is essentially a subset of the calling function.
-    unsafe {
-        update_maker_seat_after_fill::<IS_BUY, false>(
-            ctx,
-            top_order.maker_seat_sector,
-            // The base/quote amount filled is simply the (now previously) top order's amounts
-            // remaining, since this was a full fill.
-            top_order.base_remaining,
-            top_order.quote_remaining,
-            top_order.encoded_price,
-        )
-    }?;
+    // The base/quote amount filled is simply the (now previously) top order's amounts remaining,
+    // since this was a full fill.
+    if is_self_trade {
+        unsafe {
+            update_maker_seat_after_self_trade::<IS_BUY, false>(
+                market_account,
+                top_order.maker_seat_sector,
+                top_order.base_remaining,
+                top_order.quote_remaining,
+                top_order.encoded_price,
+            )
+        }?;
+    } else {
+        let rebate = unsafe {
+            update_maker_seat_after_fill::<IS_BUY, false>(
+                market_account,
+                top_order.maker_seat_sector,
+                top_order.base_remaining,
+                top_order.quote_remaining,
+                top_order.encoded_price,
+                maker_rebate_bps,
+            )
+        }?;
+        *maker_rebates = maker_rebates
+            .checked_add(rebate)
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+    }
 
     // 3. Update the constrained amount not filled yet and the counter asset total filled.
     // Safety: The amount of constraint asset remaining must be >= the denominated constrained
@@ -233,20 +490,30 @@ unsafe fn full_fill<const IS_BUY: bool, const BASE_DENOM: bool>(
     *constraint_asset_remaining = constraint_asset_remaining
         .unchecked_sub(top_order.get_constrained_remaining::<BASE_DENOM>());
 
-    *counter_asset_filled = counter_asset_filled
-        .checked_add(top_order.get_counter_asset_remaining::<BASE_DENOM>())
-        .ok_or(DropsetError::ArithmeticOverflow)?;
+    if !is_self_trade {
+        *counter_asset_filled = counter_asset_filled
+            .checked_add(top_order.get_counter_asset_remaining::<BASE_DENOM>())
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+    }
 
     Ok(())
 }
 
+/// Partially fill the order and return the base and quote amounts filled.
+///
+/// If `is_self_trade` is true, the order belongs to the taker and is only decremented by the
+/// returned amounts: the decremented collateral is returned to the maker's seat and the counter
+/// asset filled isn't updated.
 #[inline(always)]
 fn partial_fill<const IS_BUY: bool, const BASE_DENOM: bool>(
-    ctx: &'_ mut MarketOrderContext<'_>,
+    market_account: &'_ mut MarketAccountView<'_>,
     constraint_asset_remaining: &mut u64,
     counter_asset_filled: &mut u64,
+    maker_rebates: &mut u64,
+    maker_rebate_bps: u16,
     top_order: &OrderSnapshot,
-) -> DropsetResult {
+    is_self_trade: bool,
+) -> Result<(u64, u64), DropsetError> {
     let remaining_constrained_asset_in_top_order =
         dropset_non_zero_u64(top_order.get_constrained_remaining::<BASE_DENOM>())?;
     let remaining_counter_asset_in_top_order =
@@ -259,14 +526,16 @@ fn partial_fill<const IS_BUY: bool, const BASE_DENOM: bool>(
     )?;
 
     // Add the partial fill amount to the total counter asset filled.
-    *counter_asset_filled = counter_asset_filled
-        .checked_add(partial_counter_asset_fill_amount)
-        .ok_or(DropsetError::ArithmeticOverflow)?;
+    if !is_self_trade {
+        *counter_asset_filled = counter_asset_filled
+            .checked_add(partial_counter_asset_fill_amount)
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+    }
 
     let (base_filled, quote_filled) = {
         // Now update the order to reflect the new remaining amounts after the partial fill.
         // Safety: Scoped mutable borrow of the market account data.
-        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        let mut market = unsafe { market_account.load_unchecked_mut() };
 
         // Safety: The order sector index is non-NIL and pointing to a valid order sector.
         let order =
@@ -297,17 +566,33 @@ fn partial_fill<const IS_BUY: bool, const BASE_DENOM: bool>(
     // Update the maker's seat to reflect the partial fill.
     // Safety: The market account data is not currently borrowed and the maker's user seat inside
     // the top order still points to a valid user.
-    unsafe {
-        update_maker_seat_after_fill::<IS_BUY, true>(
-            ctx,
-            top_order.maker_seat_sector,
-            base_filled,
-            quote_filled,
-            top_order.encoded_price,
-        )
-    }?;
+    if is_self_trade {
+        unsafe {
+            update_maker_seat_after_self_trade::<IS_BUY, true>(
+                market_account,
+                top_order.maker_seat_sector,
+                base_filled,
+                quote_filled,
+                top_order.encoded_price,
+            )
+        }?;
+    } else {
+        let rebate = unsafe {
+            update_maker_seat_after_fill::<IS_BUY, true>(
+                market_account,
+                top_order.maker_seat_sector,
+                base_filled,
+                quote_filled,
+                top_order.encoded_price,
+                maker_rebate_bps,
+            )
+        }?;
+        *maker_rebates = maker_rebates
+            .checked_add(rebate)
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+    }
 
-    Ok(())
+    Ok((base_filled, quote_filled))
 }
 
 #[inline(always)]
@@ -320,45 +605,114 @@ fn dropset_non_zero_u64(value: u64) -> Result<NonZeroU64, DropsetError> {
     }
 }
 
+/// Credits the maker's seat with the proceeds of the fill plus their rebate and returns the rebate.
+///
 /// # Safety
 ///
 /// The market account data must not be currently borrowed and the passed order's maker seat sector
 /// index must still point to a valid seat in memory.
 #[inline(always)]
 unsafe fn update_maker_seat_after_fill<const IS_BUY: bool, const PARTIAL_FILL: bool>(
-    ctx: &'_ mut MarketOrderContext<'_>,
+    market_account: &'_ mut MarketAccountView<'_>,
     maker_seat_sector: SectorIndex,
     base_filled: u64,
     quote_filled: u64,
     encoded_price: u32,
-) -> DropsetResult {
+    maker_rebate_bps: u16,
+) -> Result<u64, DropsetError> {
     // Safety: Single, scoped mutable borrow of the market account data.
-    let market = ctx.market_account.load_unchecked_mut();
+    let mut market = market_account.load_unchecked_mut();
     // Safety: The user seat sector index is in-bounds, as it came from the order.
     let sector = unsafe { Sector::from_sector_index_mut(market.sectors, maker_seat_sector) };
     let maker_seat = sector.load_payload_mut::<MarketSeat>();
-    if IS_BUY {
+    let rebate = if IS_BUY {
         // Market buy means a maker's ask got filled, so they receive quote.
         maker_seat.try_increment_quote_available(quote_filled)?;
-
-        // If it's a complete/full fill, remove the order sector index from the price to index map.
-        if !PARTIAL_FILL {
-            maker_seat
-                .user_order_sectors
-                .asks
-                .find_remove(encoded_price)?;
-        }
+        // The taker receives base, so the maker's rebate is paid in base.
+        let rebate = maker_rebate(base_filled, maker_rebate_bps);
+        maker_seat.try_increment_base_available(rebate)?;
+        rebate
     } else {
         // Market sell means a maker's bid got filled, so they receive base.
         maker_seat.try_increment_base_available(base_filled)?;
+        // The taker receives quote, so the maker's rebate is paid in quote.
+        let rebate = maker_rebate(quote_filled, maker_rebate_bps);
+        maker_seat.try_increment_quote_available(rebate)?;
+        rebate
+    };
 
-        // If it's a complete/full fill, remove the order sector index from the price to index map.
-        if !PARTIAL_FILL {
-            maker_seat
-                .user_order_sectors
-                .bids
-                .find_remove(encoded_price)?;
+    // If it's a complete/full fill, remove the order sector index from the price to index map.
+    if !PARTIAL_FILL {
+        // Safety: The maker seat sector index came from the order, so it's a valid seat.
+        unsafe {
+            remove_from_maker_order_sectors::<IS_BUY>(&mut market, maker_seat_sector, encoded_price)
+        }?;
+    }
+
+    Ok(rebate)
+}
+
+/// Removes the filled or self-matched order at `encoded_price` from the maker seat's price -> order
+/// sectors mapping for the side matched by the taker.
+///
+/// # Safety
+///
+/// Caller guarantees `maker_seat_sector` is a valid seat's sector index.
+#[inline(always)]
+unsafe fn remove_from_maker_order_sectors<const IS_BUY: bool>(
+    market: &mut MarketRefMut,
+    maker_seat_sector: SectorIndex,
+    encoded_price: u32,
+) -> DropsetResult {
+    // Safety: Caller guarantees `maker_seat_sector` is a valid seat's sector index.
+    unsafe {
+        if IS_BUY {
+            market
+                .seat_order_sectors::<AskOrders>(maker_seat_sector)
+                .find_remove(encoded_price)
+        } else {
+            market
+                .seat_order_sectors::<BidOrders>(maker_seat_sector)
+                .find_remove(encoded_price)
         }
+    }?;
+
+    Ok(())
+}
+
+/// Returns the collateral decremented from a self-matched order to the maker's seat. Nothing is
+/// exchanged, so there's no rebate.
+///
+/// # Safety
+///
+/// The market account data must not be currently borrowed and the passed order's maker seat sector
+/// index must still point to a valid seat in memory.
+#[inline(always)]
+unsafe fn update_maker_seat_after_self_trade<const IS_BUY: bool, const PARTIAL_FILL: bool>(
+    market_account: &'_ mut MarketAccountView<'_>,
+    maker_seat_sector: SectorIndex,
+    base_decremented: u64,
+    quote_decremented: u64,
+    encoded_price: u32,
+) -> DropsetResult {
+    // Safety: Single, scoped mutable borrow of the market account data.
+    let mut market = market_account.load_unchecked_mut();
+    // Safety: The user seat sector index is in-bounds, as it came from the order.
+    let sector = unsafe { Sector::from_sector_index_mut(market.sectors, maker_seat_sector) };
+    let maker_seat = sector.load_payload_mut::<MarketSeat>();
+    if IS_BUY {
+        // A market buy matches asks, which are collateralized with base.
+        maker_seat.try_increment_base_available(base_decremented)?;
+    } else {
+        // A market sell matches bids, which are collateralized with quote.
+        maker_seat.try_increment_quote_available(quote_decremented)?;
+    }
+
+    if !PARTIAL_FILL {
+        // Safety: The maker seat sector index came from the order, so it's a valid seat.
+        unsafe {
+            remove_from_maker_order_sectors::<IS_BUY>(&mut market, maker_seat_sector, encoded_price)
+        }?;
     }
 
     Ok(())
@@ -370,13 +724,13 @@ unsafe fn update_maker_seat_after_fill<const IS_BUY: bool, const PARTIAL_FILL: b
 /// must still point to a valid seat in memory.
 #[cfg(debug_assertions)]
 unsafe fn ensure_order_has_been_removed<const IS_BUY: bool>(
-    ctx: &'_ MarketOrderContext,
+    market_account: &'_ MarketAccountView,
     top_order: &OrderSnapshot,
 ) {
     use price::LeEncodedPrice;
 
     // Safety: Single, scoped mutable borrow of the market account data.
-    let market = ctx.market_account.load_unchecked();
+    let market = market_account.load_unchecked();
     // Safety: The user seat sector index is in-bounds, as it came from the order.
     let sector = unsafe { Sector::from_sector_index(market.sectors, top_order.maker_seat_sector) };
     let maker_seat = sector.load_payload::<MarketSeat>();
@@ -392,5 +746,9 @@ unsafe fn ensure_order_has_been_removed<const IS_BUY: bool>(
         &maker_seat.user_order_sectors.bids
     };
 
-    debug_assert!({ orders.get(le_encoded_price).is_none() });
+    debug_assert!({
+        // Safety: The maker seat's order sectors belong to this market.
+        unsafe { orders.iter(market.sectors) }
+            .all(|e| e.encoded_price.as_slice() != le_encoded_price.as_slice())
+    });
 }
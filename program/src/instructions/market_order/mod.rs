@@ -1,18 +1,23 @@
 //! See [`process_market_order`].
 
-mod fill_market_order;
-mod mul_div_checked;
+pub mod fill_market_order;
+pub mod mul_div_checked;
 
-// #[cfg(feature = "debug")]
 use dropset_interface::{
     error::DropsetError,
     events::MarketOrderEventInstructionData,
-    instructions::MarketOrderInstructionData,
+    instructions::{
+        MarketOrderInstructionData,
+        SelfTradeBehavior,
+        TimeInForce,
+    },
+    return_data::TakerReturnData,
 };
 use mul_div_checked::mul_div_checked;
 use pinocchio::{
     account::AccountView,
     error::ProgramError,
+    Address,
 };
 
 use crate::{
@@ -25,10 +30,14 @@ use crate::{
         fill_market_order,
         AmountsFilled,
     },
-    shared::token_utils::market_transfers::{
-        deposit_non_zero_to_market,
-        withdraw_non_zero_from_market,
+    shared::{
+        fees::charge_taker_fee,
+        token_utils::market_transfers::{
+            deposit_non_zero_to_market,
+            withdraw_non_zero_from_market,
+        },
     },
+    validation::market_account_view::MarketAccountView,
 };
 
 /// Instruction handler logic for processing a market order.
@@ -41,27 +50,64 @@ use crate::{
 pub unsafe fn process_market_order<'a>(
     accounts: &'a [AccountView],
     instruction_data: &[u8],
-    _event_buffer: &mut EventBuffer,
+    event_buffer: &mut EventBuffer,
 ) -> Result<EventBufferContext<'a>, ProgramError> {
     let MarketOrderInstructionData {
         order_size,
         is_buy,
         is_base,
+        limit_price,
+        min_amount_out,
+        time_in_force,
+        self_trade_behavior,
     } = MarketOrderInstructionData::unpack_untagged(instruction_data)?;
+    let time_in_force = TimeInForce::try_from(time_in_force)?;
+    let self_trade_behavior = SelfTradeBehavior::try_from(self_trade_behavior)?;
 
     // Safety: No account data in `accounts` is currently borrowed.
     let mut ctx = unsafe { MarketOrderContext::load(accounts) }?;
 
-    let AmountsFilled {
-        base: base_filled,
-        quote: quote_filled,
-    } = match (is_buy, is_base) {
-        (false, false) => fill_market_order::<false, false>(&mut ctx, order_size),
-        (true, false) => fill_market_order::<true, false>(&mut ctx, order_size),
-        (false, true) => fill_market_order::<false, true>(&mut ctx, order_size),
-        (true, true) => fill_market_order::<true, true>(&mut ctx, order_size),
+    // Safety: No account data is currently borrowed.
+    let TakerFill {
+        base_filled,
+        quote_filled,
+        amount_received,
+        taker_fee,
+        maker_rebates,
+    } = unsafe {
+        take_from_book(
+            &mut ctx.market_account,
+            ctx.event_authority,
+            event_buffer,
+            ctx.user.address(),
+            order_size,
+            is_buy,
+            is_base,
+            limit_price,
+            time_in_force,
+            self_trade_behavior,
+        )
     }?;
 
+    // A buy means the taker pays quote and receives base, and a sell means the opposite.
+    let (mint_in, amount_filled_in, mint_out) = match is_buy {
+        true => (&ctx.quote_mint, quote_filled, &ctx.base_mint),
+        false => (&ctx.base_mint, base_filled, &ctx.quote_mint),
+    };
+
+    // If the fill stopped early at the limit price or the book ran dry, the amount received may be
+    // less than what the taker is willing to accept. The amount received is net of the transfer fee
+    // withheld from the withdrawal, if the received mint has one.
+    // Safety: No account data is currently borrowed.
+    if unsafe { mint_out.post_fee_amount(amount_received) }? < min_amount_out {
+        return Err(DropsetError::SlippageExceeded.into());
+    }
+
+    // The taker transfers enough for the market to receive the full amount filled after any
+    // transfer fee is withheld.
+    // Safety: No account data is currently borrowed.
+    let amount_in = unsafe { mint_in.pre_fee_amount(amount_filled_in) }?;
+
     // Try to transfer the taker side's tokens to the market account.
     // Safety: No account data is currently borrowed.
     let (taker_amount_filled, taker_amount_deposited) = unsafe {
@@ -72,7 +118,7 @@ pub unsafe fn process_market_order<'a>(
                 &ctx.quote_market_ata,
                 ctx.user,
                 &ctx.quote_mint,
-                quote_filled,
+                amount_in,
             )?;
 
             // And receives base.
@@ -81,7 +127,7 @@ pub unsafe fn process_market_order<'a>(
                 &ctx.base_market_ata,
                 &ctx.market_account,
                 &ctx.base_mint,
-                base_filled,
+                amount_received,
             )?;
 
             (quote_filled, quote_transferred)
@@ -92,7 +138,7 @@ pub unsafe fn process_market_order<'a>(
                 &ctx.base_market_ata,
                 ctx.user,
                 &ctx.base_mint,
-                base_filled,
+                amount_in,
             )?;
 
             // And receives quote.
@@ -101,33 +147,175 @@ pub unsafe fn process_market_order<'a>(
                 &ctx.quote_market_ata,
                 &ctx.market_account,
                 &ctx.quote_mint,
-                quote_filled,
+                amount_received,
             )?;
 
             (base_filled, base_transferred)
         }
     };
 
-    // Ensure that the order size matches the exact amount transferred.
-    if taker_amount_filled != taker_amount_deposited {
+    // Ensure the market received at least the amount filled. Rounding up the amount transferred
+    // for a transfer fee can leave the market with at most a dust amount more than the amount
+    // filled.
+    let Some(excess_deposited) = taker_amount_deposited.checked_sub(taker_amount_filled) else {
         return Err(DropsetError::AmountFilledVsTransferredMismatch.into());
+    };
+
+    // The taker doesn't necessarily have a seat, so any excess is accrued as fees rather than left
+    // in the market token account untracked.
+    if excess_deposited > 0 {
+        // Safety: Scoped mutable borrow of the market account data to accrue the excess.
+        let market = unsafe { ctx.market_account.load_unchecked_mut() };
+        if is_buy {
+            market.header.try_accrue_quote_fees(excess_deposited)?;
+        } else {
+            market.header.try_accrue_base_fees(excess_deposited)?;
+        }
     }
 
-    // #[cfg(feature = "debug")]
-    _event_buffer.add_to_buffer(
+    event_buffer.add_to_buffer(
         MarketOrderEventInstructionData::new(
             order_size,
             is_buy,
             is_base,
             base_filled,
             quote_filled,
+            time_in_force as u8,
+            taker_fee,
+            maker_rebates,
         ),
         ctx.event_authority,
         ctx.market_account.clone(),
     )?;
 
+    event_buffer.taker_return_data = Some(TakerReturnData {
+        base_filled,
+        quote_filled,
+        taker_fee,
+    });
+
     Ok(EventBufferContext {
         event_authority: ctx.event_authority,
         market_account: ctx.market_account,
     })
 }
+
+/// The amounts filled by a taker order and the fees charged on them.
+pub struct TakerFill {
+    pub base_filled: u64,
+    pub quote_filled: u64,
+    /// The amount the taker receives after the taker fee; base for a buy and quote for a sell.
+    pub amount_received: u64,
+    pub taker_fee: u64,
+    pub maker_rebates: u64,
+}
+
+/// Fills a taker order against the book, enforces its time-in-force policy and charges the taker
+/// fee. Settling the taker's side of the fill is left to the caller.
+///
+/// # Safety
+///
+/// Caller guarantees the market account data is not currently borrowed in any capacity.
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+pub unsafe fn take_from_book<'a>(
+    market_account: &'_ mut MarketAccountView<'a>,
+    event_authority: &'a AccountView,
+    event_buffer: &mut EventBuffer,
+    taker: &Address,
+    order_size: u64,
+    is_buy: bool,
+    is_base: bool,
+    limit_price: u32,
+    time_in_force: TimeInForce,
+    self_trade_behavior: SelfTradeBehavior,
+) -> Result<TakerFill, ProgramError> {
+    let maker_rebate_bps = {
+        // Safety: Scoped borrow of the market account data to check its status and read the fee
+        // schedule.
+        let market = unsafe { market_account.load_unchecked() };
+        market.header.status()?.check_allows_new_orders()?;
+        market.header.maker_rebate_bps()
+    };
+
+    // Safety: The market account data borrow above is dropped.
+    let AmountsFilled {
+        base: base_filled,
+        quote: quote_filled,
+        maker_rebates,
+        self_trade_decremented,
+    } = unsafe {
+        match (is_buy, is_base) {
+            (false, false) => fill_market_order::<false, false>(
+                market_account,
+                event_authority,
+                event_buffer,
+                order_size,
+                limit_price,
+                maker_rebate_bps,
+                taker,
+                self_trade_behavior,
+            ),
+            (true, false) => fill_market_order::<true, false>(
+                market_account,
+                event_authority,
+                event_buffer,
+                order_size,
+                limit_price,
+                maker_rebate_bps,
+                taker,
+                self_trade_behavior,
+            ),
+            (false, true) => fill_market_order::<false, true>(
+                market_account,
+                event_authority,
+                event_buffer,
+                order_size,
+                limit_price,
+                maker_rebate_bps,
+                taker,
+                self_trade_behavior,
+            ),
+            (true, true) => fill_market_order::<true, true>(
+                market_account,
+                event_authority,
+                event_buffer,
+                order_size,
+                limit_price,
+                maker_rebate_bps,
+                taker,
+                self_trade_behavior,
+            ),
+        }
+    }?;
+
+    // An immediate-or-cancel order simply drops whatever wasn't filled, but a fill-or-kill order
+    // must fill its entire order size. Size decremented by self trades was matched against the
+    // book just like a fill, so it counts toward the order size, the same way it's consumed from
+    // a crossing `PostOrder`.
+    if time_in_force == TimeInForce::FillOrKill {
+        let constrained_filled = if is_base { base_filled } else { quote_filled };
+        let constrained_matched = constrained_filled
+            .checked_add(self_trade_decremented)
+            .ok_or(DropsetError::ArithmeticOverflow)?;
+        if constrained_matched != order_size {
+            return Err(DropsetError::FillOrKillNotFilled.into());
+        }
+    }
+
+    // The taker receives base on a buy and quote on a sell, minus the taker fee.
+    let amount_out = if is_buy { base_filled } else { quote_filled };
+    let (amount_received, taker_fee) = {
+        // Safety: Scoped mutable borrow of the market account data to accrue the fees.
+        let market = unsafe { market_account.load_unchecked_mut() };
+        charge_taker_fee(market.header, is_buy, amount_out, maker_rebates)
+    }?;
+
+    Ok(TakerFill {
+        base_filled,
+        quote_filled,
+        amount_received,
+        taker_fee,
+        maker_rebates,
+    })
+}
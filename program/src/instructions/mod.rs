@@ -3,24 +3,56 @@
 //! Routes decoded instructions to their corresponding handlers and encapsulates all
 //! on-chain logic for each supported operation.
 
+pub mod amend_order;
 pub mod batch_replace;
+pub mod cancel_all_orders;
 pub mod cancel_order;
+pub mod cancel_order_by_client_id;
+pub mod close_empty_seats;
+pub mod close_market;
 pub mod close_seat;
+pub mod collect_fees;
+pub mod compact_market;
 pub mod deposit;
+pub mod deposit_both;
+pub mod deposit_for;
 pub mod expand_market;
 pub mod flush_events;
 pub mod market_order;
 pub mod post_order;
+pub mod prune_expired_orders;
 pub mod register_market;
+pub mod seat_market_order;
+pub mod set_market_status;
+pub mod set_seat_delegate;
+pub mod settle_and_close_seat;
+pub mod update_market_config;
 pub mod withdraw;
+pub mod withdraw_both;
 
+pub use amend_order::process_amend_order;
 pub use batch_replace::process_batch_replace;
+pub use cancel_all_orders::process_cancel_all_orders;
 pub use cancel_order::process_cancel_order;
+pub use cancel_order_by_client_id::process_cancel_order_by_client_id;
+pub use close_empty_seats::process_close_empty_seats;
+pub use close_market::process_close_market;
 pub use close_seat::process_close_seat;
+pub use collect_fees::process_collect_fees;
+pub use compact_market::process_compact_market;
 pub use deposit::process_deposit;
+pub use deposit_both::process_deposit_both;
+pub use deposit_for::process_deposit_for;
 pub use expand_market::process_expand_market;
 pub use flush_events::process_flush_events;
 pub use market_order::process_market_order;
 pub use post_order::process_post_order;
+pub use prune_expired_orders::process_prune_expired_orders;
 pub use register_market::process_register_market;
+pub use seat_market_order::process_seat_market_order;
+pub use set_market_status::process_set_market_status;
+pub use set_seat_delegate::process_set_seat_delegate;
+pub use settle_and_close_seat::process_settle_and_close_seat;
+pub use update_market_config::process_update_market_config;
 pub use withdraw::process_withdraw;
+pub use withdraw_both::process_withdraw_both;
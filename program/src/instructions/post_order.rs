@@ -1,10 +1,19 @@
 //! See [`process_post_order`].
 
-#[cfg(feature = "debug")]
-use dropset_interface::events::PostOrderEventInstructionData;
+use core::num::NonZeroU64;
+
 use dropset_interface::{
-    error::DropsetError,
-    instructions::PostOrderInstructionData,
+    error::{
+        DropsetError,
+        DropsetResult,
+    },
+    events::PostOrderEventInstructionData,
+    instructions::{
+        ExpiryKind,
+        PostOrderInstructionData,
+        SelfTradeBehavior,
+    },
+    return_data::TakerReturnData,
     state::{
         asks_dll::AskOrders,
         bids_dll::BidOrders,
@@ -14,16 +23,24 @@ use dropset_interface::{
             OrdersCollection,
         },
         sector::{
-            Sector,
             SectorIndex,
+            NIL,
         },
     },
 };
 use pinocchio::{
     account::AccountView,
     error::ProgramError,
+    sysvars::{
+        clock::Clock,
+        Sysvar,
+    },
+};
+use price::{
+    to_order_info,
+    LeEncodedPrice,
+    OrderInfo,
 };
-use price::to_order_info;
 
 use crate::{
     context::{
@@ -31,14 +48,41 @@ use crate::{
         EventBufferContext,
     },
     events::EventBuffer,
+    instructions::market_order::{
+        fill_market_order::{
+            fill_market_order,
+            AmountsFilled,
+        },
+        mul_div_checked::mul_div_checked,
+    },
     shared::{
+        fees::charge_taker_fee,
         order_operations::insert_order,
-        seat_operations::load_mut_seat_with_hint,
+        seat_operations::{
+            load_mut_seat_with_hint,
+            load_seat_user_for_signer,
+            load_seat_with_hint_unchecked,
+        },
     },
 };
 
 /// Instruction handler logic for posting a user's bid or ask order on the market's order book.
 ///
+/// Post-only orders are rejected if they would cross the book. Otherwise, the order first fills
+/// against crossing orders, settles the fill against the user's seat balances and then posts any
+/// remainder to the book.
+///
+/// A posted order with an expiry can no longer be filled once its expiry has been reached.
+///
+/// Unless the order is post-only, the amounts filled by its crossing portion are set as the
+/// instruction's [`TakerReturnData`].
+///
+/// If the order crosses one of the user's own orders, the order's self-trade behavior determines
+/// whether the instruction fails, the resting order is canceled, or both orders are decremented.
+///
+/// If the optional expansion payer is passed and the market has no free sector for the posted
+/// order, the market account is expanded by a single sector funded by the payer.
+///
 /// # Safety
 ///
 /// Caller upholds the safety contract detailed in
@@ -47,91 +91,312 @@ use crate::{
 pub unsafe fn process_post_order<'a>(
     accounts: &'a [AccountView],
     instruction_data: &[u8],
-    _event_buffer: &mut EventBuffer,
+    event_buffer: &mut EventBuffer,
 ) -> Result<EventBufferContext<'a>, ProgramError> {
     let PostOrderInstructionData {
         order_info_args,
         is_bid,
         user_sector_index_hint,
+        post_only,
+        expiry,
+        self_trade_behavior,
+        client_order_id,
     } = PostOrderInstructionData::unpack_untagged(instruction_data)?;
+    let self_trade_behavior = SelfTradeBehavior::try_from(self_trade_behavior)?;
+
+    // The clock is also needed to skip expired orders when checking whether the order crosses.
+    let clock = Clock::get()?;
+    if expiry.kind != ExpiryKind::None as u8 {
+        expiry.validate(clock.slot, clock.unix_timestamp)?;
+    }
 
     // Safety: No account data in `accounts` is currently borrowed.
     let mut ctx = unsafe { PostOrderContext::load(accounts) }?;
 
     let order_info = to_order_info(order_info_args).map_err(DropsetError::from)?;
 
-    let (base_atoms, quote_atoms) = (order_info.base_atoms, order_info.quote_atoms);
+    // The signer may be the seat's user or its delegate, but the order always belongs to the user.
+    let user = {
+        // Safety: Scoped borrow of the market account data to check its status and config.
+        let market = unsafe { ctx.market_account.load_unchecked() };
+        market.header.status()?.check_allows_new_orders()?;
+        market
+            .header
+            .check_order_against_config(&order_info.encoded_price, order_info.base_atoms)?;
+        load_seat_user_for_signer(&market, user_sector_index_hint, ctx.user)?
+    };
+
+    // If the order isn't post-only, first fill it against any crossing orders on the opposite side
+    // of the book, exactly like a base-denominated market order with the order's price as the
+    // limit.
+    let AmountsFilled {
+        base: base_filled,
+        quote: quote_filled,
+        maker_rebates,
+        self_trade_decremented,
+    } = if post_only {
+        AmountsFilled {
+            base: 0,
+            quote: 0,
+            maker_rebates: 0,
+            self_trade_decremented: 0,
+        }
+    } else {
+        let base_atoms = order_info.base_atoms;
+        let limit_price = order_info.encoded_price.as_u32();
+        let market_account = &mut ctx.market_account;
+        let event_authority = ctx.event_authority;
+        let user = &user;
+        // Safety: The market account is currently not borrowed in any capacity.
+        unsafe {
+            let rebate_bps = market_account.load_unchecked().header.maker_rebate_bps();
+            if is_bid {
+                fill_market_order::<true, true>(
+                    market_account,
+                    event_authority,
+                    event_buffer,
+                    base_atoms,
+                    limit_price,
+                    rebate_bps,
+                    user,
+                    self_trade_behavior,
+                )
+            } else {
+                fill_market_order::<false, true>(
+                    market_account,
+                    event_authority,
+                    event_buffer,
+                    base_atoms,
+                    limit_price,
+                    rebate_bps,
+                    user,
+                    self_trade_behavior,
+                )
+            }
+        }?
+    };
+
+    // Any amount decremented by a self trade is dropped from the order along with the amount
+    // filled, so neither is posted.
+    let base_consumed = base_filled
+        .checked_add(self_trade_decremented)
+        .ok_or(DropsetError::ArithmeticOverflow)?;
+    let (base_remaining, quote_remaining) = remaining_after_fill(&order_info, base_consumed)?;
+
+    // The full order was checked against the market config up front, but a partially filled
+    // remainder may have dropped below the minimum order size. Such a remainder is dropped instead
+    // of posted, just like one too small to be represented in both base and quote, and its size is
+    // reported in the event.
+    let posts_remainder = base_remaining != 0
+        && quote_remaining != 0
+        && (base_consumed == 0 || {
+            // Safety: Scoped borrow of the market account data to check the remainder.
+            let market = unsafe { ctx.market_account.load_unchecked() };
+            market
+                .header
+                .check_order_against_config(&order_info.encoded_price, base_remaining)
+                .is_ok()
+        });
+    let (base_atoms, quote_atoms, base_dropped) = if posts_remainder {
+        (base_remaining, quote_remaining, 0)
+    } else {
+        (0, 0, base_remaining)
+    };
 
     // To avoid convoluted borrow checking rules, optimistically insert the order with the index
     // hint passed in, assuming it's valid. It's verified later when mutating the market seat.
-    let order = Order::new(order_info, user_sector_index_hint);
+    let mut order = Order::new_with_expiry(
+        OrderInfo {
+            encoded_price: order_info.encoded_price,
+            base_atoms,
+            quote_atoms,
+        },
+        user_sector_index_hint,
+        expiry,
+    );
+    order.set_client_order_id(client_order_id);
     let le_encoded_price = *order.le_encoded_price();
 
+    if let Some(payer) = ctx.expansion_payer {
+        // A posted remainder needs a free sector for the order and, if the user's inline order
+        // sectors are full, another one for the side's overflow order sectors.
+        let num_sectors_needed = if posts_remainder {
+            // Safety: Scoped immutable borrow of the market account, checks the free sectors.
+            let market = unsafe { ctx.market_account.load_unchecked() };
+            // Safety: The index hint was verified as in-bounds when the seat's user was loaded.
+            let seat = unsafe { load_seat_with_hint_unchecked(&market, user_sector_index_hint) };
+            let order_sectors = if is_bid {
+                &seat.user_order_sectors.bids
+            } else {
+                &seat.user_order_sectors.asks
+            };
+            let num_sectors = 1 + order_sectors.needs_overflow_sector() as u32;
+            num_sectors.saturating_sub(market.header.num_free_sectors())
+        } else {
+            0
+        };
+
+        if num_sectors_needed != 0 {
+            // Safety: Scoped mutable borrow to resize the market account and add the new sectors.
+            unsafe { ctx.market_account.resize(payer, num_sectors_needed as u16) }?;
+        }
+    }
+
     // Safety: The market account is currently not borrowed in any capacity.
     let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
 
-    let order_sector_index = {
+    // The crossing portion of the order pays the taker fee on the asset it receives.
+    let amount_out = if is_bid { base_filled } else { quote_filled };
+    let (amount_received, taker_fee) =
+        charge_taker_fee(&mut *market.header, is_bid, amount_out, maker_rebates)?;
+
+    // Only post the remainder if the order wasn't completely filled and it wasn't dropped above.
+    let order_sector_index = if posts_remainder {
         if is_bid {
-            post_only_check_and_insert_order::<BidOrders>(&mut market, order)
+            post_only_check_and_insert_order::<BidOrders>(&mut market, order, &clock)
         } else {
-            post_only_check_and_insert_order::<AskOrders>(&mut market, order)
-        }
-    }?;
+            post_only_check_and_insert_order::<AskOrders>(&mut market, order, &clock)
+        }?
+    } else {
+        NIL
+    };
 
-    Sector::check_in_bounds(market.sectors, user_sector_index_hint)?;
-    // Find and verify the user's seat with the given index hint.
-    // Safety: The index hint was just verified as in-bounds.
-    let user_seat =
-        load_mut_seat_with_hint(&mut market, user_sector_index_hint, ctx.user.address())?;
+    let max_orders_per_user = market.header.max_orders_per_user() as usize;
 
-    let order_sector_index_bytes = order_sector_index.to_le_bytes();
+    // Find and verify the user's seat with the given index hint.
+    // Safety: The index hint was verified as in-bounds when the seat's user was loaded, and the
+    // market account can only grow since then.
+    let user_seat = load_mut_seat_with_hint(&mut market, user_sector_index_hint, &user)?;
 
-    // 1. Check that the user has enough collateral to place the order and update their seat with
+    // 1. Settle any filled amounts against the user's seat balances.
+    // 2. Check that the user has enough collateral to place the order and update their seat with
     //    the resulting decremented amount.
-    // 2. Update the user seat's mapped order sectors. This also checks for duplicate prices so that
-    //    all of a user's orders have a unique price.
     if is_bid {
-        // 1. If the user is posting a bid, they intend to provide quote and receive base.
-        user_seat.try_decrement_quote_available(quote_atoms)?;
-        // 2. Add the order to the user's bids.
-        user_seat
-            .user_order_sectors
-            .bids
-            .add(&le_encoded_price, &order_sector_index_bytes)?;
+        // 1. A crossing bid pays quote for the base it receives, net of the taker fee.
+        user_seat.try_decrement_quote_available(quote_filled)?;
+        user_seat.try_increment_base_available(amount_received)?;
+        if order_sector_index != NIL {
+            // 2. If the user is posting a bid, they intend to provide quote and receive base.
+            user_seat.try_decrement_quote_available(quote_atoms)?;
+        }
     } else {
-        // 1. If the user is posting an ask, they intend to provide base and receive quote.
-        user_seat.try_decrement_base_available(base_atoms)?;
-        // 2. Add the order to the user's asks.
-        user_seat
-            .user_order_sectors
-            .asks
-            .add(&le_encoded_price, &order_sector_index_bytes)?;
+        // 1. A crossing ask pays base for the quote it receives, net of the taker fee.
+        user_seat.try_decrement_base_available(base_filled)?;
+        user_seat.try_increment_quote_available(amount_received)?;
+        if order_sector_index != NIL {
+            // 2. If the user is posting an ask, they intend to provide base and receive quote.
+            user_seat.try_decrement_base_available(base_atoms)?;
+        }
     }
 
-    #[cfg(feature = "debug")]
-    _event_buffer.add_to_buffer(
+    // 3. Add the order to the user seat's mapped order sectors. This also checks for duplicate
+    //    prices so that all of a user's orders have a unique price.
+    if order_sector_index != NIL {
+        // Safety: The user's seat was just verified at the index hint.
+        unsafe {
+            if is_bid {
+                add_to_seat_order_sectors::<BidOrders>(
+                    &mut market,
+                    user_sector_index_hint,
+                    max_orders_per_user,
+                    &le_encoded_price,
+                    order_sector_index,
+                )
+            } else {
+                add_to_seat_order_sectors::<AskOrders>(
+                    &mut market,
+                    user_sector_index_hint,
+                    max_orders_per_user,
+                    &le_encoded_price,
+                    order_sector_index,
+                )
+            }
+        }?;
+    }
+
+    event_buffer.add_to_buffer(
         PostOrderEventInstructionData::new(
             is_bid,
             user_sector_index_hint,
             order_sector_index,
             base_atoms,
             quote_atoms,
+            base_filled,
+            quote_filled,
+            taker_fee,
+            base_dropped,
+            client_order_id,
         ),
         ctx.event_authority,
         ctx.market_account.clone(),
     )?;
 
+    // An order that could take liquidity reports what filled, like a market order does.
+    if !post_only {
+        event_buffer.taker_return_data = Some(TakerReturnData {
+            base_filled,
+            quote_filled,
+            taker_fee,
+        });
+    }
+
     Ok(EventBufferContext {
         event_authority: ctx.event_authority,
         market_account: ctx.market_account,
     })
 }
 
+/// Returns the base and quote atoms left in the order after `base_consumed` base atoms were filled
+/// against crossing orders or decremented by self trades. The quote remaining is scaled down
+/// proportionally, the same way a partially filled maker order is.
+#[inline(always)]
+fn remaining_after_fill(
+    order_info: &OrderInfo,
+    base_consumed: u64,
+) -> Result<(u64, u64), DropsetError> {
+    if base_consumed == 0 {
+        return Ok((order_info.base_atoms, order_info.quote_atoms));
+    }
+
+    let base_remaining = order_info
+        .base_atoms
+        .checked_sub(base_consumed)
+        .ok_or(DropsetError::ArithmeticUnderflow)?;
+    let base_atoms =
+        NonZeroU64::new(order_info.base_atoms).ok_or(DropsetError::AmountCannotBeZero)?;
+    let quote_remaining = mul_div_checked(base_remaining, order_info.quote_atoms, base_atoms)?;
+
+    Ok((base_remaining, quote_remaining))
+}
+
+/// Adds the posted order to the seat's `Side` order sectors if the user has fewer than the market's
+/// max orders per user.
+///
+/// # Safety
+///
+/// Caller guarantees `seat_index` is a valid seat's sector index.
+#[inline(always)]
+unsafe fn add_to_seat_order_sectors<Side: OrdersCollection>(
+    market: &mut MarketRefMut,
+    seat_index: SectorIndex,
+    max_orders_per_user: usize,
+    le_encoded_price: &LeEncodedPrice,
+    order_sector_index: SectorIndex,
+) -> DropsetResult {
+    // Safety: Caller guarantees `seat_index` is a valid seat's sector index.
+    let mut order_sectors = unsafe { market.seat_order_sectors::<Side>(seat_index) };
+    if order_sectors.num_orders() >= max_orders_per_user {
+        return Err(DropsetError::UserHasMaxOrders);
+    }
+    order_sectors.add(le_encoded_price, &order_sector_index.to_le_bytes())
+}
+
 fn post_only_check_and_insert_order<T: OrdersCollection>(
     market: &mut MarketRefMut,
     new_order: Order,
+    clock: &Clock,
 ) -> Result<SectorIndex, DropsetError> {
-    T::post_only_crossing_check(&new_order, market)?;
+    T::post_only_crossing_check(&new_order, market, clock.slot, clock.unix_timestamp)?;
     let orders = market.orders::<T>();
     let next_index = T::find_new_order_next_index(orders.iter(), &new_order);
     insert_order(next_index, &mut market.orders::<T>(), new_order)
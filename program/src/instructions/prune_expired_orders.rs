@@ -0,0 +1,165 @@
+//! See [`process_prune_expired_orders`].
+
+use dropset_interface::{
+    events::ExpireOrderEventInstructionData,
+    instructions::PruneExpiredOrdersInstructionData,
+    state::{
+        asks_dll::AskOrders,
+        bids_dll::BidOrders,
+        linked_list::LinkedListHeaderOperations,
+        order::{
+            Order,
+            OrdersCollection,
+        },
+        sector::{
+            Sector,
+            NIL,
+        },
+    },
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{
+        clock::Clock,
+        Sysvar,
+    },
+};
+
+use crate::{
+    context::{
+        prune_expired_orders_context::PruneExpiredOrdersContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::order_operations::remove_order_and_refund_maker,
+};
+
+/// The max number of orders visited across both sides of the book, expired or not. Orders are
+/// sorted by price rather than expiry, so without this bound a book full of unexpired orders would
+/// be walked in its entirety no matter how small `max_orders` is.
+const MAX_ORDERS_VISITED: u16 = 256;
+
+/// Instruction handler logic for permissionlessly removing expired orders from both sides of the
+/// market's order book and returning their collateral to each maker's seat.
+///
+/// At most `max_orders` expired orders are removed, bids first and then asks, and at most
+/// [`MAX_ORDERS_VISITED`] orders are visited in total.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::PruneExpiredOrders`].
+#[inline(never)]
+pub unsafe fn process_prune_expired_orders<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let PruneExpiredOrdersInstructionData { max_orders } =
+        PruneExpiredOrdersInstructionData::unpack_untagged(instruction_data)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { PruneExpiredOrdersContext::load(accounts) }?;
+
+    {
+        // Safety: Scoped borrow of the market account data to check its status.
+        let market = unsafe { ctx.market_account.load_unchecked() };
+        market.header.status()?.check_allows_cancels()?;
+    }
+
+    let clock = Clock::get()?;
+    let mut visits_remaining = MAX_ORDERS_VISITED;
+
+    // Safety: The market account data isn't currently borrowed.
+    let bids_pruned = unsafe {
+        prune_expired_orders_on_side::<BidOrders>(
+            &mut ctx,
+            event_buffer,
+            &clock,
+            true,
+            max_orders,
+            &mut visits_remaining,
+        )
+    }?;
+    // Safety: The market account data isn't currently borrowed.
+    unsafe {
+        prune_expired_orders_on_side::<AskOrders>(
+            &mut ctx,
+            event_buffer,
+            &clock,
+            false,
+            max_orders - bids_pruned,
+            &mut visits_remaining,
+        )
+    }?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
+
+/// Walks one side of the book from the top, removing up to `max_orders` expired orders and adding
+/// an [`ExpireOrderEventInstructionData`] to the event buffer for each one. The walk stops early
+/// once `visits_remaining` orders have been visited, decrementing it for each order visited.
+///
+/// Returns the number of orders removed.
+///
+/// # Safety
+///
+/// The market account data must not be currently borrowed.
+#[inline(always)]
+unsafe fn prune_expired_orders_on_side<Side: OrdersCollection>(
+    ctx: &mut PruneExpiredOrdersContext,
+    event_buffer: &mut EventBuffer,
+    clock: &Clock,
+    is_bid: bool,
+    max_orders: u16,
+    visits_remaining: &mut u16,
+) -> Result<u16, ProgramError> {
+    let mut num_pruned: u16 = 0;
+
+    // Safety: Scoped borrow of the market account data to read the top of book.
+    let mut sector_index = Side::head(unsafe { ctx.market_account.load_unchecked() }.header);
+
+    while sector_index != NIL && num_pruned < max_orders && *visits_remaining > 0 {
+        *visits_remaining -= 1;
+        let (next_index, expired_order) = {
+            // Safety: Scoped mutable borrow of the market account data.
+            let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+            // Safety: `sector_index` is a non-NIL index from the orders collection.
+            let sector = unsafe { Sector::from_sector_index(market.sectors, sector_index) };
+            // Read the next index before the sector is removed from the list.
+            let next_index = sector.next();
+            let order = sector.load_payload::<Order>();
+
+            if order.is_expired(clock.slot, clock.unix_timestamp) {
+                let event = ExpireOrderEventInstructionData::new(
+                    is_bid,
+                    order.user_seat(),
+                    sector_index,
+                    order.encoded_price().as_u32(),
+                    order.base_remaining(),
+                    order.quote_remaining(),
+                );
+                // Safety: `sector_index` points to a valid order in the `Side` orders collection.
+                unsafe { remove_order_and_refund_maker::<Side>(&mut market, sector_index) }?;
+                (next_index, Some(event))
+            } else {
+                (next_index, None)
+            }
+        };
+
+        // Emit the event after the market data borrow above is dropped, since adding to the event
+        // buffer may flush it.
+        if let Some(event) = expired_order {
+            event_buffer.add_to_buffer(event, ctx.event_authority, ctx.market_account.clone())?;
+            num_pruned += 1;
+        }
+
+        sector_index = next_index;
+    }
+
+    Ok(num_pruned)
+}
@@ -9,6 +9,7 @@ use dropset_interface::{
         sector::SECTOR_SIZE,
         transmutable::Transmutable,
     },
+    utils::owned_by,
 };
 use pinocchio::{
     account::AccountView,
@@ -28,12 +29,18 @@ use crate::{
     events::EventBuffer,
     market_seeds,
     market_signer,
-    shared::market_operations::initialize_market_account_data,
+    shared::{
+        market_operations::initialize_market_account_data,
+        token_utils::token_2022_extensions::check_supported_mint_extensions,
+    },
     validation::market_account_view::MarketAccountView,
 };
 
 /// Instruction handler logic for initializing a new market account and its header metadata.
 ///
+/// `spl_token_2022` mints are rejected if they have an extension the market can't support, such as
+/// a transfer hook. Transfer fees are supported.
+///
 /// # Safety
 ///
 /// Caller upholds the safety contract detailed in
@@ -44,13 +51,32 @@ pub unsafe fn process_register_market<'a>(
     instruction_data: &[u8],
     event_buffer: &mut EventBuffer,
 ) -> Result<EventBufferContext<'a>, ProgramError> {
-    let num_sectors = RegisterMarketInstructionData::unpack_untagged(instruction_data)?.num_sectors;
+    let RegisterMarketInstructionData {
+        num_sectors,
+        market_id,
+        fee_recipient,
+        taker_fee_bps,
+        maker_rebate_bps,
+        authority,
+        min_order_base_atoms,
+        price_mantissa_tick,
+        max_orders_per_user,
+    } = RegisterMarketInstructionData::unpack_untagged(instruction_data)?;
     let ctx = RegisterMarketContext::load(accounts)?;
 
+    // Reject `spl_token_2022` mints with extensions the market can't support before creating any
+    // accounts. The mints' owners are validated later in the ATA `Create` instruction.
+    for mint in [ctx.base_mint, ctx.quote_mint] {
+        if owned_by(mint, &pinocchio_token_2022::ID) {
+            // Safety: Scoped immutable borrow of the mint account data.
+            check_supported_mint_extensions(unsafe { mint.borrow_unchecked() })?;
+        }
+    }
+
     // It's not necessary to check the returned PDA here because `CreateAccount` will fail if the
     // market account info's address doesn't match.
     let (_pda, market_bump) = Address::try_find_program_address(
-        market_seeds!(ctx.base_mint.address(), ctx.quote_mint.address()),
+        market_seeds!(ctx.base_mint.address(), ctx.quote_mint.address(), market_id),
         &crate::ID,
     )
     .ok_or(DropsetError::AddressDerivationFailed)?;
@@ -70,6 +96,7 @@ pub unsafe fn process_register_market<'a>(
     .invoke_signed(&[market_signer!(
         ctx.base_mint.address(),
         ctx.quote_mint.address(),
+        market_id,
         market_bump
     )])?;
 
@@ -95,19 +122,29 @@ pub unsafe fn process_register_market<'a>(
     }
     .invoke()?;
 
-    initialize_market_account_data(
+    let mut market = initialize_market_account_data(
         // Safety: Scoped mutable borrow of the market account data to initialize it.
         unsafe { ctx.market_account.account.borrow_unchecked_mut() },
         ctx.base_mint.address(),
         ctx.quote_mint.address(),
         market_bump,
+        market_id,
+    )?;
+    market
+        .header
+        .set_fee_schedule(&fee_recipient, taker_fee_bps, maker_rebate_bps)?;
+    market.header.authority = authority;
+    market.header.set_market_config(
+        min_order_base_atoms,
+        price_mantissa_tick,
+        max_orders_per_user,
     )?;
 
     // Safety: `ctx.market_account.account` was just initialized as a market account.
     let market_account = unsafe { MarketAccountView::new_unchecked(ctx.market_account.account) };
 
     event_buffer.add_to_buffer(
-        RegisterMarketEventInstructionData::new(*market_account.account().address()),
+        RegisterMarketEventInstructionData::new(*market_account.account().address(), market_id),
         ctx.event_authority,
         market_account.clone(),
     )?;
@@ -0,0 +1,136 @@
+//! See [`process_seat_market_order`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::MarketOrderEventInstructionData,
+    instructions::{
+        SeatMarketOrderInstructionData,
+        SelfTradeBehavior,
+        TimeInForce,
+    },
+    return_data::TakerReturnData,
+    state::sector::Sector,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        seat_market_order_context::SeatMarketOrderContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    instructions::market_order::{
+        take_from_book,
+        TakerFill,
+    },
+    shared::seat_operations::load_mut_seat_with_hint,
+};
+
+/// Instruction handler logic for processing a market order that's settled against the taker's seat
+/// balances instead of their token accounts.
+///
+/// The taker's seat is debited the amount filled on the side they pay and credited the amount
+/// received after the taker fee on the side they receive, so no token transfers are made.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::SeatMarketOrder`].
+#[inline(never)]
+pub unsafe fn process_seat_market_order<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let SeatMarketOrderInstructionData {
+        order_size,
+        is_buy,
+        is_base,
+        limit_price,
+        min_amount_out,
+        time_in_force,
+        self_trade_behavior,
+        user_sector_index_hint,
+    } = SeatMarketOrderInstructionData::unpack_untagged(instruction_data)?;
+    let time_in_force = TimeInForce::try_from(time_in_force)?;
+    let self_trade_behavior = SelfTradeBehavior::try_from(self_trade_behavior)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { SeatMarketOrderContext::load(accounts) }?;
+
+    // Safety: No account data is currently borrowed.
+    let TakerFill {
+        base_filled,
+        quote_filled,
+        amount_received,
+        taker_fee,
+        maker_rebates,
+    } = unsafe {
+        take_from_book(
+            &mut ctx.market_account,
+            ctx.event_authority,
+            event_buffer,
+            ctx.user.address(),
+            order_size,
+            is_buy,
+            is_base,
+            limit_price,
+            time_in_force,
+            self_trade_behavior,
+        )
+    }?;
+
+    // If the fill stopped early at the limit price or the book ran dry, the amount received may be
+    // less than what the taker is willing to accept.
+    if amount_received < min_amount_out {
+        return Err(DropsetError::SlippageExceeded.into());
+    }
+
+    {
+        // Safety: Scoped mutable borrow of the market account data to settle the taker's seat.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        Sector::check_in_bounds(market.sectors, user_sector_index_hint)?;
+        // Safety: The user sector index hint was just verified as in-bounds.
+        let seat = unsafe {
+            load_mut_seat_with_hint(&mut market, user_sector_index_hint, ctx.user.address())
+        }?;
+
+        // A buy means the taker's seat pays quote and receives base, and a sell means the opposite.
+        if is_buy {
+            seat.try_decrement_quote_available(quote_filled)?;
+            seat.try_increment_base_available(amount_received)?;
+        } else {
+            seat.try_decrement_base_available(base_filled)?;
+            seat.try_increment_quote_available(amount_received)?;
+        }
+    }
+
+    event_buffer.add_to_buffer(
+        MarketOrderEventInstructionData::new(
+            order_size,
+            is_buy,
+            is_base,
+            base_filled,
+            quote_filled,
+            time_in_force as u8,
+            taker_fee,
+            maker_rebates,
+        ),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    event_buffer.taker_return_data = Some(TakerReturnData {
+        base_filled,
+        quote_filled,
+        taker_fee,
+    });
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
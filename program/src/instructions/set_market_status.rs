@@ -0,0 +1,64 @@
+//! See [`process_set_market_status`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::SetMarketStatusEventInstructionData,
+    instructions::SetMarketStatusInstructionData,
+    state::market_status::MarketStatus,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        set_market_status_context::SetMarketStatusContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+};
+
+/// Instruction handler logic for the market authority transitioning the market to a new
+/// [`MarketStatus`].
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::SetMarketStatus`].
+#[inline(never)]
+pub unsafe fn process_set_market_status<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let SetMarketStatusInstructionData { status } =
+        SetMarketStatusInstructionData::unpack_untagged(instruction_data)?;
+    let new_status = MarketStatus::try_from(status)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { SetMarketStatusContext::load(accounts) }?;
+
+    {
+        // Safety: Scoped mutable borrow of the market account data to update the status.
+        let market = unsafe { ctx.market_account.load_unchecked_mut() };
+        if !ctx.authority.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if market.header.authority != *ctx.authority.address() {
+            return Err(DropsetError::IncorrectMarketAuthority.into());
+        }
+        market.header.set_status(new_status)?;
+    }
+
+    event_buffer.add_to_buffer(
+        SetMarketStatusEventInstructionData::new(status),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -0,0 +1,72 @@
+//! See [`process_set_seat_delegate`].
+
+use dropset_interface::{
+    events::SetSeatDelegateEventInstructionData,
+    instructions::SetSeatDelegateInstructionData,
+    state::sector::Sector,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    Address,
+};
+
+use crate::{
+    context::{
+        set_seat_delegate_context::SetSeatDelegateContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::seat_operations::load_mut_seat_with_hint,
+};
+
+/// Instruction handler logic for a user setting or removing the delegate on their market seat.
+///
+/// The delegate can place and cancel orders for the seat, but only the seat's user can withdraw
+/// from or close it.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::SetSeatDelegate`].
+#[inline(never)]
+pub unsafe fn process_set_seat_delegate<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let SetSeatDelegateInstructionData {
+        user_sector_index_hint,
+        delegate,
+    } = SetSeatDelegateInstructionData::unpack_untagged(instruction_data)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { SetSeatDelegateContext::load(accounts) }?;
+
+    if !ctx.user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    {
+        // Safety: Scoped mutable borrow of the market account data to update the user's seat.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+        Sector::check_in_bounds(market.sectors, user_sector_index_hint)?;
+        // Only the seat's user can set its delegate, so the delegate itself can't.
+        // Safety: The user sector index hint was just verified as in-bounds.
+        let seat = unsafe {
+            load_mut_seat_with_hint(&mut market, user_sector_index_hint, ctx.user.address())
+        }?;
+        seat.set_delegate((delegate != Address::default()).then_some(delegate));
+    }
+
+    event_buffer.add_to_buffer(
+        SetSeatDelegateEventInstructionData::new(user_sector_index_hint, delegate),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -0,0 +1,120 @@
+//! See [`process_settle_and_close_seat`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::CloseSeatEventInstructionData,
+    instructions::SettleAndCloseSeatInstructionData,
+    state::sector::Sector,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        settle_and_close_seat_context::SettleAndCloseSeatContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::{
+        seat_operations::load_seat_with_hint,
+        token_utils::market_transfers::withdraw_non_zero_from_market,
+    },
+};
+
+/// Instruction handler logic for closing a market seat with no open orders and transferring its
+/// remaining base and quote available to the seat owner's token accounts.
+///
+/// The seat owner and the market authority can close any seat. Anyone else can only settle and
+/// close an abandoned seat, i.e., one with only dust available and no delegate, so balances a user
+/// keeps on their seat, and the seat's delegate, can't be removed by arbitrary third parties.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::SettleAndCloseSeat`].
+#[inline(never)]
+pub unsafe fn process_settle_and_close_seat<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let sector_index_hint =
+        SettleAndCloseSeatInstructionData::unpack_untagged(instruction_data)?.sector_index_hint;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { SettleAndCloseSeatContext::load(accounts) }?;
+
+    if !ctx.closer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Remove the seat after copying the seat's base and quote available.
+    let (base_available, quote_available) = unsafe {
+        // Safety: Scoped mutable borrow of market account data.
+        let mut market = ctx.market_account.load_unchecked_mut();
+
+        Sector::check_in_bounds(market.sectors, sector_index_hint)?;
+        // Safety: The index hint was just verified as in-bounds.
+        let seat = load_seat_with_hint(&market, sector_index_hint, ctx.user.address())?;
+        // The collateral locked in open orders isn't included in the seat's balances, so closing a
+        // seat with open orders would strand that collateral.
+        if seat.has_open_orders() {
+            return Err(DropsetError::SeatHasOpenOrders.into());
+        }
+        let closer = ctx.closer.address();
+        if closer != ctx.user.address()
+            && closer != &market.header.authority
+            && !seat.is_abandoned()
+        {
+            return Err(DropsetError::UnauthorizedSeatCloser.into());
+        }
+        let copied_values = (seat.base_available(), seat.quote_available());
+
+        // Remove the seat, push it to the free stack, and zero it out.
+        market
+            .seats()
+            // Safety: The index hint was verified as in-bounds.
+            .remove_at(sector_index_hint);
+
+        copied_values
+    };
+
+    if base_available > 0 {
+        // Safety: The market account data isn't currently borrowed.
+        unsafe {
+            withdraw_non_zero_from_market(
+                &ctx.base_user_ata,
+                &ctx.base_market_ata,
+                &ctx.market_account,
+                &ctx.base_mint,
+                base_available,
+            )
+        }?;
+    }
+
+    if quote_available > 0 {
+        // Safety: The market account data isn't currently borrowed.
+        unsafe {
+            withdraw_non_zero_from_market(
+                &ctx.quote_user_ata,
+                &ctx.quote_market_ata,
+                &ctx.market_account,
+                &ctx.quote_mint,
+                quote_available,
+            )
+        }?;
+    }
+
+    event_buffer.add_to_buffer(
+        CloseSeatEventInstructionData::new(sector_index_hint),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -0,0 +1,85 @@
+//! See [`process_update_market_config`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::UpdateMarketConfigEventInstructionData,
+    instructions::UpdateMarketConfigInstructionData,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        update_market_config_context::UpdateMarketConfigContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+};
+
+/// Instruction handler logic for the market authority updating the market's tunable parameters and
+/// fee schedule.
+///
+/// Fees already accrued aren't affected by a new fee schedule, but they're collected by the new fee
+/// recipient.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::UpdateMarketConfig`].
+#[inline(never)]
+pub unsafe fn process_update_market_config<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let UpdateMarketConfigInstructionData {
+        min_order_base_atoms,
+        price_mantissa_tick,
+        max_orders_per_user,
+        fee_recipient,
+        taker_fee_bps,
+        maker_rebate_bps,
+    } = UpdateMarketConfigInstructionData::unpack_untagged(instruction_data)?;
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { UpdateMarketConfigContext::load(accounts) }?;
+
+    {
+        // Safety: Scoped mutable borrow of the market account data to update the config.
+        let market = unsafe { ctx.market_account.load_unchecked_mut() };
+        if !ctx.authority.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if market.header.authority != *ctx.authority.address() {
+            return Err(DropsetError::IncorrectMarketAuthority.into());
+        }
+        market.header.set_market_config(
+            min_order_base_atoms,
+            price_mantissa_tick,
+            max_orders_per_user,
+        )?;
+        market
+            .header
+            .set_fee_schedule(&fee_recipient, taker_fee_bps, maker_rebate_bps)?;
+    }
+
+    event_buffer.add_to_buffer(
+        UpdateMarketConfigEventInstructionData::new(
+            min_order_base_atoms,
+            price_mantissa_tick,
+            max_orders_per_user,
+            fee_recipient,
+            taker_fee_bps,
+            maker_rebate_bps,
+        ),
+        ctx.event_authority,
+        ctx.market_account.clone(),
+    )?;
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -0,0 +1,110 @@
+//! See [`process_withdraw_both`].
+
+use dropset_interface::{
+    error::DropsetError,
+    events::WithdrawEventInstructionData,
+    instructions::WithdrawBothInstructionData,
+    state::sector::Sector,
+};
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
+
+use crate::{
+    context::{
+        withdraw_both_context::WithdrawBothContext,
+        EventBufferContext,
+    },
+    events::EventBuffer,
+    shared::{
+        seat_operations::load_mut_seat_with_hint,
+        token_utils::market_transfers::withdraw_non_zero_from_market,
+    },
+};
+
+/// Instruction handler logic for withdrawing base and quote from a market seat in one instruction.
+///
+/// A zero amount skips the transfer for that token, but at least one amount must be non-zero. A
+/// withdraw event is emitted for each token withdrawn.
+///
+/// # Safety
+///
+/// Caller upholds the safety contract detailed in
+/// [`dropset_interface::instructions::generated_program::WithdrawBoth`].
+#[inline(never)]
+pub unsafe fn process_withdraw_both<'a>(
+    accounts: &'a [AccountView],
+    instruction_data: &[u8],
+    event_buffer: &mut EventBuffer,
+) -> Result<EventBufferContext<'a>, ProgramError> {
+    let WithdrawBothInstructionData {
+        base_amount,
+        quote_amount,
+        sector_index_hint,
+    } = WithdrawBothInstructionData::unpack_untagged(instruction_data)?;
+
+    if base_amount == 0 && quote_amount == 0 {
+        return Err(DropsetError::AmountCannotBeZero.into());
+    }
+
+    // Safety: No account data in `accounts` is currently borrowed.
+    let mut ctx = unsafe { WithdrawBothContext::load(accounts) }?;
+
+    // Update the seat first, checking for underflow, as that means the user tried to withdraw more
+    // than they have available.
+    {
+        // Safety: Scoped mutable borrow of market account data to update the user's seat.
+        let mut market = unsafe { ctx.market_account.load_unchecked_mut() };
+
+        // Find the seat with the index hint or fail and return early.
+        Sector::check_in_bounds(market.sectors, sector_index_hint)?;
+        // Safety: The hint was just verified as in-bounds.
+        let seat =
+            unsafe { load_mut_seat_with_hint(&mut market, sector_index_hint, ctx.user.address()) }?;
+
+        seat.try_decrement_base_available(base_amount)?;
+        seat.try_decrement_quote_available(quote_amount)?;
+    }
+
+    if base_amount != 0 {
+        // Safety: No account data is currently borrowed.
+        unsafe {
+            withdraw_non_zero_from_market(
+                &ctx.base_user_ata,
+                &ctx.base_market_ata,
+                &ctx.market_account,
+                &ctx.base_mint,
+                base_amount,
+            )
+        }?;
+        event_buffer.add_to_buffer(
+            WithdrawEventInstructionData::new(base_amount, true),
+            ctx.event_authority,
+            ctx.market_account.clone(),
+        )?;
+    }
+
+    if quote_amount != 0 {
+        // Safety: No account data is currently borrowed.
+        unsafe {
+            withdraw_non_zero_from_market(
+                &ctx.quote_user_ata,
+                &ctx.quote_market_ata,
+                &ctx.market_account,
+                &ctx.quote_mint,
+                quote_amount,
+            )
+        }?;
+        event_buffer.add_to_buffer(
+            WithdrawEventInstructionData::new(quote_amount, false),
+            ctx.event_authority,
+            ctx.market_account.clone(),
+        )?;
+    }
+
+    Ok(EventBufferContext {
+        event_authority: ctx.event_authority,
+        market_account: ctx.market_account,
+    })
+}
@@ -1,8 +1,10 @@
 //! Handles resizing Solana accounts in-place with rent and lamport adjustments, ensuring safety
 //! when reallocating market or seat accounts.
 
+use dropset_interface::error::DropsetError;
 use pinocchio::{
     account::AccountView,
+    error::ProgramError,
     sysvars::{
         rent::Rent,
         Sysvar,
@@ -16,6 +18,9 @@ use pinocchio::{
 /// - If the lamport diff is zero, the transfer CPI isn't invoked.
 /// - Otherwise, the `payer` transfers the necessary lamports.
 ///
+/// The account is always resized, even if it already holds enough lamports for the new size, e.g.
+/// because someone sent lamports directly to it.
+///
 /// # Safety
 ///
 /// Caller guarantees:
@@ -36,17 +41,55 @@ pub unsafe fn fund_then_resize_unchecked(
     let new_lamports_required = Rent::get()?.try_minimum_balance(new_size)?;
     let lamports_diff = new_lamports_required.saturating_sub(current_lamports);
 
-    if lamports_diff == 0 {
-        return Ok(());
-    }
-
-    pinocchio_system::instructions::Transfer {
-        from: payer, // WRITE
-        to: account, // WRITE
-        lamports: lamports_diff,
+    if lamports_diff != 0 {
+        pinocchio_system::instructions::Transfer {
+            from: payer, // WRITE
+            to: account, // WRITE
+            lamports: lamports_diff,
+        }
+        .invoke()?;
     }
-    .invoke()?;
 
     // Safety: Scoped mutable borrow of the account data.
     unsafe { account.resize_unchecked(new_size) }
 }
+
+/// Shrinks `account` to `new_size` bytes, then moves the lamports no longer required for rent from
+/// `account` to `destination`.
+///
+/// Returns the number of lamports refunded.
+///
+/// # Safety
+///
+/// Caller guarantees:
+/// - WRITE accounts are not currently borrowed in *any* capacity.
+/// - `account` is owned by this program so its lamports can be debited directly.
+/// - `new_size` is less than or equal to the account's current data length.
+///
+/// ### Accounts
+///   0. `[WRITE]` Account to be resized
+///   1. `[WRITE]` Destination
+pub unsafe fn resize_then_refund_unchecked(
+    account: &AccountView,
+    destination: &AccountView,
+    new_size: usize,
+) -> Result<u64, ProgramError> {
+    // Safety: Scoped mutable borrow of the account data.
+    unsafe { account.resize_unchecked(new_size) }?;
+
+    let lamports_required = Rent::get()?.try_minimum_balance(new_size)?;
+    let lamports_refunded = account.lamports().saturating_sub(lamports_required);
+
+    if lamports_refunded == 0 {
+        return Ok(0);
+    }
+
+    let destination_lamports = destination
+        .lamports()
+        .checked_add(lamports_refunded)
+        .ok_or(DropsetError::ArithmeticOverflow)?;
+    account.set_lamports(lamports_required);
+    destination.set_lamports(destination_lamports);
+
+    Ok(lamports_refunded)
+}
@@ -0,0 +1,88 @@
+//! Fee calculations for taker fees and maker rebates, both expressed in basis points.
+
+use dropset_interface::{
+    error::DropsetError,
+    state::market_header::{
+        MarketHeader,
+        BPS_DENOMINATOR,
+    },
+};
+
+/// Calculates the taker fee charged on `amount`, rounded up in favor of the market.
+///
+/// The result is always <= `amount` as long as `taker_fee_bps` <= [`BPS_DENOMINATOR`], which is
+/// enforced when the fee schedule is set.
+#[inline(always)]
+pub fn taker_fee(amount: u64, taker_fee_bps: u16) -> u64 {
+    let numerator = (amount as u128) * (taker_fee_bps as u128);
+    numerator.div_ceil(BPS_DENOMINATOR as u128) as u64
+}
+
+/// Calculates the maker rebate paid on `amount`, rounded down in favor of the market.
+///
+/// Since the maker rebate is always <= the taker fee and rounds in the opposite direction, the sum
+/// of all rebates paid for a single taker order never exceeds the taker fee charged for it.
+#[inline(always)]
+pub fn maker_rebate(amount: u64, maker_rebate_bps: u16) -> u64 {
+    let numerator = (amount as u128) * (maker_rebate_bps as u128);
+    (numerator / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Charges the market's taker fee on `amount_out`, the amount of the asset the taker receives from
+/// a fill, i.e., base for a buy and quote for a sell. The fee net of `maker_rebates` already
+/// credited to makers during the fill is accrued to the market header.
+///
+/// Returns the amount the taker actually receives and the taker fee charged.
+#[inline(always)]
+pub fn charge_taker_fee(
+    header: &mut MarketHeader,
+    is_buy: bool,
+    amount_out: u64,
+    maker_rebates: u64,
+) -> Result<(u64, u64), DropsetError> {
+    let fee = taker_fee(amount_out, header.taker_fee_bps());
+    let net_fee = fee
+        .checked_sub(maker_rebates)
+        .ok_or(DropsetError::ArithmeticUnderflow)?;
+
+    if is_buy {
+        header.try_accrue_base_fees(net_fee)?;
+    } else {
+        header.try_accrue_quote_fees(net_fee)?;
+    }
+
+    // Safety: The taker fee is always <= the amount it's charged on.
+    let amount_received = unsafe { amount_out.unchecked_sub(fee) };
+
+    Ok((amount_received, fee))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn taker_fee_rounds_up() {
+        assert_eq!(taker_fee(10_000, 30), 30);
+        assert_eq!(taker_fee(10_001, 30), 31);
+        assert_eq!(taker_fee(1, 1), 1);
+        assert_eq!(taker_fee(1_000, 0), 0);
+        assert_eq!(taker_fee(u64::MAX, BPS_DENOMINATOR), u64::MAX);
+    }
+
+    #[test]
+    fn maker_rebate_rounds_down() {
+        assert_eq!(maker_rebate(10_000, 10), 10);
+        assert_eq!(maker_rebate(10_999, 10), 10);
+        assert_eq!(maker_rebate(1, 1), 0);
+        assert_eq!(maker_rebate(u64::MAX, BPS_DENOMINATOR), u64::MAX);
+    }
+
+    #[test]
+    fn split_rebates_never_exceed_taker_fee() {
+        let fills = [1, 999, 3_333, 10_001, 7];
+        let total: u64 = fills.iter().sum();
+        let rebates: u64 = fills.iter().map(|fill| maker_rebate(*fill, 25)).sum();
+        assert!(rebates <= taker_fee(total, 25));
+    }
+}
@@ -3,12 +3,24 @@
 use dropset_interface::{
     error::DropsetError,
     state::{
+        asks_dll::AskOrders,
+        bids_dll::BidOrders,
         market::{
             Market,
             MarketRefMut,
         },
         market_header::MarketHeader,
-        sector::SECTOR_SIZE,
+        market_seat::MarketSeat,
+        order::{
+            Order,
+            OrdersCollection,
+        },
+        sector::{
+            Sector,
+            SectorIndex,
+            NIL,
+            SECTOR_SIZE,
+        },
         transmutable::Transmutable,
     },
 };
@@ -24,6 +36,7 @@ pub fn initialize_market_account_data<'a>(
     base_mint: &Address,
     quote_mint: &Address,
     market_bump: u8,
+    market_id: u16,
 ) -> Result<MarketRefMut<'a>, DropsetError> {
     let account_data_len = zeroed_market_account_data.len();
     if account_data_len < MarketHeader::LEN {
@@ -47,6 +60,7 @@ pub fn initialize_market_account_data<'a>(
         MarketHeader::init(
             core::ptr::addr_of_mut!(*market.header),
             market_bump,
+            market_id,
             base_mint,
             quote_mint,
         );
@@ -64,6 +78,162 @@ pub fn initialize_market_account_data<'a>(
     Ok(market)
 }
 
+/// Relocates every live sector (seats, seats' overflow order sectors, bids and asks) to the front
+/// of the market's sectors region so that only `num_free_sectors` free sectors remain in front of
+/// the returned sector count, and every sector at or past it is unused.
+///
+/// Every reference to a relocated sector is rewritten: the DLL links and heads/tails, each order's
+/// user seat index, each seat's overflow sector index and each seat's price -> order sectors
+/// mapping. The market's data isn't truncated here; the caller is expected to truncate the account
+/// data to the returned number of sectors.
+///
+/// # Safety
+///
+/// Caller guarantees the market's sectors, links and free stack are valid.
+pub unsafe fn compact_market_sectors(
+    market: &mut MarketRefMut,
+    num_free_sectors: u32,
+) -> Result<u32, DropsetError> {
+    if num_free_sectors > market.header.num_free_sectors() {
+        return Err(DropsetError::InvalidNumFreeSectors);
+    }
+
+    // The number of sectors after compaction. Every sector that isn't free is live, including the
+    // overflow sectors that aren't counted in the header, so this is the number of live sectors
+    // plus the free sectors kept.
+    let num_sectors = market.get_capacity() - market.header.num_free_sectors() + num_free_sectors;
+
+    // Remove the free sectors past the new end first, so that every sector popped off the free
+    // stack below is a valid destination. There are always exactly enough: the free sectors
+    // before the new end outnumber the live sectors past it by `num_free_sectors`.
+    market.free_stack().retain_below(num_sectors);
+
+    // Relocate the seats past the new end, then point each of their orders to the new seat index.
+    // Each seat's overflow sectors are relocated too if they're past the new end.
+    let mut index = market.header.seats_dll_head();
+    while index != NIL {
+        let seat_index = if index < num_sectors {
+            index
+        } else {
+            let new_index = market.free_stack().pop_free_sector()?;
+            market.seats().relocate(index, new_index);
+
+            // Safety: `new_index` is the relocated seat's sector index.
+            let order_indices = unsafe {
+                [
+                    market
+                        .seat_order_sectors::<BidOrders>(new_index)
+                        .to_sector_indices(),
+                    market
+                        .seat_order_sectors::<AskOrders>(new_index)
+                        .to_sector_indices(),
+                ]
+            };
+
+            for order_index in order_indices.into_iter().flatten().filter(|i| *i != NIL) {
+                // Safety: All order sector indices in a user seat are in-bounds.
+                unsafe { Sector::from_sector_index_mut(market.sectors, order_index) }
+                    .load_payload_mut::<Order>()
+                    .set_user_seat(new_index);
+            }
+            new_index
+        };
+
+        // Safety: `seat_index` is a valid seat's sector index.
+        unsafe {
+            relocate_overflow_sector_past_end::<BidOrders>(market, seat_index, num_sectors)?;
+            relocate_overflow_sector_past_end::<AskOrders>(market, seat_index, num_sectors)?;
+        }
+
+        // Safety: `seat_index` is a valid seat's sector index.
+        index = unsafe { Sector::from_sector_index(market.sectors, seat_index) }.next();
+    }
+
+    relocate_orders_past_end::<BidOrders>(market, num_sectors)?;
+    relocate_orders_past_end::<AskOrders>(market, num_sectors)?;
+
+    Ok(num_sectors)
+}
+
+/// Relocates the seat's `Side` overflow order sectors to a free sector if it's at or past `end`.
+///
+/// # Safety
+///
+/// Caller guarantees `seat_index` is a valid seat's sector index, the free stack only contains
+/// sectors before `end`, and that there are enough of them for every relocated sector.
+#[inline(always)]
+unsafe fn relocate_overflow_sector_past_end<Side: OrdersCollection>(
+    market: &mut MarketRefMut,
+    seat_index: SectorIndex,
+    end: SectorIndex,
+) -> Result<(), DropsetError> {
+    // Safety: Caller guarantees `seat_index` is a valid seat's sector index.
+    let overflow_index = unsafe { Sector::from_sector_index(market.sectors, seat_index) }
+        .load_payload::<MarketSeat>()
+        .user_order_sectors
+        .order_sectors::<Side>()
+        .overflow_index();
+    if overflow_index == NIL || overflow_index < end {
+        return Ok(());
+    }
+
+    let new_index = market.free_stack().pop_free_sector()?;
+    // Safety: Both indices are in-bounds and distinct, since one is free and the other isn't.
+    unsafe {
+        let sectors = market.sectors.as_mut_ptr();
+        core::ptr::copy_nonoverlapping(
+            sectors.add(overflow_index as usize * Sector::LEN),
+            sectors.add(new_index as usize * Sector::LEN),
+            Sector::LEN,
+        );
+    }
+
+    // Safety: Caller guarantees `seat_index` is a valid seat's sector index.
+    unsafe { Sector::from_sector_index_mut(market.sectors, seat_index) }
+        .load_payload_mut::<MarketSeat>()
+        .user_order_sectors
+        .order_sectors_mut::<Side>()
+        .set_overflow_index(new_index);
+
+    Ok(())
+}
+
+/// Relocates the `Side` orders at or past `end` to free sectors, then updates each relocated
+/// order's entry in its seat's price -> order sectors mapping.
+///
+/// # Safety
+///
+/// Caller guarantees the free stack only contains sectors before `end`, and that there are enough
+/// of them for every relocated order.
+#[inline(always)]
+unsafe fn relocate_orders_past_end<Side: OrdersCollection>(
+    market: &mut MarketRefMut,
+    end: SectorIndex,
+) -> Result<(), DropsetError> {
+    let mut index = Side::head(market.header);
+    while index != NIL {
+        if index < end {
+            // Safety: `index` is a non-NIL index from the orders collection.
+            index = unsafe { Sector::from_sector_index(market.sectors, index) }.next();
+            continue;
+        }
+        let new_index = market.free_stack().pop_free_sector()?;
+        market.orders::<Side>().relocate(index, new_index);
+
+        // Safety: `new_index` was just popped from the free stack, so it's in-bounds.
+        let sector = unsafe { Sector::from_sector_index(market.sectors, new_index) };
+        let seat_index = sector.load_payload::<Order>().user_seat();
+        let old_index = index;
+        index = sector.next();
+
+        // Safety: An order's user seat index is always a valid seat's sector index.
+        unsafe { market.seat_order_sectors::<Side>(seat_index) }
+            .replace_sector_index(old_index, new_index);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 pub mod tests {
     use dropset_interface::state::{
@@ -96,6 +266,7 @@ pub mod tests {
             &Address::from_str_const("11111111111111111111111111111111111111111111"),
             &Address::from_str_const("22222222222222222222222222222222222222222222"),
             254,
+            0,
         )
         .expect("Should initialize market data");
 
@@ -148,4 +319,63 @@ pub mod tests {
             assert_eq!(pk_e, pk_a);
         }
     }
+
+    #[test]
+    fn compact_market_relocates_seats() {
+        const N_SECTORS: usize = 10;
+        let mut bytes = [0u8; MarketHeader::LEN + SECTOR_SIZE * N_SECTORS];
+        let mut market = initialize_market_account_data(
+            bytes.as_mut(),
+            &Address::from_str_const("11111111111111111111111111111111111111111111"),
+            &Address::from_str_const("22222222222222222222222222222222222222222222"),
+            254,
+            0,
+        )
+        .expect("Should initialize market data");
+
+        let seats: Vec<MarketSeat> = [3, 2, 40, 0, 10, 1]
+            .into_iter()
+            .map(|n| {
+                let pk = [vec![0; 31], vec![n]].concat().try_into().unwrap();
+                MarketSeat::new(pk, n as u64, 0)
+            })
+            .collect();
+
+        let mut seat_list = market.seats();
+        seats.into_iter().for_each(|seat| {
+            assert!(try_insert_market_seat(&mut seat_list, seat).is_ok());
+        });
+
+        // Free the first and third sectors, leaving the last seat at sector index 5.
+        unsafe {
+            seat_list.remove_at(0);
+            seat_list.remove_at(2);
+        }
+
+        assert!(matches!(
+            unsafe { compact_market_sectors(&mut market, 7) },
+            Err(DropsetError::InvalidNumFreeSectors)
+        ));
+
+        let num_sectors =
+            unsafe { compact_market_sectors(&mut market, 1) }.expect("Should compact market");
+        assert_eq!(num_sectors, 5);
+        assert_eq!(market.header.num_free_sectors(), 1);
+        assert_eq!(market.header.num_seats(), 4);
+
+        let resulting_seats: Vec<(SectorIndex, u64)> = market
+            .iter_seats()
+            .map(|(i, sector)| (i, sector.load_payload::<MarketSeat>().base_available()))
+            .collect();
+
+        // The seats keep their order, and every seat is now before the new end.
+        assert_eq!(
+            resulting_seats
+                .iter()
+                .map(|(_, base)| *base)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 10]
+        );
+        assert!(resulting_seats.iter().all(|(i, _)| *i < num_sectors));
+    }
 }
@@ -9,6 +9,7 @@ use dropset_interface::{
             MarketRefMut,
         },
         market_header::MarketHeader,
+        market_seat::MarketSeat,
         order::{
             NextSectorIndex,
             Order,
@@ -19,8 +20,15 @@ use dropset_interface::{
             SectorIndex,
             NIL,
         },
+        user_order_sectors::MAX_ORDERS_USIZE,
     },
 };
+use solana_address::Address;
+
+use crate::shared::seat_operations::{
+    load_mut_seat_with_hint,
+    load_seat_with_hint,
+};
 
 /// Insert a new user order into the orders collection.
 ///
@@ -83,6 +91,136 @@ pub unsafe fn load_mut_order_from_sector_index<'m>(
     sector.load_payload_mut::<Order>()
 }
 
+/// Removes a user's orders from the market orders collection and update the seat balance to reflect
+/// the collateral returned from closing those orders.
+///
+/// Note this does *not* remove the entries from the user seat's price -> order sectors mapping.
+///
+/// Returns the number of orders removed.
+///
+/// # Safety
+///
+/// Caller guarantees the user seat index passed is a non-NIL, valid, and in-bounds sector index.
+#[inline(always)]
+pub unsafe fn remove_orders_from_market_and_update_seat_balance<Side: OrdersCollection>(
+    market: &mut MarketRefMut,
+    user_address: &Address,
+    valid_user_seat_index: SectorIndex,
+) -> Result<u8, DropsetError> {
+    // Find and verify the user's seat with the given index hint.
+    // Safety: The index hint was just verified as in-bounds.
+    load_seat_with_hint(market, valid_user_seat_index, user_address)?;
+    // Safety: The seat index was just verified as a valid seat's sector index.
+    let order_indices =
+        unsafe { market.seat_order_sectors::<Side>(valid_user_seat_index) }.to_sector_indices();
+
+    let mut collateral_returned: u64 = 0;
+    let mut num_removed: u8 = 0;
+
+    for idx in order_indices {
+        if idx != NIL {
+            num_removed += 1;
+            // Safety: Caller upholds the safety contract.
+            let collateral_remaining =
+                unsafe { load_order_from_sector_index(market, idx).collateral_amount::<Side>() };
+            collateral_returned = collateral_returned
+                .checked_add(collateral_remaining)
+                .ok_or(DropsetError::ArithmeticOverflow)?;
+            market.orders::<Side>().remove_at(idx);
+        }
+    }
+
+    {
+        let mut_user_seat = load_mut_seat_with_hint(market, valid_user_seat_index, user_address)?;
+        mut_user_seat.try_increment_collateral_available::<Side>(collateral_returned)?;
+    }
+
+    Ok(num_removed)
+}
+
+/// Removes all of a user's orders on one side of the book, returns their collateral to the user's
+/// seat and frees the seat's price -> order sectors mapping for that side.
+///
+/// Returns the canceled orders' client order ids and the number of orders canceled. Only the first
+/// `num_canceled` client order ids are meaningful.
+///
+/// # Safety
+///
+/// Caller guarantees the user seat index passed is a non-NIL, valid, and in-bounds sector index.
+#[inline(always)]
+pub unsafe fn cancel_all_orders_on_side<Side: OrdersCollection>(
+    market: &mut MarketRefMut,
+    user_address: &Address,
+    valid_user_seat_index: SectorIndex,
+) -> Result<([u64; MAX_ORDERS_USIZE], u8), DropsetError> {
+    // Record the client order ids before the orders are removed from the market.
+    let mut client_order_ids = [0; MAX_ORDERS_USIZE];
+    {
+        // Safety: Caller upholds the safety contract.
+        unsafe { load_seat_with_hint(market, valid_user_seat_index, user_address) }?;
+        // Safety: The user's seat was just verified at the seat index.
+        let sector_indices =
+            unsafe { market.seat_order_sectors::<Side>(valid_user_seat_index) }.to_sector_indices();
+        for (client_order_id, idx) in client_order_ids
+            .iter_mut()
+            .zip(sector_indices.into_iter().filter(|idx| *idx != NIL))
+        {
+            // Safety: All order sector indices in a user seat are in-bounds.
+            *client_order_id =
+                unsafe { load_order_from_sector_index(market, idx) }.client_order_id();
+        }
+    }
+
+    // Safety: Caller upholds the safety contract.
+    let num_canceled = unsafe {
+        remove_orders_from_market_and_update_seat_balance::<Side>(
+            market,
+            user_address,
+            valid_user_seat_index,
+        )
+    }?;
+
+    // Safety: The seat index was verified above as in-bounds and belonging to the user.
+    unsafe { market.seat_order_sectors::<Side>(valid_user_seat_index) }.clear();
+
+    Ok((client_order_ids, num_canceled))
+}
+
+/// Removes a resting order from the market orders collection without filling it, returns its
+/// remaining collateral to the maker's seat and removes it from the maker seat's price -> order
+/// sectors mapping.
+///
+/// This is used to remove expired orders and self-matched orders that are canceled by the taker.
+///
+/// # Safety
+///
+/// Caller guarantees `validated_order_sector_index` is in-bounds and points to a valid order in the
+/// `Side` orders collection.
+#[inline(always)]
+pub unsafe fn remove_order_and_refund_maker<Side: OrdersCollection>(
+    market: &mut MarketRefMut,
+    validated_order_sector_index: SectorIndex,
+) -> Result<(), DropsetError> {
+    // Safety: Caller upholds the safety contract.
+    let order = unsafe { load_order_from_sector_index(market, validated_order_sector_index) };
+    let collateral_remaining = order.collateral_amount::<Side>();
+    let encoded_price = order.encoded_price().as_u32();
+    let maker_seat_index = order.user_seat();
+
+    market
+        .orders::<Side>()
+        .remove_at(validated_order_sector_index);
+
+    // Safety: The maker seat sector index came from a valid order, so it's in-bounds.
+    let sector = unsafe { Sector::from_sector_index_mut(market.sectors, maker_seat_index) };
+    let maker_seat = sector.load_payload_mut::<MarketSeat>();
+    maker_seat.try_increment_collateral_available::<Side>(collateral_remaining)?;
+    // Safety: The maker seat sector index came from a valid order, so it's a valid seat.
+    unsafe { market.seat_order_sectors::<Side>(maker_seat_index) }.find_remove(encoded_price)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     extern crate std;
@@ -92,31 +230,34 @@ mod tests {
         vec::*,
     };
 
-    use dropset_interface::state::{
-        asks_dll::{
-            AskOrders,
-            AskOrdersLinkedList,
-        },
-        bids_dll::{
-            BidOrders,
-            BidOrdersLinkedList,
+    use dropset_interface::{
+        instructions::OrderExpiry,
+        state::{
+            asks_dll::{
+                AskOrders,
+                AskOrdersLinkedList,
+            },
+            bids_dll::{
+                BidOrders,
+                BidOrdersLinkedList,
+            },
+            linked_list::{
+                LinkedList,
+                LinkedListHeaderOperations,
+            },
+            market::MarketRefMut,
+            market_header::MarketHeader,
+            order::{
+                Order,
+                OrdersCollection,
+            },
+            sector::{
+                SectorIndex,
+                NIL,
+                SECTOR_SIZE,
+            },
+            transmutable::Transmutable,
         },
-        linked_list::{
-            LinkedList,
-            LinkedListHeaderOperations,
-        },
-        market::MarketRefMut,
-        market_header::MarketHeader,
-        order::{
-            Order,
-            OrdersCollection,
-        },
-        sector::{
-            SectorIndex,
-            NIL,
-            SECTOR_SIZE,
-        },
-        transmutable::Transmutable,
     };
     use price::{
         to_order_info,
@@ -148,6 +289,7 @@ mod tests {
             &Address::from_str_const("11111111111111111111111111111111111111111111"),
             &Address::from_str_const("22222222222222222222222222222222222222222222"),
             254,
+            0,
         )
         .expect("Should initialize market data")
     }
@@ -358,9 +500,9 @@ mod tests {
 
         // Placing an ask when there are no bids should succeed regardless of price.
         assert_eq!(market.bids().iter().count(), 0);
-        assert!(AskOrders::post_only_crossing_check(&order_1, &market).is_ok());
-        assert!(AskOrders::post_only_crossing_check(&order_2, &market).is_ok());
-        assert!(AskOrders::post_only_crossing_check(&order_3, &market).is_ok());
+        assert!(AskOrders::post_only_crossing_check(&order_1, &market, 0, 0).is_ok());
+        assert!(AskOrders::post_only_crossing_check(&order_2, &market, 0, 0).is_ok());
+        assert!(AskOrders::post_only_crossing_check(&order_3, &market, 0, 0).is_ok());
 
         // Insert a single order to the bid side.
         insert_helper(&mut market.bids(), &order_2);
@@ -376,15 +518,15 @@ mod tests {
 
         // Placing an ask with a higher price than the top bid should succeed.
         assert!(order_3.encoded_price() > get_bids_head_price(market.bids()));
-        assert!(AskOrders::post_only_crossing_check(&order_3, &market).is_ok());
+        assert!(AskOrders::post_only_crossing_check(&order_3, &market, 0, 0).is_ok());
 
         // Placing an ask with an equal price to the top bid should fail.
         assert_eq!(order_2.encoded_price(), get_bids_head_price(market.bids()));
-        assert!(AskOrders::post_only_crossing_check(&order_2, &market).is_err());
+        assert!(AskOrders::post_only_crossing_check(&order_2, &market, 0, 0).is_err());
 
         // Placing an ask with a lower price than the top bid should fail.
         assert!(order_1.encoded_price() < get_bids_head_price(market.bids()));
-        assert!(AskOrders::post_only_crossing_check(&order_1, &market).is_err());
+        assert!(AskOrders::post_only_crossing_check(&order_1, &market, 0, 0).is_err());
     }
 
     #[test]
@@ -400,9 +542,9 @@ mod tests {
 
         // Placing a bid when there are no asks should succeed regardless of price.
         assert_eq!(market.asks().iter().count(), 0);
-        assert!(BidOrders::post_only_crossing_check(&order_1, &market).is_ok());
-        assert!(BidOrders::post_only_crossing_check(&order_2, &market).is_ok());
-        assert!(BidOrders::post_only_crossing_check(&order_3, &market).is_ok());
+        assert!(BidOrders::post_only_crossing_check(&order_1, &market, 0, 0).is_ok());
+        assert!(BidOrders::post_only_crossing_check(&order_2, &market, 0, 0).is_ok());
+        assert!(BidOrders::post_only_crossing_check(&order_3, &market, 0, 0).is_ok());
 
         // Insert a single order to the ask side.
         insert_helper(&mut market.asks(), &order_2);
@@ -418,15 +560,47 @@ mod tests {
 
         // Placing a bid with a lower price than the top ask should succeed.
         assert!(order_1.encoded_price() < get_asks_head_price(market.asks()));
-        assert!(BidOrders::post_only_crossing_check(&order_1, &market).is_ok());
+        assert!(BidOrders::post_only_crossing_check(&order_1, &market, 0, 0).is_ok());
 
         // Placing a bid with an equal price to the top ask should fail.
         assert_eq!(order_2.encoded_price(), get_asks_head_price(market.asks()));
-        assert!(BidOrders::post_only_crossing_check(&order_2, &market).is_err());
+        assert!(BidOrders::post_only_crossing_check(&order_2, &market, 0, 0).is_err());
 
         // Placing a bid with a higher price than the top ask should fail.
         assert!(order_3.encoded_price() > get_asks_head_price(market.asks()));
-        assert!(BidOrders::post_only_crossing_check(&order_3, &market).is_err());
+        assert!(BidOrders::post_only_crossing_check(&order_3, &market, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_post_only_crossing_check_skips_expired_orders() {
+        let bytes = &mut [0u8; MARKET_LEN];
+        let mut market = create_simple_market(bytes);
+
+        let expires_at_slot = 100;
+        let expiring_ask = {
+            let order_info = to_order_info(OrderInfoArgs::order_at_price(20_000_000))
+                .expect("The unit test should pass a valid price mantissa");
+            Order::new_with_expiry(order_info, 1, OrderExpiry::at_slot(expires_at_slot))
+        };
+        insert_helper(&mut market.asks(), &expiring_ask);
+        insert_helper(&mut market.asks(), &create_test_order(30_000_000, 2));
+
+        let bid = create_test_order(20_000_000, 3);
+
+        // Before the ask expires, a bid at its price would take.
+        assert!(
+            BidOrders::post_only_crossing_check(&bid, &market, expires_at_slot - 1, 0).is_err()
+        );
+
+        // Once it's expired, the ask can't be filled, so the bid no longer crosses.
+        assert!(BidOrders::post_only_crossing_check(&bid, &market, expires_at_slot, 0).is_ok());
+
+        // A bid that crosses the unexpired ask behind the expired one still fails.
+        let crossing_bid = create_test_order(30_000_000, 3);
+        assert!(
+            BidOrders::post_only_crossing_check(&crossing_bid, &market, expires_at_slot, 0)
+                .is_err()
+        );
     }
 
     #[test]
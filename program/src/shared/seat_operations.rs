@@ -17,6 +17,10 @@ use dropset_interface::{
         },
     },
 };
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+};
 use solana_address::{
     address_eq,
     Address,
@@ -100,6 +104,34 @@ where
     }
 }
 
+/// Returns the user that owns the seat at the index hint for an order instruction signed by
+/// `signer`, checking that `signer` signed the instruction and is either the seat's user or its
+/// delegate.
+///
+/// Order instructions operate on the returned seat user's address, so a delegate's orders and
+/// fills are attributed to the seat's user.
+pub fn load_seat_user_for_signer<H, S>(
+    market: &Market<H, S>,
+    hint: SectorIndex,
+    signer: &AccountView,
+) -> Result<Address, ProgramError>
+where
+    H: AsRef<MarketHeader>,
+    S: AsRef<[u8]>,
+{
+    if !signer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Sector::check_in_bounds(market.sectors.as_ref(), hint)?;
+    // Safety: The hint was just verified as in-bounds.
+    let seat = unsafe { load_seat_with_hint_unchecked(market, hint) };
+    if seat.is_user_or_delegate(signer.address()) {
+        Ok(seat.user)
+    } else {
+        Err(DropsetError::InvalidIndexHint.into())
+    }
+}
+
 /// Loads a mutable market seat given an index hint, checking that the seat belongs to the user
 /// address passed.
 ///
@@ -127,7 +159,6 @@ pub unsafe fn load_mut_seat_with_hint<'m>(
 /// # Safety
 ///
 /// Caller guarantees `hint` is in-bounds of `market.sectors` bytes.
-#[allow(dead_code)]
 pub unsafe fn load_seat_with_hint_unchecked<H, S>(
     market: &Market<H, S>,
     hint: SectorIndex,
@@ -2,10 +2,11 @@
 
 #[macro_export]
 macro_rules! market_seeds {
-    ($base:expr, $quote:expr) => {
+    ($base:expr, $quote:expr, $market_id:expr) => {
         &[
             $base.as_ref(),
             $quote.as_ref(),
+            &$market_id.to_le_bytes(),
             ::dropset_interface::seeds::market::MARKET_SEED_STR,
         ]
     };
@@ -19,16 +20,18 @@ macro_rules! market_seeds {
 /// use solana_address::Address;
 ///
 /// let bump: u8 = 0x10;
+/// let market_id: u16 = 0;
 /// let base_mint = Address::from_str_const("11111111111111111111111111111111111111111111");
 /// let quote_mint = Address::from_str_const("22222222222222222222222222222222222222222222");
-/// let signer: Signer = market_signer!(base_mint, quote_mint, bump);
+/// let signer: Signer = market_signer!(base_mint, quote_mint, market_id, bump);
 /// ```
 #[macro_export]
 macro_rules! market_signer {
-    ( $base_mint:expr, $quote_mint:expr, $bump:expr ) => {
+    ( $base_mint:expr, $quote_mint:expr, $market_id:expr, $bump:expr ) => {
         ::solana_instruction_view::cpi::Signer::from(&::solana_instruction_view::seeds!(
             $base_mint.as_ref(),
             $quote_mint.as_ref(),
+            &$market_id.to_le_bytes(),
             ::dropset_interface::seeds::market::MARKET_SEED_STR,
             &[$bump]
         ))
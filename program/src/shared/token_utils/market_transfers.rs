@@ -7,12 +7,18 @@ use dropset_interface::{
 };
 use pinocchio::{
     account::AccountView,
+    cpi::invoke,
     error::ProgramError,
     ProgramResult,
 };
+use solana_instruction_view::{
+    InstructionAccount,
+    InstructionView,
+};
 
 use crate::{
     market_signer,
+    shared::token_utils::token_2022_extensions::get_transfer_fee_config,
     validation::{
         market_account_view::MarketAccountView,
         mint_account_view::MintAccountView,
@@ -23,7 +29,8 @@ use crate::{
 /// Deposits `amount` of mint token from the user to the market account. This does not track or
 /// update seat balances.
 ///
-/// Returns an error if the amount deposited is zero.
+/// Returns the amount the market account actually received, which is net of the transfer fee for
+/// `spl_token_2022` mints with a transfer fee. Returns an error if the amount deposited is zero.
 ///
 /// # Safety
 ///
@@ -92,6 +99,9 @@ pub unsafe fn deposit_non_zero_to_market<'a, 't>(
 /// Withdraws `amount` of token `ctx.mint` from the market account to the user. This does not track
 /// or update seat balances.
 ///
+/// For `spl_token_2022` mints with a transfer fee, the fee is withheld from the amount the user
+/// receives, so the market's balance always decreases by exactly `amount`.
+///
 /// Returns an error if the amount withdrawn is zero.
 ///
 /// # Safety
@@ -116,12 +126,13 @@ pub unsafe fn withdraw_non_zero_from_market<'t, 'a>(
         return Err(DropsetError::AmountCannotBeZero.into());
     }
 
-    let (base_mint, quote_mint, market_bump) = {
+    let (base_mint, quote_mint, market_id, market_bump) = {
         // Safety: Scoped immutable borrow of the market account.
         let market = unsafe { market_account.load_unchecked() };
         (
             market.header.base_mint,
             market.header.quote_mint,
+            market.header.market_id(),
             market.header.market_bump,
         )
     };
@@ -133,7 +144,12 @@ pub unsafe fn withdraw_non_zero_from_market<'t, 'a>(
             authority: market_account.account(), // READ
             amount,
         }
-        .invoke_signed(&[market_signer!(base_mint, quote_mint, market_bump)])
+        .invoke_signed(&[market_signer!(
+            base_mint,
+            quote_mint,
+            market_id,
+            market_bump
+        )])
     } else {
         // Safety: Scoped immutable borrow of mint account data to get the mint decimals.
         let decimals = unsafe { mint.get_mint_decimals() }?;
@@ -147,6 +163,117 @@ pub unsafe fn withdraw_non_zero_from_market<'t, 'a>(
             decimals,
             token_program: &pinocchio_token_2022::ID,
         }
-        .invoke_signed(&[market_signer!(base_mint, quote_mint, market_bump)])
+        .invoke_signed(&[market_signer!(
+            base_mint,
+            quote_mint,
+            market_id,
+            market_bump
+        )])
     }
 }
+
+/// Closes one of the market's empty token accounts, sending its rent lamports to `destination`.
+///
+/// The token program rejects the close if the token account still has a non-zero balance.
+///
+/// # Safety
+///
+/// Caller guarantees:
+/// - WRITE accounts are not currently borrowed in *any* capacity.
+/// - READ accounts are not currently mutably borrowed.
+///
+/// ### Accounts
+///   0. `[WRITE]` Market token account (closed)
+///   1. `[WRITE]` Destination account
+///   2. `[READ]`  Market account (authority)
+///   3. `[READ]`  Mint account
+pub unsafe fn close_market_token_account<'t, 'a>(
+    market_ata: &'t TokenAccountView<'a>,
+    destination: &'a AccountView,
+    market_account: &'t MarketAccountView<'a>,
+    mint: &'t MintAccountView<'a>,
+) -> ProgramResult {
+    let (base_mint, quote_mint, market_id, market_bump) = {
+        // Safety: Scoped immutable borrow of the market account.
+        let market = unsafe { market_account.load_unchecked() };
+        (
+            market.header.base_mint,
+            market.header.quote_mint,
+            market.header.market_id(),
+            market.header.market_bump,
+        )
+    };
+
+    if is_owned_by_spl_token(mint.account) {
+        pinocchio_token::instructions::CloseAccount {
+            account: market_ata.account,         // WRITE
+            destination,                         // WRITE
+            authority: market_account.account(), // READ
+        }
+        .invoke_signed(&[market_signer!(
+            base_mint,
+            quote_mint,
+            market_id,
+            market_bump
+        )])
+    } else {
+        pinocchio_token_2022::instructions::CloseAccount {
+            account: market_ata.account,         // WRITE
+            destination,                         // WRITE
+            authority: market_account.account(), // READ
+            token_program: &pinocchio_token_2022::ID,
+        }
+        .invoke_signed(&[market_signer!(
+            base_mint,
+            quote_mint,
+            market_id,
+            market_bump
+        )])
+    }
+}
+
+/// The `spl_token_2022` instruction data for `TransferFeeInstruction::HarvestWithheldTokensToMint`:
+/// the `TransferFeeExtension` instruction tag followed by the transfer fee instruction tag.
+const HARVEST_WITHHELD_TOKENS_TO_MINT_DATA: [u8; 2] = [26, 4];
+
+/// Moves the transfer fees withheld in one of the market's token accounts to the mint, since the
+/// token program won't close a token account with withheld fees. Harvesting is permissionless, so
+/// the market doesn't sign.
+///
+/// This is a no-op for `spl_token` mints and `spl_token_2022` mints without a transfer fee.
+///
+/// # Safety
+///
+/// Caller guarantees:
+/// - WRITE accounts are not currently borrowed in *any* capacity.
+///
+/// ### Accounts
+///   0. `[WRITE]` Market token account (source)
+///   1. `[WRITE]` Mint account (destination)
+pub unsafe fn harvest_withheld_market_fees<'t, 'a>(
+    market_ata: &'t TokenAccountView<'a>,
+    mint: &'t MintAccountView<'a>,
+) -> ProgramResult {
+    if is_owned_by_spl_token(mint.account) {
+        return Ok(());
+    }
+
+    // Safety: Scoped immutable borrow of the mint account data to check for a transfer fee.
+    let has_transfer_fee =
+        get_transfer_fee_config(unsafe { mint.account.borrow_unchecked() })?.is_some();
+    if !has_transfer_fee {
+        return Ok(());
+    }
+
+    invoke(
+        &InstructionView {
+            program_id: &pinocchio_token_2022::ID,
+            data: &HARVEST_WITHHELD_TOKENS_TO_MINT_DATA,
+            accounts: &[
+                InstructionAccount::writable(mint.account.address()),
+                InstructionAccount::writable(market_ata.account.address()),
+            ],
+        },
+        &[mint.account, market_ata.account],
+    )
+}
@@ -1,3 +1,4 @@
 //! Utilities for SPL token operations.
 
 pub mod market_transfers;
+pub mod token_2022_extensions;
@@ -0,0 +1,353 @@
+//! Parsing for the `spl_token_2022` mint extensions that affect how a market settles transfers.
+//!
+//! Extensions are stored as type-length-value entries after the base mint data, which is padded to
+//! the length of a token account and followed by a single account type byte.
+
+use dropset_interface::{
+    error::DropsetError,
+    state::market_header::BPS_DENOMINATOR,
+};
+
+/// The length of the base mint data, without any extensions.
+const MINT_LEN: usize = 82;
+
+/// The offset of the account type byte, which is placed after the base mint data padded to the
+/// length of a token account.
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+
+/// The offset of the first extension's type-length-value entry.
+const EXTENSIONS_OFFSET: usize = ACCOUNT_TYPE_OFFSET + 1;
+
+/// The length of an extension's type and length prefix.
+const TLV_HEADER_LEN: usize = 4;
+
+/// Extension type discriminants as defined by `spl_token_2022::extension::ExtensionType`. An
+/// uninitialized extension type marks the end of the extensions.
+const UNINITIALIZED: u16 = 0;
+const TRANSFER_FEE_CONFIG: u16 = 1;
+const CONFIDENTIAL_TRANSFER_MINT: u16 = 4;
+const DEFAULT_ACCOUNT_STATE: u16 = 6;
+const NON_TRANSFERABLE: u16 = 9;
+const PERMANENT_DELEGATE: u16 = 12;
+const TRANSFER_HOOK: u16 = 14;
+const CONFIDENTIAL_TRANSFER_FEE_CONFIG: u16 = 16;
+const CONFIDENTIAL_MINT_BURN: u16 = 24;
+const PAUSABLE: u16 = 26;
+
+/// Mint extensions that either make transfers into and out of the market unreliable or let a third
+/// party move or freeze the market's funds.
+///
+/// `DefaultAccountState` is only unsupported when new token accounts default to frozen, since the
+/// market's token accounts would be unusable; see [`check_supported_mint_extensions`].
+const UNSUPPORTED_EXTENSIONS: [u16; 7] = [
+    CONFIDENTIAL_TRANSFER_MINT,
+    NON_TRANSFERABLE,
+    PERMANENT_DELEGATE,
+    TRANSFER_HOOK,
+    CONFIDENTIAL_TRANSFER_FEE_CONFIG,
+    CONFIDENTIAL_MINT_BURN,
+    PAUSABLE,
+];
+
+/// The offset of the older transfer fee in the `TransferFeeConfig` extension data, after the
+/// transfer fee config authority, the withdraw withheld authority and the withheld amount.
+const OLDER_TRANSFER_FEE_OFFSET: usize = 32 + 32 + 8;
+
+/// The length of a single `TransferFee`: the epoch, the maximum fee and the fee in basis points.
+const TRANSFER_FEE_LEN: usize = 8 + 8 + 2;
+
+/// The `spl_token_2022::state::AccountState::Frozen` discriminant, stored as the single byte of the
+/// `DefaultAccountState` extension data.
+const ACCOUNT_STATE_FROZEN: u8 = 2;
+
+/// The length of the `TransferFeeConfig` extension data.
+const TRANSFER_FEE_CONFIG_LEN: usize = OLDER_TRANSFER_FEE_OFFSET + 2 * TRANSFER_FEE_LEN;
+
+/// A mint's transfer fee for a single epoch range, as stored in its `TransferFeeConfig` extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferFee {
+    /// The first epoch the fee applies to.
+    pub epoch: u64,
+    /// The maximum fee charged on a single transfer, in token atoms.
+    pub maximum_fee: u64,
+    /// The fee charged on each transfer in basis points.
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    fn from_bytes(bytes: &[u8; TRANSFER_FEE_LEN]) -> Self {
+        let (epoch, rest) = bytes.split_at(8);
+        let (maximum_fee, basis_points) = rest.split_at(8);
+        Self {
+            epoch: u64::from_le_bytes(epoch.try_into().unwrap()),
+            maximum_fee: u64::from_le_bytes(maximum_fee.try_into().unwrap()),
+            basis_points: u16::from_le_bytes(basis_points.try_into().unwrap()),
+        }
+    }
+
+    /// Calculates the fee withheld from a transfer of `amount`, rounded up and capped at the
+    /// maximum fee exactly like `spl_token_2022` does.
+    #[inline(always)]
+    pub fn calculate_fee(&self, amount: u64) -> u64 {
+        if self.basis_points == 0 || amount == 0 {
+            return 0;
+        }
+        let numerator = (amount as u128) * (self.basis_points as u128);
+        let fee = numerator.div_ceil(BPS_DENOMINATOR as u128);
+        // The fee is at most `amount` since the basis points never exceed the denominator.
+        (fee as u64).min(self.maximum_fee)
+    }
+
+    /// Calculates the smallest amount to transfer so that the destination receives at least
+    /// `post_fee_amount` after the fee is withheld.
+    #[inline(always)]
+    pub fn calculate_pre_fee_amount(&self, post_fee_amount: u64) -> Result<u64, DropsetError> {
+        let basis_points = self.basis_points as u128;
+        let denominator = BPS_DENOMINATOR as u128;
+        match (basis_points, post_fee_amount) {
+            (0, _) | (_, 0) => Ok(post_fee_amount),
+            (bps, _) if bps >= denominator => post_fee_amount
+                .checked_add(self.maximum_fee)
+                .ok_or(DropsetError::ArithmeticOverflow),
+            (bps, _) => {
+                let numerator = (post_fee_amount as u128) * denominator;
+                let raw_pre_fee_amount = numerator.div_ceil(denominator - bps);
+                if raw_pre_fee_amount - (post_fee_amount as u128) >= self.maximum_fee as u128 {
+                    post_fee_amount
+                        .checked_add(self.maximum_fee)
+                        .ok_or(DropsetError::ArithmeticOverflow)
+                } else {
+                    u64::try_from(raw_pre_fee_amount).or(Err(DropsetError::ArithmeticOverflow))
+                }
+            }
+        }
+    }
+}
+
+/// Iterates over the type and value of each extension in the mint account data.
+fn iter_extensions(mint_data: &[u8]) -> impl Iterator<Item = Result<(u16, &[u8]), DropsetError>> {
+    let mut offset = EXTENSIONS_OFFSET;
+    core::iter::from_fn(move || {
+        let header = mint_data.get(offset..offset + TLV_HEADER_LEN)?;
+        let extension_type = u16::from_le_bytes([header[0], header[1]]);
+        if extension_type == UNINITIALIZED {
+            return None;
+        }
+        let length = u16::from_le_bytes([header[2], header[3]]) as usize;
+        let value_start = offset + TLV_HEADER_LEN;
+        offset = value_start + length;
+        Some(
+            mint_data
+                .get(value_start..offset)
+                .map(|value| (extension_type, value))
+                .ok_or(DropsetError::InvalidMintAccount),
+        )
+    })
+}
+
+/// Checks that a `spl_token_2022` mint doesn't have any extensions the market can't support, such
+/// as transfer hooks, non-transferable tokens, a permanent delegate or token accounts that default
+/// to frozen.
+pub fn check_supported_mint_extensions(mint_data: &[u8]) -> Result<(), DropsetError> {
+    if mint_data.len() <= MINT_LEN {
+        return Ok(());
+    }
+    for extension in iter_extensions(mint_data) {
+        let (extension_type, value) = extension?;
+        if UNSUPPORTED_EXTENSIONS.contains(&extension_type) {
+            return Err(DropsetError::UnsupportedMintExtension);
+        }
+        if extension_type == DEFAULT_ACCOUNT_STATE {
+            let state = value.first().ok_or(DropsetError::InvalidMintAccount)?;
+            if *state == ACCOUNT_STATE_FROZEN {
+                return Err(DropsetError::UnsupportedMintExtension);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The older and newer transfer fees stored in a mint's `TransferFeeConfig` extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferFeeConfig {
+    pub older: TransferFee,
+    pub newer: TransferFee,
+}
+
+impl TransferFeeConfig {
+    /// Returns the transfer fee that applies to transfers in `epoch`.
+    #[inline(always)]
+    pub fn get_epoch_fee(&self, epoch: u64) -> TransferFee {
+        if epoch >= self.newer.epoch {
+            self.newer
+        } else {
+            self.older
+        }
+    }
+}
+
+/// Returns the `spl_token_2022` mint's transfer fee config, if it has the `TransferFeeConfig`
+/// extension.
+pub fn get_transfer_fee_config(
+    mint_data: &[u8],
+) -> Result<Option<TransferFeeConfig>, DropsetError> {
+    if mint_data.len() <= MINT_LEN {
+        return Ok(None);
+    }
+    for extension in iter_extensions(mint_data) {
+        let (extension_type, value) = extension?;
+        if extension_type != TRANSFER_FEE_CONFIG {
+            continue;
+        }
+        let fees: &[u8; 2 * TRANSFER_FEE_LEN] = value
+            .get(OLDER_TRANSFER_FEE_OFFSET..TRANSFER_FEE_CONFIG_LEN)
+            .and_then(|fees| fees.try_into().ok())
+            .ok_or(DropsetError::InvalidMintAccount)?;
+        let (older, newer) = fees.split_at(TRANSFER_FEE_LEN);
+        return Ok(Some(TransferFeeConfig {
+            older: TransferFee::from_bytes(older.try_into().unwrap()),
+            newer: TransferFee::from_bytes(newer.try_into().unwrap()),
+        }));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec::Vec;
+
+    use super::*;
+
+    /// Builds mint account data with the passed extensions appended after the account type byte.
+    fn mint_data_with_extensions(extensions: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut data = std::vec![0u8; EXTENSIONS_OFFSET];
+        // The mint account type.
+        data[ACCOUNT_TYPE_OFFSET] = 1;
+        for (extension_type, value) in extensions {
+            data.extend_from_slice(&extension_type.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    fn transfer_fee_config_data(older: TransferFee, newer: TransferFee) -> Vec<u8> {
+        let mut value = std::vec![0u8; OLDER_TRANSFER_FEE_OFFSET];
+        for fee in [older, newer] {
+            value.extend_from_slice(&fee.epoch.to_le_bytes());
+            value.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+            value.extend_from_slice(&fee.basis_points.to_le_bytes());
+        }
+        value
+    }
+
+    #[test]
+    fn supported_mint_extensions() {
+        assert!(check_supported_mint_extensions(&[0; MINT_LEN]).is_ok());
+
+        let metadata_pointer = [0u8; 64];
+        let fee = TransferFee {
+            epoch: 0,
+            maximum_fee: 0,
+            basis_points: 0,
+        };
+        let fee_config = transfer_fee_config_data(fee, fee);
+        let data = mint_data_with_extensions(&[
+            (TRANSFER_FEE_CONFIG, &fee_config),
+            (18, &metadata_pointer),
+        ]);
+        assert!(check_supported_mint_extensions(&data).is_ok());
+
+        for extension_type in UNSUPPORTED_EXTENSIONS {
+            let data = mint_data_with_extensions(&[
+                (TRANSFER_FEE_CONFIG, &fee_config),
+                (extension_type, &[0u8; 32]),
+            ]);
+            assert_eq!(
+                check_supported_mint_extensions(&data),
+                Err(DropsetError::UnsupportedMintExtension)
+            );
+        }
+
+        // Token accounts that default to initialized are fine, but defaulting to frozen isn't.
+        let initialized = mint_data_with_extensions(&[(DEFAULT_ACCOUNT_STATE, &[1])]);
+        assert!(check_supported_mint_extensions(&initialized).is_ok());
+        let frozen = mint_data_with_extensions(&[(DEFAULT_ACCOUNT_STATE, &[ACCOUNT_STATE_FROZEN])]);
+        assert_eq!(
+            check_supported_mint_extensions(&frozen),
+            Err(DropsetError::UnsupportedMintExtension)
+        );
+
+        // An extension whose length runs past the end of the account data is invalid.
+        let mut data = mint_data_with_extensions(&[(18, &metadata_pointer)]);
+        data.truncate(data.len() - 1);
+        assert_eq!(
+            check_supported_mint_extensions(&data),
+            Err(DropsetError::InvalidMintAccount)
+        );
+    }
+
+    #[test]
+    fn transfer_fee_config() {
+        let older = TransferFee {
+            epoch: 0,
+            maximum_fee: 1_000,
+            basis_points: 50,
+        };
+        let newer = TransferFee {
+            epoch: 10,
+            maximum_fee: 5_000,
+            basis_points: 100,
+        };
+        let data = mint_data_with_extensions(&[
+            (18, &[0u8; 64]),
+            (TRANSFER_FEE_CONFIG, &transfer_fee_config_data(older, newer)),
+        ]);
+
+        let config = get_transfer_fee_config(&data)
+            .expect("Should parse the extensions")
+            .expect("Should have a transfer fee config");
+        assert_eq!(config, TransferFeeConfig { older, newer });
+        assert_eq!(config.get_epoch_fee(9), older);
+        assert_eq!(config.get_epoch_fee(10), newer);
+        assert_eq!(get_transfer_fee_config(&[0; MINT_LEN]), Ok(None));
+        assert_eq!(
+            get_transfer_fee_config(&mint_data_with_extensions(&[])),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn transfer_fee_calculations() {
+        let fee = TransferFee {
+            epoch: 0,
+            maximum_fee: 5_000,
+            basis_points: 100,
+        };
+        assert_eq!(fee.calculate_fee(0), 0);
+        assert_eq!(fee.calculate_fee(1), 1);
+        assert_eq!(fee.calculate_fee(10_000), 100);
+        assert_eq!(fee.calculate_fee(10_001), 101);
+        assert_eq!(fee.calculate_fee(1_000_000), 5_000);
+
+        // The pre-fee amount always nets at least the post-fee amount after the fee is withheld.
+        for post_fee_amount in [0, 1, 99, 9_900, 9_901, 495_000, 10_000_000] {
+            let pre_fee_amount = fee
+                .calculate_pre_fee_amount(post_fee_amount)
+                .expect("Should calculate the pre-fee amount");
+            let received = pre_fee_amount - fee.calculate_fee(pre_fee_amount);
+            assert!(received >= post_fee_amount);
+            assert!(received - post_fee_amount <= 1);
+        }
+        assert_eq!(fee.calculate_pre_fee_amount(9_900), Ok(10_000));
+        assert_eq!(fee.calculate_pre_fee_amount(10_000_000), Ok(10_005_000));
+
+        let full_fee = TransferFee {
+            epoch: 0,
+            maximum_fee: 7,
+            basis_points: BPS_DENOMINATOR,
+        };
+        assert_eq!(full_fee.calculate_pre_fee_amount(100), Ok(107));
+    }
+}
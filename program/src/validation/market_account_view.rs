@@ -20,11 +20,18 @@ use dropset_interface::{
 };
 use pinocchio::{
     account::AccountView,
+    error::ProgramError,
     hint::unlikely,
     ProgramResult,
 };
 
-use crate::shared::account_resize::fund_then_resize_unchecked;
+use crate::shared::{
+    account_resize::{
+        fund_then_resize_unchecked,
+        resize_then_refund_unchecked,
+    },
+    market_operations::compact_market_sectors,
+};
 
 /// A validated wrapper around a raw market [`AccountView`], providing safe access
 /// to the market header and sector data after verifying ownership and layout.
@@ -162,4 +169,42 @@ impl<'a> MarketAccountView<'a> {
 
         Ok(())
     }
+
+    /// Relocates every live sector to the front of the market account, then truncates the market
+    /// account data so that only `num_free_sectors` free sectors remain and refunds the excess rent
+    /// to `destination`.
+    ///
+    /// Returns the number of sectors removed and the number of lamports refunded.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees:
+    /// - WRITE accounts are not currently borrowed in *any* capacity.
+    ///
+    /// ### Accounts
+    ///   0. `[WRITE]` Market account
+    ///   1. `[WRITE]` Destination
+    #[inline(always)]
+    pub unsafe fn compact(
+        &mut self,
+        destination: &AccountView,
+        num_free_sectors: u32,
+    ) -> Result<(u32, u64), ProgramError> {
+        let (num_sectors, num_sectors_removed) = {
+            // Safety: Scoped mutable borrow of the market account data to relocate its sectors.
+            let mut market = unsafe { self.load_unchecked_mut() };
+            let capacity = market.get_capacity();
+            // Safety: The market account's sectors, links and free stack are valid.
+            let num_sectors = unsafe { compact_market_sectors(&mut market, num_free_sectors) }?;
+            (num_sectors, capacity - num_sectors)
+        };
+
+        let new_size = MarketHeader::LEN + (num_sectors as usize) * SECTOR_SIZE;
+
+        // Safety: Scoped writes to the market account and destination to shrink the market account.
+        let lamports_refunded =
+            unsafe { resize_then_refund_unchecked(self.account, destination, new_size) }?;
+
+        Ok((num_sectors_removed, lamports_refunded))
+    }
 }
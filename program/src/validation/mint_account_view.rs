@@ -3,17 +3,28 @@
 use dropset_interface::{
     error::DropsetError,
     state::market::MarketRef,
+    utils::is_owned_by_spl_token,
 };
 use pinocchio::{
     account::AccountView,
     error::ProgramError,
+    sysvars::{
+        clock::Clock,
+        Sysvar,
+    },
 };
 use pinocchio_token_interface::state::{
     load_unchecked as pinocchio_load_unchecked,
     mint::Mint,
+    Transmutable,
 };
 use solana_address::address_eq;
 
+use crate::shared::token_utils::token_2022_extensions::{
+    get_transfer_fee_config,
+    TransferFee,
+};
+
 /// A validated wrapper around a raw mint [`AccountView`], exposing verified metadata such as
 /// supply, decimals, and authorities.
 #[derive(Clone)]
@@ -85,7 +96,11 @@ impl<'a> MintAccountView<'a> {
     ///   0. `[READ]` Mint account
     #[inline(always)]
     pub unsafe fn get_mint_decimals(&self) -> Result<u8, ProgramError> {
-        let data = unsafe { self.account.borrow_unchecked() };
+        // `spl_token_2022` mints with extensions store them after the base mint data, so only the
+        // base mint data is loaded.
+        let data = unsafe { self.account.borrow_unchecked() }
+            .get(..Mint::LEN)
+            .ok_or(ProgramError::InvalidAccountData)?;
         // Safety: `self` contains a verifiably initialized base or quote mint, since the market
         // header stores their addresses and they are checked against the values in the header any
         // time `self` is constructed.
@@ -93,4 +108,67 @@ impl<'a> MintAccountView<'a> {
             .map_err(|_| ProgramError::InvalidAccountData)?
             .decimals)
     }
+
+    /// Borrows the mint account's data to get the transfer fee for the current epoch. This is
+    /// `None` for `spl_token` mints and `spl_token_2022` mints without a transfer fee.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees:
+    /// - WRITE accounts are not currently borrowed in *any* capacity.
+    /// - READ accounts are not currently mutably borrowed.
+    ///
+    /// ### Accounts
+    ///   0. `[READ]` Mint account
+    #[inline(always)]
+    pub unsafe fn get_transfer_fee(&self) -> Result<Option<TransferFee>, ProgramError> {
+        if is_owned_by_spl_token(self.account) {
+            return Ok(None);
+        }
+        let data = unsafe { self.account.borrow_unchecked() };
+        match get_transfer_fee_config(data)? {
+            Some(config) => Ok(Some(config.get_epoch_fee(Clock::get()?.epoch))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the amount the destination receives when `amount` is transferred, after the mint's
+    /// transfer fee is withheld.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees:
+    /// - WRITE accounts are not currently borrowed in *any* capacity.
+    /// - READ accounts are not currently mutably borrowed.
+    ///
+    /// ### Accounts
+    ///   0. `[READ]` Mint account
+    #[inline(always)]
+    pub unsafe fn post_fee_amount(&self, amount: u64) -> Result<u64, ProgramError> {
+        // Safety: Scoped immutable borrow of the mint account data.
+        Ok(match unsafe { self.get_transfer_fee() }? {
+            Some(fee) => amount - fee.calculate_fee(amount),
+            None => amount,
+        })
+    }
+
+    /// Returns the amount that must be transferred for the destination to receive at least
+    /// `amount` after the mint's transfer fee is withheld.
+    ///
+    /// # Safety
+    ///
+    /// Caller guarantees:
+    /// - WRITE accounts are not currently borrowed in *any* capacity.
+    /// - READ accounts are not currently mutably borrowed.
+    ///
+    /// ### Accounts
+    ///   0. `[READ]` Mint account
+    #[inline(always)]
+    pub unsafe fn pre_fee_amount(&self, amount: u64) -> Result<u64, ProgramError> {
+        // Safety: Scoped immutable borrow of the mint account data.
+        Ok(match unsafe { self.get_transfer_fee() }? {
+            Some(fee) => fee.calculate_pre_fee_amount(amount)?,
+            None => amount,
+        })
+    }
 }
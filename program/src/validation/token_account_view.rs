@@ -9,6 +9,7 @@ use pinocchio_token_interface::state::{
     account::Account,
     load as pinocchio_load,
     load_unchecked as pinocchio_load_unchecked,
+    Transmutable,
 };
 use solana_address::{
     address_eq,
@@ -43,7 +44,11 @@ impl<'a> TokenAccountView<'a> {
 
         // Safety: Immutable borrow of token account data to check the expected mint/owner, dropped
         // before the function returns.
-        let account_data = unsafe { token_account.borrow_unchecked() };
+        // `spl_token_2022` token accounts with extensions store them after the base account data,
+        // so only the base account data is loaded.
+        let account_data = unsafe { token_account.borrow_unchecked() }
+            .get(..Account::LEN)
+            .ok_or(ProgramError::InvalidAccountData)?;
 
         // Note the load below also checks that the account has been initialized.
         // Safety: Mint account owner has been verified, so the account data is valid.
@@ -72,7 +77,9 @@ impl<'a> TokenAccountView<'a> {
     ///   0. `[READ]` Token account
     #[inline(always)]
     pub unsafe fn get_balance(&self) -> Result<u64, ProgramError> {
-        let data = unsafe { self.account.borrow_unchecked() };
+        let data = unsafe { self.account.borrow_unchecked() }
+            .get(..Account::LEN)
+            .ok_or(ProgramError::InvalidAccountData)?;
 
         // Safety: Account is verified as initialized and owned by one of the spl token programs
         // upon construction of Self.
@@ -1,8 +1,11 @@
 //! Defines the Display-able types for some event instruction data.
 
 use dropset_interface::events::{
+    DepositForEventInstructionData,
     HeaderEventInstructionData,
     RegisterMarketEventInstructionData,
+    SetSeatDelegateEventInstructionData,
+    UpdateMarketConfigEventInstructionData,
 };
 use solana_address::Address;
 
@@ -28,12 +31,73 @@ impl From<HeaderEventInstructionData> for DisplayHeaderData {
 #[derive(Debug)]
 pub struct DisplayRegisterMarketData {
     pub market: Address,
+    pub market_id: u16,
 }
 
 impl From<RegisterMarketEventInstructionData> for DisplayRegisterMarketData {
     fn from(value: RegisterMarketEventInstructionData) -> Self {
         Self {
             market: value.market,
+            market_id: value.market_id,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DisplaySetSeatDelegateData {
+    pub seat_sector_index: u32,
+    pub delegate: Address,
+}
+
+impl From<SetSeatDelegateEventInstructionData> for DisplaySetSeatDelegateData {
+    fn from(value: SetSeatDelegateEventInstructionData) -> Self {
+        Self {
+            seat_sector_index: value.seat_sector_index,
+            delegate: value.delegate,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DisplayDepositForData {
+    pub amount: u64,
+    pub is_base: bool,
+    pub seat_sector_index: u32,
+    pub funder: Address,
+    pub beneficiary: Address,
+}
+
+impl From<DepositForEventInstructionData> for DisplayDepositForData {
+    fn from(value: DepositForEventInstructionData) -> Self {
+        Self {
+            amount: value.amount,
+            is_base: value.is_base,
+            seat_sector_index: value.seat_sector_index,
+            funder: value.funder,
+            beneficiary: value.beneficiary,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DisplayUpdateMarketConfigData {
+    pub min_order_base_atoms: u64,
+    pub price_mantissa_tick: u32,
+    pub max_orders_per_user: u8,
+    pub fee_recipient: Address,
+    pub taker_fee_bps: u16,
+    pub maker_rebate_bps: u16,
+}
+
+impl From<UpdateMarketConfigEventInstructionData> for DisplayUpdateMarketConfigData {
+    fn from(value: UpdateMarketConfigEventInstructionData) -> Self {
+        Self {
+            min_order_base_atoms: value.min_order_base_atoms,
+            price_mantissa_tick: value.price_mantissa_tick,
+            max_orders_per_user: value.max_orders_per_user,
+            fee_recipient: value.fee_recipient,
+            taker_fee_bps: value.taker_fee_bps,
+            maker_rebate_bps: value.maker_rebate_bps,
         }
     }
 }
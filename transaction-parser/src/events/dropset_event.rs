@@ -2,15 +2,27 @@
 //! events or contiguous instruction data.
 
 use dropset_interface::events::{
+    AmendOrderEventInstructionData,
+    BatchReplaceEventInstructionData,
     CancelOrderEventInstructionData,
+    CloseMarketEventInstructionData,
     CloseSeatEventInstructionData,
+    CollectFeesEventInstructionData,
+    CompactMarketEventInstructionData,
     DepositEventInstructionData,
+    DepositForEventInstructionData,
     DropsetEventTag,
     ExpandMarketEventInstructionData,
+    ExpireOrderEventInstructionData,
+    FillEventInstructionData,
     HeaderEventInstructionData,
     MarketOrderEventInstructionData,
     PostOrderEventInstructionData,
     RegisterMarketEventInstructionData,
+    SelfTradeEventInstructionData,
+    SetMarketStatusEventInstructionData,
+    SetSeatDelegateEventInstructionData,
+    UpdateMarketConfigEventInstructionData,
     WithdrawEventInstructionData,
 };
 use instruction_macros_traits::Tagged;
@@ -28,6 +40,18 @@ pub enum DropsetEvent {
     CancelOrder(CancelOrderEventInstructionData),
     MarketOrder(MarketOrderEventInstructionData),
     ExpandMarket(ExpandMarketEventInstructionData),
+    CollectFees(CollectFeesEventInstructionData),
+    Fill(FillEventInstructionData),
+    BatchReplace(BatchReplaceEventInstructionData),
+    UpdateMarketConfig(display_types::DisplayUpdateMarketConfigData),
+    SetMarketStatus(SetMarketStatusEventInstructionData),
+    CloseMarket(CloseMarketEventInstructionData),
+    ExpireOrder(ExpireOrderEventInstructionData),
+    SelfTrade(SelfTradeEventInstructionData),
+    AmendOrder(AmendOrderEventInstructionData),
+    CompactMarket(CompactMarketEventInstructionData),
+    SetSeatDelegate(display_types::DisplaySetSeatDelegateData),
+    DepositFor(display_types::DisplayDepositForData),
 }
 
 impl DropsetEvent {
@@ -42,6 +66,18 @@ impl DropsetEvent {
             Self::CancelOrder(_) => CancelOrderEventInstructionData::LEN_WITH_TAG,
             Self::MarketOrder(_) => MarketOrderEventInstructionData::LEN_WITH_TAG,
             Self::ExpandMarket(_) => ExpandMarketEventInstructionData::LEN_WITH_TAG,
+            Self::CollectFees(_) => CollectFeesEventInstructionData::LEN_WITH_TAG,
+            Self::Fill(_) => FillEventInstructionData::LEN_WITH_TAG,
+            Self::BatchReplace(_) => BatchReplaceEventInstructionData::LEN_WITH_TAG,
+            Self::UpdateMarketConfig(_) => UpdateMarketConfigEventInstructionData::LEN_WITH_TAG,
+            Self::SetMarketStatus(_) => SetMarketStatusEventInstructionData::LEN_WITH_TAG,
+            Self::CloseMarket(_) => CloseMarketEventInstructionData::LEN_WITH_TAG,
+            Self::ExpireOrder(_) => ExpireOrderEventInstructionData::LEN_WITH_TAG,
+            Self::SelfTrade(_) => SelfTradeEventInstructionData::LEN_WITH_TAG,
+            Self::AmendOrder(_) => AmendOrderEventInstructionData::LEN_WITH_TAG,
+            Self::CompactMarket(_) => CompactMarketEventInstructionData::LEN_WITH_TAG,
+            Self::SetSeatDelegate(_) => SetSeatDelegateEventInstructionData::LEN_WITH_TAG,
+            Self::DepositFor(_) => DepositForEventInstructionData::LEN_WITH_TAG,
         }
     }
 }
@@ -128,6 +164,48 @@ impl DropsetEvent {
             DropsetEventTag::ExpandMarketEvent => Ok(DropsetEvent::ExpandMarket(
                 ExpandMarketEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
             )),
+            DropsetEventTag::CollectFeesEvent => Ok(DropsetEvent::CollectFees(
+                CollectFeesEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::FillEvent => Ok(DropsetEvent::Fill(
+                FillEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::BatchReplaceEvent => Ok(DropsetEvent::BatchReplace(
+                BatchReplaceEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::UpdateMarketConfigEvent => Ok(DropsetEvent::UpdateMarketConfig(
+                UpdateMarketConfigEventInstructionData::unpack_untagged(data)
+                    .map_err(|_| err())?
+                    .into(),
+            )),
+            DropsetEventTag::SetMarketStatusEvent => Ok(DropsetEvent::SetMarketStatus(
+                SetMarketStatusEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::CloseMarketEvent => Ok(DropsetEvent::CloseMarket(
+                CloseMarketEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::ExpireOrderEvent => Ok(DropsetEvent::ExpireOrder(
+                ExpireOrderEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::SelfTradeEvent => Ok(DropsetEvent::SelfTrade(
+                SelfTradeEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::AmendOrderEvent => Ok(DropsetEvent::AmendOrder(
+                AmendOrderEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::CompactMarketEvent => Ok(DropsetEvent::CompactMarket(
+                CompactMarketEventInstructionData::unpack_untagged(data).map_err(|_| err())?,
+            )),
+            DropsetEventTag::SetSeatDelegateEvent => Ok(DropsetEvent::SetSeatDelegate(
+                SetSeatDelegateEventInstructionData::unpack_untagged(data)
+                    .map_err(|_| err())?
+                    .into(),
+            )),
+            DropsetEventTag::DepositForEvent => Ok(DropsetEvent::DepositFor(
+                DepositForEventInstructionData::unpack_untagged(data)
+                    .map_err(|_| err())?
+                    .into(),
+            )),
         }
     }
 }
@@ -3,17 +3,22 @@
 
 use std::collections::HashMap;
 
-use dropset_interface::state::{
-    market::MarketRef,
-    market_header::MarketHeader,
-    market_seat::MarketSeat,
-    order::Order,
-    sector::{
-        Sector,
-        SectorIndex,
+use dropset_interface::{
+    error::DropsetError,
+    instructions::OrderExpiry,
+    state::{
+        market::MarketRef,
+        market_header::MarketHeader,
+        market_seat::MarketSeat,
+        market_status::MarketStatus,
+        order::Order,
+        sector::{
+            Sector,
+            SectorIndex,
+        },
+        transmutable::Transmutable,
+        user_order_sectors::PriceToIndexEntry,
     },
-    transmutable::Transmutable,
-    user_order_sectors::UserOrderSectors,
 };
 use itertools::Itertools;
 use price::EncodedPrice;
@@ -36,8 +41,19 @@ pub struct MarketHeaderView {
     pub base_mint: Address,
     pub quote_mint: Address,
     pub market_bump: u8,
+    pub market_id: u16,
     pub nonce: u64,
-    pub _padding: [u8; 3],
+    pub fee_recipient: Address,
+    pub taker_fee_bps: u16,
+    pub maker_rebate_bps: u16,
+    pub base_fees_accrued: u64,
+    pub quote_fees_accrued: u64,
+    pub authority: Address,
+    pub min_order_base_atoms: u64,
+    pub price_mantissa_tick: u32,
+    pub max_orders_per_user: u8,
+    pub status: MarketStatus,
+    pub _padding: [u8; 7],
 }
 
 /// A view on a market account's data with the collection of type T sectors.
@@ -88,6 +104,7 @@ pub fn try_market_view_all_from_owner_and_data(
 /// - `account_data` is at least [`MarketHeader::LEN`] bytes (i.e., initialized enough to contain a
 ///   header).
 /// - `account_data` is properly aligned for a market with a header + some number of [`Sector`]s.
+/// - The market header's status is a valid [`MarketStatus`].
 ///
 /// On success, returns a [`MarketViewAll`] over `account_data` (header + sector bytes).
 pub fn try_market_view_all(account_data: &[u8]) -> anyhow::Result<MarketViewAll> {
@@ -107,7 +124,8 @@ pub fn try_market_view_all(account_data: &[u8]) -> anyhow::Result<MarketViewAll>
     // Safety: Length was just checked.
     let market = unsafe { MarketRef::from_bytes(account_data) };
 
-    Ok(market.into())
+    MarketViewAll::try_from(market)
+        .map_err(|e| anyhow::Error::msg(format!("Account has an invalid market header: {e}")))
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -118,7 +136,16 @@ pub struct MarketSeatView {
     pub user: Address,
     pub base_available: u64,
     pub quote_available: u64,
-    pub user_order_sectors: UserOrderSectors,
+    pub user_order_sectors: UserOrderSectorsView,
+    pub delegate: Option<Address>,
+}
+
+/// The mapped entries of a user seat's price -> order sectors mapping, including the entries in the
+/// seat's overflow sectors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserOrderSectorsView {
+    pub bids: Vec<PriceToIndexEntry>,
+    pub asks: Vec<PriceToIndexEntry>,
 }
 
 #[derive(Clone, Debug)]
@@ -130,12 +157,24 @@ pub struct OrderView {
     pub user_seat: SectorIndex,
     pub base_remaining: u64,
     pub quote_remaining: u64,
+    pub expiry: OrderExpiry,
+    pub client_order_id: u64,
 }
 
-impl From<(SectorIndex, &Sector)> for MarketSeatView {
-    fn from(index_and_seat: (SectorIndex, &Sector)) -> Self {
-        let (sector_index, sector) = index_and_seat;
+impl From<(SectorIndex, &Sector, &[u8])> for MarketSeatView {
+    /// Converts the seat's sector index, sector and the market's sectors region, which is needed to
+    /// read the seat's overflow order sectors.
+    fn from(index_seat_and_sectors: (SectorIndex, &Sector, &[u8])) -> Self {
+        let (sector_index, sector, sectors) = index_seat_and_sectors;
         let seat = sector.load_payload::<MarketSeat>();
+        let order_sectors = &seat.user_order_sectors;
+        // Safety: The seat's order sectors belong to the market with the passed sectors region.
+        let user_order_sectors = unsafe {
+            UserOrderSectorsView {
+                bids: order_sectors.bids.iter(sectors).copied().collect(),
+                asks: order_sectors.asks.iter(sectors).copied().collect(),
+            }
+        };
         Self {
             prev_index: sector.prev(),
             index: sector_index,
@@ -143,7 +182,8 @@ impl From<(SectorIndex, &Sector)> for MarketSeatView {
             user: seat.user,
             base_available: seat.base_available(),
             quote_available: seat.quote_available(),
-            user_order_sectors: seat.user_order_sectors.clone(),
+            user_order_sectors,
+            delegate: seat.delegate().copied(),
         }
     }
 }
@@ -160,13 +200,18 @@ impl From<(SectorIndex, &Sector)> for OrderView {
             user_seat: order.user_seat(),
             base_remaining: order.base_remaining(),
             quote_remaining: order.quote_remaining(),
+            expiry: order.expiry(),
+            client_order_id: order.client_order_id(),
         }
     }
 }
 
-impl From<&MarketHeader> for MarketHeaderView {
-    fn from(header: &MarketHeader) -> Self {
-        Self {
+impl TryFrom<&MarketHeader> for MarketHeaderView {
+    type Error = DropsetError;
+
+    /// Fails if the header's market status byte isn't a valid [`MarketStatus`].
+    fn try_from(header: &MarketHeader) -> Result<Self, Self::Error> {
+        Ok(Self {
             discriminant: header.discriminant(),
             num_seats: header.num_seats(),
             num_bids: header.num_bids(),
@@ -182,15 +227,32 @@ impl From<&MarketHeader> for MarketHeaderView {
             base_mint: header.base_mint,
             quote_mint: header.quote_mint,
             market_bump: header.market_bump,
+            market_id: header.market_id(),
             nonce: header.num_events(),
-            _padding: [0; 3],
-        }
+            fee_recipient: header.fee_recipient,
+            taker_fee_bps: header.taker_fee_bps(),
+            maker_rebate_bps: header.maker_rebate_bps(),
+            base_fees_accrued: header.base_fees_accrued(),
+            quote_fees_accrued: header.quote_fees_accrued(),
+            authority: header.authority,
+            min_order_base_atoms: header.min_order_base_atoms(),
+            price_mantissa_tick: header.price_mantissa_tick(),
+            max_orders_per_user: header.max_orders_per_user(),
+            status: header.status()?,
+            _padding: [0; 7],
+        })
     }
 }
 
-impl From<MarketRef<'_>> for MarketViewAll {
-    fn from(market: MarketRef<'_>) -> Self {
-        let seats = market.iter_seats().map(MarketSeatView::from).collect_vec();
+impl TryFrom<MarketRef<'_>> for MarketViewAll {
+    type Error = DropsetError;
+
+    /// Fails if the market header can't be converted into a [`MarketHeaderView`].
+    fn try_from(market: MarketRef<'_>) -> Result<Self, Self::Error> {
+        let seats = market
+            .iter_seats()
+            .map(|(i, sector)| MarketSeatView::from((i, sector, market.sectors)))
+            .collect_vec();
         let bids = market.iter_bids().map(OrderView::from).collect_vec();
         let asks = market.iter_asks().map(OrderView::from).collect_vec();
 
@@ -235,12 +297,12 @@ impl From<MarketRef<'_>> for MarketViewAll {
                 .push(ask.clone());
         }
 
-        Self {
-            header: market.header.into(),
+        Ok(Self {
+            header: market.header.try_into()?,
             seats,
             bids,
             asks,
             users,
-        }
+        })
     }
 }